@@ -0,0 +1,162 @@
+// Represents the delta modulation channel (DMC) of the APU
+// Only the memory reader is emulated : it fetches the samples through the DMA unit
+// at the rate of the channel, and asserts the IRQ line at the end of a sample
+
+// ===== CONSTANTS =====
+
+// Number of CPU cycles between two output bits, for each rate index (NTSC)
+const RATES: [u16;16] = [428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54];
+
+// ===== DMC STRUCT =====
+
+pub struct DMC {
+    // $4010
+    pub irq_enabled: bool,
+    pub loop_flag: bool,
+    pub rate: u16,
+    // $4011
+    pub output_level: u8,
+    // $4012 / $4013
+    pub sample_address: u16,
+    pub sample_length: u16,
+
+    // Memory reader
+    pub current_address: u16,
+    pub bytes_remaining: u16,
+    pub o_sample_buffer: Option<u8>,
+
+    // Output unit
+    pub timer: u16,
+    pub shift_register: u8,
+    pub bits_remaining: u8,
+    pub silence: bool,
+
+    // Set when the sample buffer is empty and bytes remain, until the DMA unit fetches the byte
+    pub dma_request: bool,
+    pub irq_flag: bool
+}
+
+impl Default for DMC {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DMC {
+    pub fn new() -> Self {
+        DMC {
+            irq_enabled: false,
+            loop_flag: false,
+            rate: RATES[0],
+            output_level: 0,
+            sample_address: 0xC000,
+            sample_length: 1,
+
+            current_address: 0xC000,
+            bytes_remaining: 0,
+            o_sample_buffer: None,
+
+            timer: RATES[0],
+            shift_register: 0,
+            bits_remaining: 8,
+            silence: true,
+
+            dma_request: false,
+            irq_flag: false
+        }
+    }
+
+    // ===== REGISTERS =====
+
+    // Writes to $4010 - $4013
+    pub fn write_register(&mut self, address: u16, value: u8) {
+        match address {
+            0x4010 => {
+                self.irq_enabled = (value & 0x80) == 0x80;
+                self.loop_flag = (value & 0x40) == 0x40;
+                self.rate = RATES[(value & 0x0F) as usize];
+                if !self.irq_enabled {
+                    self.irq_flag = false;
+                }
+            }
+            0x4011 => self.output_level = value & 0x7F,
+            0x4012 => self.sample_address = 0xC000 + ((value as u16) << 6),
+            0x4013 => self.sample_length = ((value as u16) << 4) + 1,
+            _ => panic!("Wrong address given to DMC : {:#x}", address)
+        }
+    }
+
+    // Bit 4 of $4015 starts or stops the sample
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.irq_flag = false;
+        if !enabled {
+            self.bytes_remaining = 0;
+        }
+        else if self.bytes_remaining == 0 {
+            self.restart();
+        }
+        self.update_dma_request();
+    }
+
+    // Bits of $4015 driven by the DMC
+    pub fn read_status(&self) -> u8 {
+        ((self.irq_flag as u8) << 7) | (((self.bytes_remaining > 0) as u8) << 4)
+    }
+
+    // ===== CLOCK =====
+
+    // Called on every CPU cycle
+    pub fn clock(&mut self) {
+        self.timer -= 1;
+        if self.timer == 0 {
+            self.timer = self.rate;
+            self.clock_output();
+        }
+    }
+
+    fn clock_output(&mut self) {
+        if !self.silence {
+            self.shift_register >>= 1;
+        }
+        self.bits_remaining -= 1;
+        if self.bits_remaining == 0 {
+            // Start a new output cycle with the content of the sample buffer
+            self.bits_remaining = 8;
+            match self.o_sample_buffer.take() {
+                Some(sample) => {
+                    self.shift_register = sample;
+                    self.silence = false;
+                }
+                None => self.silence = true
+            }
+            self.update_dma_request();
+        }
+    }
+
+    // ===== MEMORY READER =====
+
+    fn restart(&mut self) {
+        self.current_address = self.sample_address;
+        self.bytes_remaining = self.sample_length;
+    }
+
+    fn update_dma_request(&mut self) {
+        self.dma_request = self.o_sample_buffer.is_none() && self.bytes_remaining > 0;
+    }
+
+    // Gives the byte fetched by the DMA unit at the current address
+    pub fn fill_sample_buffer(&mut self, value: u8) {
+        self.o_sample_buffer = Some(value);
+        self.current_address = if self.current_address == 0xFFFF { 0x8000 } else { self.current_address + 1 };
+        self.bytes_remaining -= 1;
+        if self.bytes_remaining == 0 {
+            if self.loop_flag {
+                self.restart();
+            }
+            else if self.irq_enabled {
+                self.irq_flag = true;
+            }
+        }
+        self.dma_request = false;
+    }
+}
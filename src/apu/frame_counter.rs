@@ -0,0 +1,153 @@
+// Represents the frame counter of the APU
+// Only the sequencer timing and the frame interrupt are emulated, the channels aren't clocked
+// In 4-step mode, the IRQ flag is set on the last 3 cycles of the sequence unless it is inhibited
+
+// ===== CONSTANTS =====
+
+// Number of CPU cycles of a sequence for each mode (NTSC)
+const FOUR_STEP_PERIOD: u16 = 29830;
+const FIVE_STEP_PERIOD: u16 = 37282;
+// First cycle of the sequence where the IRQ flag is set in 4-step mode
+const IRQ_CYCLE: u16 = 29828;
+
+// ===== FRAME COUNTER STRUCT =====
+
+pub struct FrameCounter {
+    // $4017
+    pub five_step: bool,
+    pub irq_inhibit: bool,
+
+    // CPU cycles since the beginning of the sequence
+    pub cycle: u16,
+    // A write to $4017 restarts the sequence 3 or 4 cycles later, depending on the APU cycle
+    pub o_reset_delay: Option<u8>,
+    pub odd_cycle: bool,
+
+    pub irq_flag: bool
+}
+
+impl Default for FrameCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameCounter {
+    // The power on state is the one of a write of $00 to $4017
+    pub fn new() -> Self {
+        FrameCounter {
+            five_step: false,
+            irq_inhibit: false,
+
+            cycle: 0,
+            o_reset_delay: None,
+            odd_cycle: false,
+
+            irq_flag: false
+        }
+    }
+
+    // Called when the reset button is pressed, the mode of the last write is kept
+    pub fn reset(&mut self) {
+        self.irq_flag = false;
+        self.cycle = 0;
+        self.o_reset_delay = None;
+    }
+
+    // ===== REGISTERS =====
+
+    // Writes to $4017
+    pub fn write_register(&mut self, value: u8) {
+        self.five_step = (value & 0x80) == 0x80;
+        self.irq_inhibit = (value & 0x40) == 0x40;
+        if self.irq_inhibit {
+            self.irq_flag = false;
+        }
+        self.o_reset_delay = Some(if self.odd_cycle { 4 } else { 3 });
+    }
+
+    // Bit of $4015 driven by the frame counter, the flag is cleared by the read
+    pub fn read_status(&self) -> u8 {
+        (self.irq_flag as u8) << 6
+    }
+
+    // ===== CLOCK =====
+
+    // Called on every CPU cycle
+    pub fn clock(&mut self) {
+        self.odd_cycle = !self.odd_cycle;
+        if let Some(delay) = self.o_reset_delay {
+            if delay > 1 {
+                self.o_reset_delay = Some(delay - 1);
+            }
+            else {
+                self.o_reset_delay = None;
+                self.cycle = 0;
+                return;
+            }
+        }
+
+        self.cycle += 1;
+        if !self.five_step && !self.irq_inhibit && self.cycle >= IRQ_CYCLE {
+            self.irq_flag = true;
+        }
+        let period: u16 = if self.five_step { FIVE_STEP_PERIOD } else { FOUR_STEP_PERIOD };
+        if self.cycle == period {
+            self.cycle = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Clocks the frame counter until the IRQ flag is set, returns the number of cycles or None after 2 sequences
+    fn cycles_to_irq(frame_counter: &mut FrameCounter) -> Option<u32> {
+        (1..=2*FIVE_STEP_PERIOD as u32).find(|_| {
+            frame_counter.clock();
+            frame_counter.irq_flag
+        })
+    }
+
+    #[test]
+    fn four_step_irq() {
+        // The sequence starts 3 cycles after a write on an even cycle, 4 after a write on an odd cycle
+        let mut frame_counter: FrameCounter = FrameCounter::new();
+        frame_counter.write_register(0x00);
+        assert_eq!(cycles_to_irq(&mut frame_counter), Some(3 + IRQ_CYCLE as u32));
+        assert_eq!(frame_counter.read_status(), 0x40);
+
+        let mut frame_counter: FrameCounter = FrameCounter::new();
+        frame_counter.clock();
+        frame_counter.write_register(0x00);
+        assert_eq!(cycles_to_irq(&mut frame_counter), Some(4 + IRQ_CYCLE as u32));
+    }
+
+    #[test]
+    fn no_irq() {
+        // 5-step mode
+        let mut frame_counter: FrameCounter = FrameCounter::new();
+        frame_counter.write_register(0x80);
+        assert_eq!(cycles_to_irq(&mut frame_counter), None);
+
+        // Inhibited, the write also clears the flag
+        let mut frame_counter: FrameCounter = FrameCounter::new();
+        assert_eq!(cycles_to_irq(&mut frame_counter), Some(IRQ_CYCLE as u32));
+        frame_counter.write_register(0x40);
+        assert_eq!(frame_counter.read_status(), 0x00);
+        assert_eq!(cycles_to_irq(&mut frame_counter), None);
+    }
+
+    #[test]
+    fn reset() {
+        let mut frame_counter: FrameCounter = FrameCounter::new();
+        frame_counter.write_register(0x80);
+        cycles_to_irq(&mut frame_counter);
+        frame_counter.irq_flag = true;
+        frame_counter.reset();
+        assert!(frame_counter.five_step);
+        assert_eq!(frame_counter.read_status(), 0x00);
+        assert_eq!(frame_counter.cycle, 0);
+    }
+}
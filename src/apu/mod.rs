@@ -0,0 +1,2 @@
+pub mod dmc;
+pub mod frame_counter;
\ No newline at end of file
@@ -0,0 +1,111 @@
+// Disassembles the PRG ROM of a ROM into ca65 source files, one per 16KB bank
+// The header, the CHR ROM and a ld65 configuration are written too, to assemble the ROM again
+// Exit status : 0 if the files were written, 1 if an argument is invalid or a file couldn't be read or written
+
+use std::{fs::{self, File}, path::{Path, PathBuf}, process};
+
+use clap::{App, Arg};
+use env_logger::Env;
+use log::{error, info};
+use nes_emulator::{cartridge::cartridge::Cartridge, code_data_logger::CodeDataLogger, disassembler::{header_source, Disassembler}, symbols::SymbolTable};
+
+fn main() {
+
+    // ===== APP CREATION AND ARGUMENT PARSING =====
+
+    let matches = App::new("Nesmulator disassembler")
+        .version("0.1.0")
+        .author("AntoineRR <ant.romero2@orange.fr>")
+        .about("Disassembles the PRG ROM of a NES ROM into ca65 source files")
+        .arg(
+            Arg::new("game")
+                .index(1)
+                .value_name("FILE")
+                .about("Sets the nes file to disassemble")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .value_name("DIR")
+                .takes_value(true)
+                .default_value(".")
+                .about("Directory where the source files are written")
+        )
+        .arg(
+            Arg::new("cdl")
+                .long("cdl")
+                .value_name("FILE")
+                .takes_value(true)
+                .about("Uses a FCEUX Code/Data Logger file to separate the code from the data")
+        )
+        .arg(
+            Arg::new("symbols")
+                .long("symbols")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .about("Loads the labels of a ca65 debug file (.dbg), a FCEUX name list (.nl) or a Mesen label file (.mlb), can be repeated")
+        )
+        .get_matches();
+
+    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+
+    let out_dir: PathBuf = PathBuf::from(matches.value_of("out").unwrap());
+    if let Err(why) = fs::create_dir_all(&out_dir) {
+        exit_with_error(&format!("Couldn't create {}: {}", out_dir.display(), why));
+    }
+
+    // ===== INPUT FILES =====
+
+    // Cartridge::new panics when the ROM can't be opened
+    let game_path: &Path = Path::new(matches.value_of("game").unwrap());
+    if let Err(why) = File::open(game_path) {
+        exit_with_error(&format!("Couldn't open {}: {}", game_path.display(), why));
+    }
+    let cartridge: Cartridge = Cartridge::new(game_path);
+
+    let o_code_data_logger: Option<CodeDataLogger> = matches.value_of("cdl").map(|path| {
+        CodeDataLogger::load(Path::new(path), cartridge.prg_rom_size(), cartridge.chr_rom_size()).unwrap_or_else(|why| exit_with_error(&why))
+    });
+
+    let mut symbols: SymbolTable = SymbolTable::new();
+    if let Some(paths) = matches.values_of("symbols") {
+        for path in paths {
+            if let Err(why) = symbols.load(Path::new(path)) {
+                exit_with_error(&format!("Couldn't load the symbols: {}", why));
+            }
+        }
+    }
+
+    // ===== DISASSEMBLY =====
+
+    let mut disassembler: Disassembler = Disassembler::new(cartridge.prg_rom.clone(), o_code_data_logger, symbols);
+    disassembler.analyse();
+
+    let o_chr_file: Option<&str> = match cartridge.chr_rom.is_empty() {
+        true => None,
+        false => Some("chr.bin")
+    };
+    if let Some(chr_file) = o_chr_file {
+        write_file(&out_dir.join(chr_file), &cartridge.chr_rom);
+    }
+    write_file(&out_dir.join("header.s"), header_source(&cartridge.header, o_chr_file).as_bytes());
+    for bank in 0..disassembler.banks() {
+        write_file(&out_dir.join(format!("bank_{:02X}.s", bank)), disassembler.bank_source(bank).as_bytes());
+    }
+    write_file(&out_dir.join("nes.cfg"), disassembler.linker_config(cartridge.chr_rom.len()).as_bytes());
+}
+
+fn write_file(path: &Path, content: &[u8]) {
+    match fs::write(path, content) {
+        Err(why) => exit_with_error(&format!("Couldn't write {}: {}", path.display(), why)),
+        Ok(_) => info!("Saved {}", path.display())
+    }
+}
+
+fn exit_with_error(message: &str) -> ! {
+    error!("{}", message);
+    process::exit(1);
+}
@@ -0,0 +1,139 @@
+// Runs a ROM without window for a given number of frames, and saves screenshots as PNG files
+// Exit status : 0 if the run succeeded, 1 if the movie or the screenshots couldn't be used
+
+use std::{fs, path::{Path, PathBuf}, process, sync::{Arc, Mutex}};
+
+use clap::{App, Arg};
+use env_logger::Env;
+use log::{error, info};
+use nes_emulator::{cartridge::cartridge::Cartridge, code_data_logger::CodeDataLogger, headless::Headless, movie::Movie};
+
+fn main() {
+
+    // ===== APP CREATION AND ARGUMENT PARSING =====
+
+    let matches = App::new("Nesmulator headless runner")
+        .version("0.1.0")
+        .author("AntoineRR <ant.romero2@orange.fr>")
+        .about("Runs a NES ROM without window")
+        .arg(
+            Arg::new("game")
+                .index(1)
+                .value_name("FILE")
+                .about("Sets the nes file to run in the emulator")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::new("frames")
+                .long("frames")
+                .value_name("N")
+                .takes_value(true)
+                .required(true)
+                .about("Number of frames to run")
+        )
+        .arg(
+            Arg::new("screenshot-every")
+                .long("screenshot-every")
+                .value_name("K")
+                .takes_value(true)
+                .about("Saves a screenshot every K frames, only the last frame is saved otherwise")
+        )
+        .arg(
+            Arg::new("input")
+                .long("input")
+                .value_name("FM2_FILE")
+                .takes_value(true)
+                .about("Plays the inputs of a movie")
+        )
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .value_name("DIR")
+                .takes_value(true)
+                .default_value(".")
+                .about("Directory where the screenshots are saved")
+        )
+        .arg(
+            Arg::new("cdl")
+                .long("cdl")
+                .value_name("FILE")
+                .takes_value(true)
+                .about("Marks the code and data of the ROM in a FCEUX Code/Data Logger file, the file is updated if it exists")
+        )
+        .arg(
+            Arg::new("log")
+                .short('l')
+                .long("log")
+                .about("Display the CPU logs to the console")
+        )
+        .get_matches();
+
+    env_logger::Builder::from_env(Env::default().default_filter_or("warn")).init();
+
+    let frames: u64 = parse_number(matches.value_of("frames").unwrap(), "--frames");
+    let o_screenshot_every: Option<u64> = matches.value_of("screenshot-every").map(|value| parse_number(value, "--screenshot-every"));
+    let out_dir: PathBuf = PathBuf::from(matches.value_of("out").unwrap());
+
+    if let Err(why) = fs::create_dir_all(&out_dir) {
+        exit_with_error(&format!("Couldn't create {}: {}", out_dir.display(), why));
+    }
+
+    // ===== RUN GAME =====
+
+    let path: &Path = Path::new(matches.value_of("game").unwrap());
+    let cartridge: Cartridge = Cartridge::new(path);
+    let o_p_code_data_logger: Option<Arc<Mutex<CodeDataLogger>>> = matches.value_of("cdl").map(|cdl_path| {
+        let code_data_logger: CodeDataLogger = match Path::new(cdl_path).exists() {
+            true => CodeDataLogger::load(Path::new(cdl_path), cartridge.prg_rom_size(), cartridge.chr_rom_size()).unwrap_or_else(|why| exit_with_error(&why)),
+            false => CodeDataLogger::new(cartridge.prg_rom_size(), cartridge.chr_rom_size())
+        };
+        Arc::new(Mutex::new(code_data_logger))
+    });
+    let mut headless: Headless = Headless::new(cartridge, matches.is_present("log"));
+    if let Some(p_code_data_logger) = o_p_code_data_logger.as_ref() {
+        headless.nes.set_code_data_logger(p_code_data_logger.clone());
+    }
+
+    if let Some(movie_path) = matches.value_of("input") {
+        match Movie::load_fm2(Path::new(movie_path)) {
+            Err(why) => exit_with_error(&format!("Couldn't load movie {}: {}", movie_path, why)),
+            Ok(movie) => headless.set_movie(movie)
+        }
+    }
+
+    for frame in 1..=frames {
+        headless.run_frame();
+        let take_screenshot: bool = match o_screenshot_every {
+            Some(every) => frame % every == 0,
+            None => frame == frames
+        };
+        if take_screenshot {
+            let screenshot_path: PathBuf = out_dir.join(format!("frame_{:06}.png", frame));
+            if let Err(why) = headless.save_screenshot(&screenshot_path) {
+                exit_with_error(&why);
+            }
+            info!("Saved {}", screenshot_path.display());
+        }
+    }
+
+    if let (Some(p_code_data_logger), Some(cdl_path)) = (o_p_code_data_logger.as_ref(), matches.value_of("cdl")) {
+        if let Err(why) = p_code_data_logger.lock().unwrap().save(Path::new(cdl_path)) {
+            exit_with_error(&why);
+        }
+        info!("Saved {}", cdl_path);
+    }
+}
+
+// Parses a strictly positive number given in the arguments
+fn parse_number(value: &str, name: &str) -> u64 {
+    match value.parse::<u64>() {
+        Ok(number) if number > 0 => number,
+        _ => exit_with_error(&format!("Invalid value for {} : {:?}, value must be a positive number", name, value))
+    }
+}
+
+fn exit_with_error(message: &str) -> ! {
+    error!("{}", message);
+    process::exit(1);
+}
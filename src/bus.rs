@@ -6,33 +6,244 @@ use std::sync::{Arc, Mutex};
 
 use cartridge::mapper::Mapper;
 
-use crate::{cartridge, controllers::Controller, ppu::ppu::PPU};
+use crate::{apu::{dmc::DMC, frame_counter::FrameCounter}, cartridge, code_data_logger::{CodeDataLogger, PRG_DATA, PRG_DMC}, debugger::{AccessKind, Watchpoint, WatchpointHit}, dma::DMA, input::device::{InputDevice, InputState, create_port_device}, memory_viewer::AccessHistory, ppu::ppu::PPU};
 
 // ===== CONSTANTS =====
 
 pub const STACK_OFFSET: u16 = 0x100;
 
+// Size of the internal RAM, mirrored up to $1FFF
+const RAM_SIZE: usize = 0x800;
+
+// ===== ENUMS =====
+
+// Content of the internal RAM at power on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RamPattern {
+    Zeros,
+    // Every byte is $FF
+    Ones,
+    // Pseudo random bytes generated from the seed
+    Random(u64)
+}
+
 // ===== BUS STRUCT =====
 
 pub struct Bus {
     pub data: [u8;0x10000],
     pub o_p_mapper: Option<Box<dyn Mapper>>,
     pub p_ppu: Arc<Mutex<PPU>>,
+
+    // Last value seen on the data bus
+    pub open_bus: u8,
+
+    // DMA unit, halts the CPU for the OAM and DMC transfers
+    pub dma: DMA,
+    pub dmc: DMC,
+    pub frame_counter: FrameCounter,
     
-    pub controllers: [Controller;2]
+    // Input devices plugged in the controller ports and in the expansion port
+    pub input_ports: [Box<dyn InputDevice>;2],
+    pub o_p_expansion_device: Option<Box<dyn InputDevice>>,
+
+    // Watchpoints of the debugger, checked on each access of the CPU
+    pub watchpoints: Vec<Watchpoint>,
+    // First watchpoint hit since the debugger last checked
+    pub o_watchpoint_hit: Option<WatchpointHit>,
+
+    // Marks the bytes of the PRG ROM read by the DMA unit
+    pub o_p_code_data_logger: Option<Arc<Mutex<CodeDataLogger>>>,
+
+    // Accesses of the CPU, highlighted by the memory viewer
+    pub o_access_history: Option<AccessHistory>
 }
 
 impl Bus {
     pub fn new(p_ppu: Arc<Mutex<PPU>>) -> Self {
+        let input_ports: [Box<dyn InputDevice>;2] = [
+            create_port_device("controller", 0, p_ppu.clone()),
+            create_port_device("controller", 1, p_ppu.clone())
+        ];
         Bus {
             data: [0;0x10000], // 64KB of ram
             o_p_mapper:None,
             p_ppu,
+
+            open_bus: 0,
+
+            dma: DMA::new(),
+            dmc: DMC::new(),
+            frame_counter: FrameCounter::new(),
             
-            controllers: [Controller::new();2]
+            input_ports,
+            o_p_expansion_device: None,
+
+            watchpoints: vec![],
+            o_watchpoint_hit: None,
+
+            o_p_code_data_logger: None,
+
+            o_access_history: None
+        }
+    }
+
+    // ===== RESET =====
+
+    // Called when the reset button is pressed on the NES
+    // The RAM is kept, the APU is silenced and the pending transfers are cancelled
+    pub fn reset(&mut self) {
+        self.dma = DMA::new();
+        self.dmc.set_enabled(false);
+        self.frame_counter.reset();
+    }
+
+    // Puts the bus in its power on state, with the RAM filled by the given pattern
+    pub fn power_on(&mut self, ram_pattern: RamPattern) {
+        self.data = [0;0x10000];
+        match ram_pattern {
+            RamPattern::Zeros => (),
+            RamPattern::Ones => self.data[..RAM_SIZE].fill(0xFF),
+            RamPattern::Random(seed) => {
+                // xorshift64, the state must not be 0
+                let mut state: u64 = (seed ^ 0x9E37_79B9_7F4A_7C15).max(1);
+                for byte in self.data[..RAM_SIZE].iter_mut() {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    *byte = (state >> 32) as u8;
+                }
+            }
+        }
+        self.open_bus = 0;
+        self.dma = DMA::new();
+        self.dmc = DMC::new();
+        self.frame_counter = FrameCounter::new();
+    }
+
+    // ===== INPUTS =====
+
+    // Returns the D0-D4 bits driven by the input devices on the given port
+    pub fn read_input(&mut self, port: usize) -> u8 {
+        let mut value: u8 = self.input_ports[port].read(port);
+        if let Some(device) = self.o_p_expansion_device.as_mut() {
+            value |= device.read(port);
+        }
+        value & 0x1F
+    }
+
+    // Same as read_input, without changing the state of the devices
+    pub fn peek_input(&self, port: usize) -> u8 {
+        let mut value: u8 = self.input_ports[port].peek(port);
+        if let Some(device) = self.o_p_expansion_device.as_ref() {
+            value |= device.peek(port);
+        }
+        value & 0x1F
+    }
+
+    // Sends the OUT lines to every input device
+    pub fn write_input(&mut self, value: u8) {
+        for device in self.input_ports.iter_mut() {
+            device.write(value);
+        }
+        if let Some(device) = self.o_p_expansion_device.as_mut() {
+            device.write(value);
+        }
+    }
+
+    // Gives the inputs from the frontend to every input device
+    pub fn update_inputs(&mut self, state: &InputState) {
+        for device in self.input_ports.iter_mut() {
+            device.update(state);
+        }
+        if let Some(device) = self.o_p_expansion_device.as_mut() {
+            device.update(state);
         }
     }
 
+    // ===== CLOCK =====
+
+    // Clocks the components sharing the bus for one CPU cycle
+    // The PPU runs 3 times faster than the CPU
+    pub fn tick(&mut self) {
+        {
+            let mut ppu = self.p_ppu.lock().unwrap();
+            for _ in 0..3 {
+                ppu.clock();
+            }
+        }
+        self.dmc.clock();
+        if self.dmc.dma_request {
+            self.dma.request_dmc();
+        }
+        self.frame_counter.clock();
+    }
+
+    // ===== DMA =====
+
+    // Performs the transfer of a cycle where the CPU is halted on a read of the given address
+    // The OAM DMA alternates a read on a get cycle and a write to $2004 on the next put cycle
+    // The halted read is repeated while waiting, this is how a DMC DMA deletes bits from the controller reads
+    pub fn dma_cycle(&mut self, address: u16, get_cycle: bool) {
+        // Consecutive reads of the controller ports are seen as a single read
+        let repeat_reads: bool = address != 0x4016 && address != 0x4017;
+
+        if get_cycle && self.dma.dmc_read {
+            self.log_prg(self.dmc.current_address, PRG_DMC | PRG_DATA);
+            let value: u8 = self.read(self.dmc.current_address);
+            self.dmc.fill_sample_buffer(value);
+            self.dma.dmc_pending = false;
+        }
+        else if get_cycle && self.dma.oam_pending {
+            let address: u16 = ((self.dma.oam_page as u16) << 8) + self.dma.oam_count / 2;
+            self.log_prg(address, PRG_DATA);
+            self.dma.oam_data = self.read(address);
+            self.dma.oam_count += 1;
+        }
+        else if !get_cycle && self.dma.oam_pending && !self.dma.oam_count.is_multiple_of(2) {
+            self.write(0x2004, self.dma.oam_data);
+            self.dma.oam_count += 1;
+            self.dma.oam_pending = self.dma.oam_count < 512;
+        }
+        else if repeat_reads {
+            // Dummy read while waiting for a get cycle or for the DMC
+            self.read(address);
+        }
+    }
+
+    // ===== INTERRUPTS =====
+
+    // Returns true when the PPU asserts the NMI line
+    pub fn nmi_line(&self) -> bool {
+        self.p_ppu.lock().unwrap().registers.nmi_line()
+    }
+
+    // Returns true when a component asserts the IRQ line
+    pub fn irq_line(&self) -> bool {
+        let mapper_irq: bool = match self.o_p_mapper.as_ref() {
+            Some(mapper) => mapper.irq_line(),
+            None => false
+        };
+        mapper_irq || self.dmc.irq_flag || self.frame_counter.irq_flag
+    }
+
+    // ===== WATCHPOINTS =====
+
+    // Records the access if it hits a watchpoint
+    pub fn check_watchpoints(&mut self, kind: AccessKind, address: u16, value: u8) {
+        if self.o_watchpoint_hit.is_some() {
+            return;
+        }
+        if let Some(watchpoint) = self.watchpoints.iter().find(|watchpoint| watchpoint.contains(kind, address)) {
+            self.o_watchpoint_hit = Some(WatchpointHit {
+                watchpoint: *watchpoint,
+                address,
+                value
+            });
+        }
+    }
+
+    // ===== READ AND WRITE =====
+
     // Reads data from the bus at the specified address
     pub fn read(&mut self, address: u16) -> u8 {
         let value: u8;
@@ -45,21 +256,26 @@ impl Bus {
             0x2000..=0x2007 => value = self.p_ppu.lock().unwrap().read_register(address),
             // 0x2008 - 0x3FFF / NES PPU Registers Mirrors
             0x2008..=0x3FFF => value = self.p_ppu.lock().unwrap().read_register(address & 0x2007),
-            // 0x4000 - 0x4013 / NES APU I/O Registers
-            0x4000..=0x4013 => value = self.data[address as usize],
-            // 0x4014 / NES PPU Register
-            0x4014 => value = self.p_ppu.lock().unwrap().read_register(address),
+            // 0x4000 - 0x4014 / Write only APU and OAM DMA registers
+            0x4000..=0x4014 => value = self.open_bus,
             // 0x4015 / NES APU Register
-            0x4015 => value = self.data[address as usize],
-            // 0x4016 / First controller
-            0x4016 => value = self.controllers[0].check_shifter(),
-            // 0x4017 / Second controller
-            0x4017 => value = self.controllers[1].check_shifter(),
-            // 0x4018 - 0x4020 / I/O Refisters
-            0x4018..=0x4020 => value = self.data[address as usize],
-            // 0x4021 - 0xFFFF / Handled by the mapper
-            0x4021..=0xFFFF => value = self.o_p_mapper.as_ref().unwrap().prg_rom_read(address)
+            // Bit 5 isn't driven, and the internal read doesn't change the data bus
+            0x4015 => {
+                let status: u8 = self.dmc.read_status() | self.frame_counter.read_status() | (self.open_bus & 0x20);
+                self.frame_counter.irq_flag = false;
+                return status;
+            }
+            // 0x4016 / First controller port
+            // Only the lower bits are driven, the others keep the last value of the bus
+            0x4016 => value = (self.open_bus & 0xE0) | self.read_input(0),
+            // 0x4017 / Second controller port
+            0x4017 => value = (self.open_bus & 0xE0) | self.read_input(1),
+            // 0x4018 - 0x401F / APU test registers, disabled
+            0x4018..=0x401F => value = self.open_bus,
+            // 0x4020 - 0xFFFF / Handled by the mapper
+            0x4020..=0xFFFF => value = self.read_cartridge(address)
         }
+        self.open_bus = value;
         value
     }
 
@@ -78,26 +294,61 @@ impl Bus {
             0x2000..=0x2007 => value = self.p_ppu.lock().unwrap().registers.read_register_without_modification(address),
             // 0x2008 - 0x3FFF / NES PPU Registers Mirrors
             0x2008..=0x3FFF => value = self.p_ppu.lock().unwrap().registers.read_register_without_modification(address & 0x2007),
-            // 0x4000 - 0x4013 / NES APU I/O Registers
-            0x4000..=0x4013 => value = self.data[address as usize],
-            // 0x4014 / NES PPU Register
-            0x4014 => value = self.p_ppu.lock().unwrap().registers.read_register_without_modification(address),
+            // 0x4000 - 0x4014 / Write only APU and OAM DMA registers
+            0x4000..=0x4014 => value = self.open_bus,
             // 0x4015 / NES APU Register
-            0x4015 => value = self.data[address as usize],
-            // 0x4016 / First controller
-            0x4016 => value = self.data[address as usize],
-            // 0x4017 / Second controller
-            0x4017 => value = self.data[address as usize],
-            // 0x4018 - 0x4020 / I/O Refisters
-            0x4018..=0x4020 => value = self.data[address as usize],
-            // 0x4021 - 0xFFFF / Handled by the mapper
-            0x4021..=0xFFFF => value = self.o_p_mapper.as_ref().unwrap().prg_rom_read(address)
+            0x4015 => value = self.dmc.read_status() | self.frame_counter.read_status() | (self.open_bus & 0x20),
+            // 0x4016 / First controller port
+            0x4016 => value = (self.open_bus & 0xE0) | self.peek_input(0),
+            // 0x4017 / Second controller port
+            0x4017 => value = (self.open_bus & 0xE0) | self.peek_input(1),
+            // 0x4018 - 0x401F / APU test registers, disabled
+            0x4018..=0x401F => value = self.open_bus,
+            // 0x4020 - 0xFFFF / Handled by the mapper
+            0x4020..=0xFFFF => value = self.read_cartridge(address)
         }
         value
     }
 
+    // Reads the cartridge, the data bus keeps its last value where nothing is mapped
+    pub fn read_cartridge(&self, address: u16) -> u8 {
+        match self.o_p_mapper.as_ref().and_then(|mapper| mapper.prg_rom_read(address)) {
+            Some(value) => value,
+            None => self.open_bus
+        }
+    }
+
+    // Offset in the PRG ROM of the byte at this CPU address, with the banks currently selected by the mapper
+    pub fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        self.o_p_mapper.as_ref().and_then(|mapper| mapper.prg_rom_offset(address))
+    }
+
+    // Marks the byte of the PRG ROM mapped at this address in the Code/Data Logger
+    pub fn log_prg(&self, address: u16, flags: u8) {
+        if let Some(p_code_data_logger) = self.o_p_code_data_logger.as_ref() {
+            if let Some(offset) = self.prg_rom_offset(address) {
+                p_code_data_logger.lock().unwrap().log_prg(offset, address, flags);
+            }
+        }
+    }
+
+    // CPU address where the byte at this offset of the PRG ROM is currently mapped, if it is mapped
+    // The banks are at least 8KB long, only one address per bank window is checked
+    pub fn prg_rom_address(&self, offset: usize) -> Option<u16> {
+        (0x8000..=0xFFFF).step_by(0x2000)
+            .map(|window: u32| (window + (offset % 0x2000) as u32) as u16)
+            .find(|address| self.prg_rom_offset(*address) == Some(offset))
+    }
+
+    // Returns true if a write to this address only changes memory : the internal RAM or the RAM of the cartridge
+    // Used by the debugging tools, writes to the registers of the PPU, the APU or the mapper have side effects
+    pub fn is_ram(address: u16) -> bool {
+        matches!(address, 0x0000..=0x1FFF | 0x6000..=0x7FFF)
+    }
+
     // Writes data to the bus at the specified address
     pub fn write(&mut self, address: u16, value: u8) {
+        self.open_bus = value;
         match address {
             // 0x0000 - 0x07FF / 2KB CPU RAM
             0x0000..=0x7FF => self.data[address as usize] = value,
@@ -107,28 +358,41 @@ impl Bus {
             0x2000..=0x2007 => self.p_ppu.lock().unwrap().write_register(address, value),
             // 0x2008 - 0x3FFF / NES PPU Registers Mirrors
             0x2008..=0x3FFF => self.p_ppu.lock().unwrap().write_register(address & 0x2007, value),
-            // 0x4000 - 0x4013 / NES APU I/O Registers
-            0x4000..=0x4013 => self.data[address as usize] = value,
-            // 0x4014 / NES PPU Register
-            0x4014 => self.p_ppu.lock().unwrap().write_register(address, value),
+            // 0x4000 - 0x400F / NES APU I/O Registers
+            0x4000..=0x400F => self.data[address as usize] = value,
+            // 0x4010 - 0x4013 / DMC Registers
+            0x4010..=0x4013 => {
+                self.data[address as usize] = value;
+                self.dmc.write_register(address, value);
+            }
+            // 0x4014 / OAM DMA
+            0x4014 => {
+                self.p_ppu.lock().unwrap().write_register(address, value);
+                self.dma.request_oam(value);
+            }
             // 0x4015 / NES APU Register
-            0x4015 => self.data[address as usize] = value,
-            // 0x4016 / First controller
-            0x4016 => {
-                if (value & 0x01) > 0 {
-                    self.controllers[0].update_shifter();
+            0x4015 => {
+                self.data[address as usize] = value;
+                self.dmc.set_enabled((value & 0x10) == 0x10);
+                if self.dmc.dma_request {
+                    self.dma.request_dmc();
                 }
             }
-            // 0x4017 / Second controller
+            // 0x4016 / OUT lines of the input devices
+            0x4016 => self.write_input(value),
+            // 0x4017 / NES APU Frame counter
             0x4017 => {
-                if (value & 0x01) > 0 {
-                    self.controllers[1].update_shifter();
+                self.data[address as usize] = value;
+                self.frame_counter.write_register(value);
+            }
+            // 0x4018 - 0x401F / APU test registers, disabled
+            0x4018..=0x401F => (),
+            // 0x4020 - 0xFFFF / Handled by the mapper
+            0x4020..=0xFFFF => {
+                if let Some(mapper) = self.o_p_mapper.as_mut() {
+                    mapper.prg_rom_write(address, value);
                 }
             }
-            // 0x4018 - 0x4020 / I/O Refisters
-            0x4018..=0x4020 => self.data[address as usize] = value,
-            // 0x4021 - 0xFFFF / Handled by the mapper
-            0x4021..=0xFFFF => self.o_p_mapper.as_mut().unwrap().prg_rom_write(address, value)
         }
     }
 }
\ No newline at end of file
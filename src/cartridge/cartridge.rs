@@ -40,6 +40,9 @@ impl INesHeader {
 pub struct Cartridge {
     pub header: INesHeader,
     pub mapper: Box<dyn Mapper>,
+    // Content of the ROM as stored in the file, read by the tools working without the mapper
+    pub prg_rom: Vec<u8>,
+    pub chr_rom: Vec<u8>
 }
 
 impl Cartridge {
@@ -71,6 +74,7 @@ impl Cartridge {
         header.control_1 = buffer[6];
         header.control_2 = buffer[7];
         header.n_ram_banks = buffer[8];
+        header.unused.copy_from_slice(&buffer[9..16]);
 
         debug!("{} PRG ROM | {} CHR ROM",header.n_prg_rom,header.n_chr_rom);
 
@@ -99,6 +103,9 @@ impl Cartridge {
             chr_rom.push(buffer);
         }
 
+        let raw_prg_rom: Vec<u8> = prg_rom.concat();
+        let raw_chr_rom: Vec<u8> = chr_rom[..header.n_chr_rom as usize].concat();
+
         let mirroring: Mirroring = match (header.control_1 & 0x01) == 1 {
             false => Mirroring::Horizontal,
             true => Mirroring::Vertical
@@ -118,7 +125,19 @@ impl Cartridge {
 
         Cartridge {
             header,
-            mapper
+            mapper,
+            prg_rom: raw_prg_rom,
+            chr_rom: raw_chr_rom
         }
     }
+
+    // Size of the PRG ROM in bytes
+    pub fn prg_rom_size(&self) -> usize {
+        self.header.n_prg_rom as usize * 16*1024
+    }
+
+    // Size of the CHR ROM in bytes, 0 when the cartridge has CHR RAM
+    pub fn chr_rom_size(&self) -> usize {
+        self.header.n_chr_rom as usize * 8*1024
+    }
 }
\ No newline at end of file
@@ -8,11 +8,21 @@ pub enum Mirroring {
 }
 
 pub trait Mapper {
-    fn prg_rom_read(&self, address: u16) -> u8;
+    // Returns None when the cartridge doesn't drive the data bus at this address
+    fn prg_rom_read(&self, address: u16) -> Option<u8>;
     fn prg_rom_write(&mut self, address: u16, value: u8);
     fn chr_rom_read(&self, address: u16) -> u8;
     fn chr_rom_write(&mut self, address: u16, value: u8);
     fn get_mirroring(&self) -> Mirroring;
+    // Offset in the PRG ROM of the byte mapped at this CPU address, None outside of the PRG ROM
+    // Used to locate the code in the ROM whatever the selected banks are
+    fn prg_rom_offset(&self, address: u16) -> Option<usize>;
+    // Offset in the CHR ROM of the byte mapped at this PPU address ($0000 - $1FFF)
+    fn chr_rom_offset(&self, address: u16) -> usize;
+    // Mappers with an IRQ counter assert the IRQ line of the CPU
+    fn irq_line(&self) -> bool {
+        false
+    }
     fn box_clone(&self) -> Box<dyn Mapper>;
 }
 
@@ -7,6 +7,8 @@ pub struct Mapper0 {
     pub mirroring: Mirroring,
     pub prg_rom: Vec<[u8;16*1024]>,
     pub chr_rom: Vec<[u8;8*1024]>,
+    // Family Basic cartridges and test ROMs use 8KB of PRG RAM
+    pub prg_ram: [u8;8*1024]
 }
 
 impl Mapper0 {
@@ -15,29 +17,31 @@ impl Mapper0 {
             mirroring,
             prg_rom,
             chr_rom,
+            prg_ram: [0;8*1024]
         }
     }
 }
 
 impl Mapper for Mapper0 {
-    fn prg_rom_read(&self, address: u16) -> u8 {
-        let value: u8;
+    fn prg_rom_read(&self, address: u16) -> Option<u8> {
+        let value: Option<u8>;
         match address {
             0x0000..=0x401F => panic!("Invalid address given to mapper : {:#X}",address),
-            0x4020..=0x5FFF => panic!("Mapper 0 doesn't use this address : {:#X}",address),
-            0x6000..=0x7FFF => panic!("Mapper 0 doesn't use this address : {:#X}",address),
-            0x8000..=0xBFFF => value = self.prg_rom[0][(address & 0x3FFF) as usize],
-            0xC000..=0xFFFF => value = self.prg_rom[(self.prg_rom.len() - 1) as usize][(address & 0x3FFF) as usize]
+            0x4020..=0x5FFF => value = None,
+            0x6000..=0x7FFF => value = Some(self.prg_ram[(address & 0x1FFF) as usize]),
+            0x8000..=0xBFFF => value = Some(self.prg_rom[0][(address & 0x3FFF) as usize]),
+            0xC000..=0xFFFF => value = Some(self.prg_rom[(self.prg_rom.len() - 1) as usize][(address & 0x3FFF) as usize])
         }
         value
     }
 
-    fn prg_rom_write(&mut self, address: u16, _value: u8) {
+    fn prg_rom_write(&mut self, address: u16, value: u8) {
         match address {
             0x0000..=0x401F => panic!("Invalid address given to mapper : {:#X}",address),
-            0x4020..=0x5FFF => panic!("Mapper 0 doesn't use this address : {:#X}",address),
-            0x6000..=0x7FFF => panic!("Mapper 0 doesn't use this address : {:#X}",address),
-            0x8000..=0xFFFF => panic!("Tried to write to the PRG ROM : {:#X}",address)
+            0x4020..=0x5FFF => (),
+            0x6000..=0x7FFF => self.prg_ram[(address & 0x1FFF) as usize] = value,
+            // Writes to the PRG ROM have no effect
+            0x8000..=0xFFFF => ()
         }
     }
 
@@ -56,6 +60,18 @@ impl Mapper for Mapper0 {
         self.mirroring
     }
 
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x0000..=0x7FFF => None,
+            0x8000..=0xBFFF => Some((address & 0x3FFF) as usize),
+            0xC000..=0xFFFF => Some((self.prg_rom.len() - 1) * 0x4000 + (address & 0x3FFF) as usize)
+        }
+    }
+
+    fn chr_rom_offset(&self, address: u16) -> usize {
+        address as usize
+    }
+
     fn box_clone(&self) -> Box<dyn Mapper> {
         Box::new((*self).clone())
     }
@@ -75,11 +75,11 @@ impl Mapper1 {
 }
 
 impl Mapper for Mapper1 {
-    fn prg_rom_read(&self, address: u16) -> u8 {
+    fn prg_rom_read(&self, address: u16) -> Option<u8> {
         let value: u8;
         match address {
             0x0000..=0x401F => panic!("Invalid address given to mapper : {:#X}",address),
-            0x4020..=0x5FFF => panic!("Mapper 1 doesn't use this address : {:#X}",address),
+            0x4020..=0x5FFF => return None,
             0x6000..=0x7FFF => value = self.ram[(address & 0x1FFF) as usize],
             0x8000..=0xBFFF => {
                 match self.get_prg_rom_bank_mode() {
@@ -96,13 +96,13 @@ impl Mapper for Mapper1 {
                 }
             }
         }
-        value
+        Some(value)
     }
 
     fn prg_rom_write(&mut self, address: u16, value: u8) {
         match address {
             0x0000..=0x401F => panic!("Invalid address given to mapper : {:#X}",address),
-            0x4020..=0x5FFF => panic!("Mapper 1 doesn't use this address : {:#X}",address),
+            0x4020..=0x5FFF => (),
             0x6000..=0x7FFF => self.ram[(address & 0x1FFF) as usize] = value,
             0x8000..=0xFFFF =>  {
                 if value & 0x80 > 0 {
@@ -190,6 +190,32 @@ impl Mapper for Mapper1 {
         }
     }
 
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        let bank: usize = match address {
+            0x0000..=0x7FFF => return None,
+            0x8000..=0xBFFF => match self.get_prg_rom_bank_mode() {
+                PrgRomBankMode::Switch32 => self.lo_prg_rom,
+                PrgRomBankMode::Switch16FirstFixed => 0,
+                PrgRomBankMode::Sxitch16LastFixed => self.lo_prg_rom
+            },
+            0xC000..=0xFFFF => match self.get_prg_rom_bank_mode() {
+                PrgRomBankMode::Switch32 => self.lo_prg_rom + 1,
+                PrgRomBankMode::Switch16FirstFixed => self.hi_prg_rom,
+                PrgRomBankMode::Sxitch16LastFixed => self.prg_rom.len() - 1
+            }
+        };
+        Some(bank * 0x4000 + (address & 0x3FFF) as usize)
+    }
+
+    fn chr_rom_offset(&self, address: u16) -> usize {
+        let bank: usize = match (self.get_chr_rom_bank_mode(), address) {
+            (_, 0x0000..=0x0FFF) => self.lo_chr_rom,
+            (ChrRomBankMode::Switch8, _) => self.lo_chr_rom + 1,
+            (ChrRomBankMode::Switch4, _) => self.hi_chr_rom
+        };
+        bank * 0x1000 + (address & 0x0FFF) as usize
+    }
+
     fn box_clone(&self) -> Box<dyn Mapper> {
         Box::new((*self).clone())
     }
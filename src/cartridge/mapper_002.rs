@@ -22,14 +22,13 @@ impl Mapper2 {
 }
 
 impl Mapper for Mapper2 {
-    fn prg_rom_read(&self, address: u16) -> u8 {
-        let value: u8;
+    fn prg_rom_read(&self, address: u16) -> Option<u8> {
+        let value: Option<u8>;
         match address {
             0x0000..=0x401F => panic!("Invalid address given to mapper : {:#X}",address),
-            0x4020..=0x5FFF => panic!("Mapper 2 doesn't use this address : {:#X}",address),
-            0x6000..=0x7FFF => value = 0,//panic!("Mapper 2 doesn't use this address : {:#X}",address),
-            0x8000..=0xBFFF => value = self.prg_rom[self.lo_prg_rom][(address & 0x3FFF) as usize],
-            0xC000..=0xFFFF => value = self.prg_rom[(self.prg_rom.len() - 1) as usize][(address & 0x3FFF) as usize]
+            0x4020..=0x7FFF => value = None,
+            0x8000..=0xBFFF => value = Some(self.prg_rom[self.lo_prg_rom][(address & 0x3FFF) as usize]),
+            0xC000..=0xFFFF => value = Some(self.prg_rom[(self.prg_rom.len() - 1) as usize][(address & 0x3FFF) as usize])
         }
         value
     }
@@ -37,8 +36,7 @@ impl Mapper for Mapper2 {
     fn prg_rom_write(&mut self, address: u16, value: u8) {
         match address {
             0x0000..=0x401F => panic!("Invalid address given to mapper : {:#X}",address),
-            0x4020..=0x5FFF => panic!("Mapper 2 doesn't use this address : {:#X}",address),
-            0x6000..=0x7FFF => panic!("Mapper 2 doesn't use this address : {:#X}",address),
+            0x4020..=0x7FFF => (),
             0x8000..=0xFFFF => self.lo_prg_rom = (value & 0x0F) as usize
         }
     }
@@ -58,6 +56,18 @@ impl Mapper for Mapper2 {
         self.mirroring
     }
 
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x0000..=0x7FFF => None,
+            0x8000..=0xBFFF => Some(self.lo_prg_rom * 0x4000 + (address & 0x3FFF) as usize),
+            0xC000..=0xFFFF => Some((self.prg_rom.len() - 1) * 0x4000 + (address & 0x3FFF) as usize)
+        }
+    }
+
+    fn chr_rom_offset(&self, address: u16) -> usize {
+        address as usize
+    }
+
     fn box_clone(&self) -> Box<dyn Mapper> {
         Box::new((*self).clone())
     }
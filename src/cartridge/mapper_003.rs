@@ -22,14 +22,13 @@ impl Mapper3 {
 }
 
 impl Mapper for Mapper3 {
-    fn prg_rom_read(&self, address: u16) -> u8 {
-        let value: u8;
+    fn prg_rom_read(&self, address: u16) -> Option<u8> {
+        let value: Option<u8>;
         match address {
             0x0000..=0x401F => panic!("Invalid address given to mapper : {:#X}",address),
-            0x4020..=0x5FFF => panic!("Mapper 3 doesn't use this address : {:#X}",address),
-            0x6000..=0x7FFF => panic!("Mapper 3 doesn't use this address : {:#X}",address),
-            0x8000..=0xBFFF => value = self.prg_rom[0][(address & 0x3FFF) as usize],
-            0xC000..=0xFFFF => value = self.prg_rom[(self.prg_rom.len() - 1) as usize][(address & 0x3FFF) as usize]
+            0x4020..=0x7FFF => value = None,
+            0x8000..=0xBFFF => value = Some(self.prg_rom[0][(address & 0x3FFF) as usize]),
+            0xC000..=0xFFFF => value = Some(self.prg_rom[(self.prg_rom.len() - 1) as usize][(address & 0x3FFF) as usize])
         }
         value
     }
@@ -37,8 +36,7 @@ impl Mapper for Mapper3 {
     fn prg_rom_write(&mut self, address: u16, value: u8) {
         match address {
             0x0000..=0x401F => panic!("Invalid address given to mapper : {:#X}",address),
-            0x4020..=0x5FFF => panic!("Mapper 3 doesn't use this address : {:#X}",address),
-            0x6000..=0x7FFF => panic!("Mapper 3 doesn't use this address : {:#X}",address),
+            0x4020..=0x7FFF => (),
             0x8000..=0xFFFF => self.selected_chr_rom = (value & 0x03) as usize
         }
     }
@@ -58,6 +56,18 @@ impl Mapper for Mapper3 {
         self.mirroring
     }
 
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        match address {
+            0x0000..=0x7FFF => None,
+            0x8000..=0xBFFF => Some((address & 0x3FFF) as usize),
+            0xC000..=0xFFFF => Some((self.prg_rom.len() - 1) * 0x4000 + (address & 0x3FFF) as usize)
+        }
+    }
+
+    fn chr_rom_offset(&self, address: u16) -> usize {
+        self.selected_chr_rom * 0x2000 + address as usize
+    }
+
     fn box_clone(&self) -> Box<dyn Mapper> {
         Box::new((*self).clone())
     }
@@ -0,0 +1,141 @@
+// Code/Data Logger, marks how each byte of the cartridge ROM has been used
+// The bytes are located by their offset in the PRG and CHR ROM, through the bank mapping of the mapper
+// The flags are saved in the FCEUX format (.cdl) : one byte per PRG ROM byte, followed by one byte per CHR ROM byte
+
+// ===== IMPORTS =====
+
+use std::{fs, path::Path};
+
+// ===== CONSTANTS =====
+
+// PRG ROM flags
+pub const PRG_CODE: u8 = 0x01;
+pub const PRG_DATA: u8 = 0x02;
+// 8KB window of the CPU address space the byte was accessed from ($8000, $A000, $C000 or $E000)
+pub const PRG_WINDOW_MASK: u8 = 0x0C;
+// Code reached by an indirect jump, and data read with an indirect addressing mode
+pub const PRG_INDIRECT_CODE: u8 = 0x10;
+pub const PRG_INDIRECT_DATA: u8 = 0x20;
+// Sample played by the DMC
+pub const PRG_DMC: u8 = 0x40;
+
+// CHR ROM flags
+pub const CHR_RENDERED: u8 = 0x01;
+pub const CHR_READ: u8 = 0x02;
+
+// ===== CODE DATA LOGGER STRUCT =====
+
+pub struct CodeDataLogger {
+    pub prg_flags: Vec<u8>,
+    pub chr_flags: Vec<u8>
+}
+
+impl CodeDataLogger {
+    // The CHR size is 0 for the cartridges with CHR RAM
+    pub fn new(prg_size: usize, chr_size: usize) -> Self {
+        CodeDataLogger {
+            prg_flags: vec![0;prg_size],
+            chr_flags: vec![0;chr_size]
+        }
+    }
+
+    // Loads a .cdl file, it must have been logged with a ROM of the same size
+    pub fn load(path: &Path, prg_size: usize, chr_size: usize) -> Result<Self, String> {
+        let data: Vec<u8> = fs::read(path).map_err(|why| format!("Couldn't read {}: {}", path.display(), why))?;
+        if data.len() != prg_size + chr_size {
+            return Err(format!("{} has {} bytes, {} were expected for this ROM", path.display(), data.len(), prg_size + chr_size));
+        }
+        Ok(CodeDataLogger {
+            prg_flags: data[..prg_size].to_vec(),
+            chr_flags: data[prg_size..].to_vec()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data: Vec<u8> = self.prg_flags.iter().chain(self.chr_flags.iter()).copied().collect();
+        fs::write(path, data).map_err(|why| format!("Couldn't write {}: {}", path.display(), why))
+    }
+
+    // ===== LOGGING =====
+
+    // Marks a byte of the PRG ROM accessed by the CPU at this address
+    pub fn log_prg(&mut self, offset: usize, address: u16, flags: u8) {
+        if let Some(byte) = self.prg_flags.get_mut(offset) {
+            *byte |= flags | (((address >> 13) & 0x03) as u8) << 2;
+        }
+    }
+
+    pub fn log_chr(&mut self, offset: usize, flags: u8) {
+        if let Some(byte) = self.chr_flags.get_mut(offset) {
+            *byte |= flags;
+        }
+    }
+
+    // ===== STATISTICS =====
+
+    // Number of PRG ROM bytes marked as code and as data
+    pub fn prg_statistics(&self) -> (usize, usize) {
+        let code: usize = self.prg_flags.iter().filter(|flags| *flags & PRG_CODE != 0).count();
+        let data: usize = self.prg_flags.iter().filter(|flags| *flags & PRG_DATA != 0).count();
+        (code, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::{bus::Bus, cartridge::{mapper::{Mapper, Mirroring}, mapper_000::Mapper0}, cpu::cpu::CPU, ppu::ppu::PPU};
+
+    // Runs the instructions of a program stored at the beginning of the PRG ROM, with a Code/Data Logger
+    fn run(program: &[u8], ram: &[u8], instructions: usize) -> CodeDataLogger {
+        let mut prg_rom: [u8;16*1024] = [0;16*1024];
+        prg_rom[..program.len()].copy_from_slice(program);
+        let mapper: Box<dyn Mapper> = Box::new(Mapper0::new(vec![prg_rom], vec![[0;8*1024]], Mirroring::Vertical));
+        let p_ppu: Arc<Mutex<PPU>> = Arc::new(Mutex::new(PPU::new(None)));
+        p_ppu.lock().unwrap().ppu_bus.o_p_mapper = Some(mapper.clone());
+        let p_bus: Arc<Mutex<Bus>> = Arc::new(Mutex::new(Bus::new(p_ppu)));
+        p_bus.lock().unwrap().o_p_mapper = Some(mapper);
+        p_bus.lock().unwrap().data[..ram.len()].copy_from_slice(ram);
+        let p_code_data_logger: Arc<Mutex<CodeDataLogger>> = Arc::new(Mutex::new(CodeDataLogger::new(16*1024, 8*1024)));
+        let mut cpu: CPU = CPU::new(p_bus, false);
+        cpu.o_p_code_data_logger = Some(p_code_data_logger.clone());
+        cpu.pc = 0xC000;
+        for _ in 0..instructions {
+            cpu.step();
+        }
+        drop(cpu);
+        Arc::try_unwrap(p_code_data_logger).ok().unwrap().into_inner().unwrap()
+    }
+
+    #[test]
+    fn prg_flags() {
+        // LDA $8010 ; LDY #$00 ; LDA ($00),Y ; STA $8040 ; JMP ($0002)
+        let program: [u8;14] = [0xAD, 0x10, 0x80, 0xA0, 0x00, 0xB1, 0x00, 0x8D, 0x40, 0x80, 0x6C, 0x02, 0x00, 0xEA];
+        let code_data_logger: CodeDataLogger = run(&program, &[0x20, 0x80, 0x30, 0x80], 5);
+        // The code runs from $C000, the data is read from $8000
+        let window_c000: u8 = 0x08;
+        assert!(code_data_logger.prg_flags[..13].iter().all(|flags| *flags == PRG_CODE | window_c000));
+        assert_eq!(code_data_logger.prg_flags[13], 0);
+        assert_eq!(code_data_logger.prg_flags[0x10], PRG_DATA);
+        assert_eq!(code_data_logger.prg_flags[0x20], PRG_DATA | PRG_INDIRECT_DATA);
+        assert_eq!(code_data_logger.prg_flags[0x30], PRG_CODE | PRG_INDIRECT_CODE);
+        assert_eq!(code_data_logger.prg_flags[0x40], 0);
+        assert_eq!(code_data_logger.prg_statistics(), (14, 2));
+    }
+
+    #[test]
+    fn file() {
+        let mut code_data_logger: CodeDataLogger = CodeDataLogger::new(0x4000, 0x2000);
+        code_data_logger.log_prg(0x1234, 0xF234, PRG_CODE);
+        code_data_logger.log_chr(0x0010, CHR_RENDERED);
+        code_data_logger.log_chr(0x2000, CHR_READ);
+        let path: std::path::PathBuf = std::env::temp_dir().join("nes_emulator_code_data_logger.cdl");
+        code_data_logger.save(&path).unwrap();
+        let loaded: CodeDataLogger = CodeDataLogger::load(&path, 0x4000, 0x2000).unwrap();
+        assert_eq!(loaded.prg_flags[0x1234], PRG_CODE | PRG_WINDOW_MASK);
+        assert_eq!(loaded.chr_flags[0x0010], CHR_RENDERED);
+        assert!(CodeDataLogger::load(&path, 0x8000, 0x2000).is_err());
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -4,18 +4,28 @@
 // ====== IMPORTS =====
 
 use core::panic;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, mpsc::Sender};
 
-use crate::{bus::Bus};
+use crate::{bus::Bus, code_data_logger::{CodeDataLogger, PRG_CODE, PRG_DATA, PRG_INDIRECT_CODE, PRG_INDIRECT_DATA}, symbols::{Symbol, SymbolTable}};
 use crate::bus::STACK_OFFSET;
+use super::cpu_bus::CpuBus;
 use super::instructions::{CpuInstruction,INSTRUCTIONS};
 use super::enums::{AdressingMode as am,Flag,Interrupt};
 use std::fmt::Write;
 
+// ===== CONSTANTS =====
+
+const BRK_OPCODE: u8 = 0x00;
+
+// Unstable instructions OR the accumulator with a value depending on the chip
+const ANE_MAGIC_CONSTANT: u8 = 0xEE;
+const LXA_MAGIC_CONSTANT: u8 = 0xFF;
+
 // ===== CPU STRUCT =====
 
 // This struct contains the various registers of the CPU
-pub struct CPU {
+// The CPU of the NES is connected to the shared NES bus
+pub struct CPU<B: CpuBus = Arc<Mutex<Bus>>> {
     // Registers
     pub a: u8, // accumulator
     pub x: u8, // x index
@@ -24,24 +34,42 @@ pub struct CPU {
     pub sp: u8, // stack pointer
     pub p: u8, // status flags
 
-    // Cycles required by the instruction to complete
-    pub cycles: u8,
-
     // Does the current instruction require an eventual additional cycle ?
+    // Only instructions reading their operand skip the dummy read of indexed addressing modes
     pub require_add_cycle: bool,
 
     // Total clock cycles from the start of the CPU
     pub total_clock: u64,
 
+    // Interrupt lines, sampled at the end of each cycle
+    // NMI is edge sensitive, the pending NMI stays set until it is handled
+    pub nmi_line: bool,
+    pub nmi_pending: bool,
+    // IRQ is level sensitive and masked by the interrupt disable flag
+    pub irq_pending: bool,
+    pub previous_irq_pending: bool,
+
+    // Set by the JAM instructions, only a reset restarts the CPU
+    pub halted: bool,
+
+    // The 2A03 has no decimal mode, it is enabled to emulate a stock NMOS 6502
+    pub decimal_mode: bool,
+
     // Display the log of the CPU
     pub display_logs: bool,
-
-    // pointer to the data bus where we read from and write to
-    pub p_bus: Arc<Mutex<Bus>>
+    // Receives the log of each instruction, used to compare it to a golden log
+    pub o_log_sender: Option<Sender<String>>,
+    // Labels and comments displayed in the logs and the disassembly
+    pub o_symbols: Option<Arc<SymbolTable>>,
+    // Marks the bytes of the PRG ROM executed and read by the instructions
+    pub o_p_code_data_logger: Option<Arc<Mutex<CodeDataLogger>>>,
+
+    // data bus where we read from and write to
+    pub bus: B
 }
 
-impl CPU {
-    pub fn new(p_bus: Arc<Mutex<Bus>>, display_logs: bool) -> Self {
+impl<B: CpuBus> CPU<B> {
+    pub fn new(bus: B, display_logs: bool) -> Self {
         CPU {
             a: 0,
             x: 0,
@@ -50,28 +78,52 @@ impl CPU {
             sp: 0xFD,
             p: 0x34,
 
-            cycles: 0,
-
             require_add_cycle: false,
 
             total_clock: 0,
 
+            nmi_line: false,
+            nmi_pending: false,
+            irq_pending: false,
+            previous_irq_pending: false,
+
+            halted: false,
+
+            decimal_mode: false,
+
             display_logs,
+            o_log_sender: None,
+            o_symbols: None,
+            o_p_code_data_logger: None,
 
-            p_bus
+            bus
         }
     }
 
     // ===== BUS ACCESS =====
 
+    // Every cycle of the CPU is a read or a write on the bus
+    // The rest of the NES is clocked before each access
+    pub fn tick(&mut self) {
+        self.poll_interrupts();
+        self.total_clock += 1;
+        self.bus.tick();
+    }
+
     // Reads data from the bus at the given address
-    pub fn read_bus(& self, address: u16) -> u8 {
-        self.p_bus.lock().unwrap().read(address)
+    // A pending DMA halts the CPU before the read
+    pub fn read_bus(&mut self, address: u16) -> u8 {
+        if self.bus.dma_pending() {
+            self.process_dma(address);
+        }
+        self.tick();
+        self.bus.read(address)
     }
 
     // Writes data to the bus at the given address
     pub fn write_bus(&mut self, address: u16, data: u8) {
-        self.p_bus.lock().unwrap().write(address, data);
+        self.tick();
+        self.bus.write(address, data);
     }
 
     // Pushes data to stack
@@ -96,6 +148,28 @@ impl CPU {
         self.read_bus(STACK_OFFSET + self.sp as u16)
     }
 
+    // ===== DMA =====
+
+    // Runs the pending DMA transfers, the CPU is halted on a read of the given address
+    // The halted read is performed on the halt cycle, the transfers are done by the bus
+    pub fn process_dma(&mut self, address: u16) {
+        // Halt cycle
+        self.dma_tick();
+        self.bus.read(address);
+
+        while self.bus.dma_pending() {
+            let get_cycle: bool = self.total_clock.is_multiple_of(2);
+            self.dma_tick();
+            self.bus.dma_cycle(address, get_cycle);
+        }
+    }
+
+    // A cycle where the CPU is halted by the DMA unit
+    pub fn dma_tick(&mut self) {
+        self.bus.start_dma_cycle();
+        self.tick();
+    }
+
     // ===== FLAG SETTER AND GETTER =====
 
     // Returns the required flag from the status register
@@ -115,80 +189,130 @@ impl CPU {
 
     // ===== INTERRUPTS =====
 
-    // Called when an interrupt occurs
-    // The interrupt disable flag from the status register needs to be 0
-    pub fn interrupt(&mut self, interrupt_type: Interrupt) {
-        if !self.get_flag(Flag::InterruptDisable) || interrupt_type == Interrupt::NMI {
-
-            if interrupt_type != Interrupt::Reset {
-                // Push program counter and status register on the stack
-                self.push_to_stack(((self.pc & 0xFF00) >> 8) as u8);
-                self.push_to_stack((self.pc & 0x00FF) as u8);
-                self.push_to_stack(self.p);
-            }
-            // Disable interrupts
-            self.set_flag(Flag::InterruptDisable, true);
-            // Load interrupt handler address into the program counter
-            let start_address: u16;
-            match interrupt_type {
-                Interrupt::IRQ => start_address = 0xFFFE,
-                Interrupt::NMI => start_address = 0xFFFA,
-                Interrupt::Reset => start_address = 0xFFFC
-            }
-            self.pc = self.read_bus(start_address) as u16 + ((self.read_bus(start_address + 1) as u16) << 8) as u16;
+    // Samples the interrupt lines at the end of the previous cycle
+    // When an instruction ends, the interrupts seen at the end of its next-to-last cycle are handled
+    pub fn poll_interrupts(&mut self) {
+        let (nmi_line, irq_line): (bool, bool) = self.bus.interrupt_lines();
+        if nmi_line && !self.nmi_line {
+            self.nmi_pending = true;
+        }
+        self.nmi_line = nmi_line;
+        self.previous_irq_pending = self.irq_pending;
+        self.irq_pending = irq_line && !self.get_flag(Flag::InterruptDisable);
+    }
 
-            if interrupt_type == Interrupt::NMI {
-                self.cycles = 7;
+    // Called when an interrupt occurs, after the current instruction
+    pub fn interrupt(&mut self, interrupt_type: Interrupt) {
+        // The next opcode is read twice and discarded
+        self.read_bus(self.pc);
+        self.read_bus(self.pc);
+        self.enter_interrupt(interrupt_type);
+    }
+
+    // Pushes the program counter and the status register, then loads the interrupt handler address
+    // Takes 5 cycles
+    pub fn enter_interrupt(&mut self, interrupt_type: Interrupt) {
+        let mut start_address: u16;
+        match interrupt_type {
+            Interrupt::IRQ | Interrupt::BRK => start_address = 0xFFFE,
+            Interrupt::NMI => start_address = 0xFFFA,
+            Interrupt::Reset => start_address = 0xFFFC
+        }
+        if interrupt_type != Interrupt::Reset {
+            // Push program counter and status register on the stack
+            // The break flag only exists on the stack, it is set when the interrupt comes from BRK
+            let break_flag: u8 = if interrupt_type == Interrupt::BRK { Flag::Break as u8 } else { 0 };
+            self.push_to_stack(((self.pc & 0xFF00) >> 8) as u8);
+            self.push_to_stack((self.pc & 0x00FF) as u8);
+            self.push_to_stack(self.p | Flag::Unused as u8 | break_flag);
+            // An NMI occurring before the handler address is read hijacks BRK and IRQ
+            if self.nmi_pending {
+                self.nmi_pending = false;
+                start_address = 0xFFFA;
             }
-            else {
-                self.cycles = 7;
+        }
+        else {
+            // The stack is read instead of written during a reset
+            for _ in 0..3 {
+                self.read_bus(STACK_OFFSET + self.sp as u16);
+                self.sp = self.sp.wrapping_sub(1);
             }
         }
+        // Disable interrupts
+        self.set_flag(Flag::InterruptDisable, true);
+        // Load interrupt handler address into the program counter
+        self.pc = self.read_bus(start_address) as u16 + ((self.read_bus(start_address + 1) as u16) << 8) as u16;
     }
 
-    // Called when the reset button is pressed on the NES
-    pub fn reset(&mut self) {
+    // Puts the registers in their power on state, the reset sequence must follow
+    pub fn power_on(&mut self) {
         self.a = 0x00;
         self.x = 0x00;
         self.y = 0x00;
-        self.sp = 0xFD;
+        // The 3 decrements of the reset sequence give 0xFD
+        self.sp = 0x00;
         self.p = 0x20;
+        self.total_clock = 0;
+        self.nmi_line = false;
+        self.irq_pending = false;
+        self.previous_irq_pending = false;
+    }
+
+    // Called when the reset button is pressed on the NES
+    // A, X, Y and the other flags are kept, the stack pointer is decremented 3 times
+    pub fn reset(&mut self) {
+        self.nmi_pending = false;
+        self.halted = false;
         self.interrupt(Interrupt::Reset);
     }
 
-    // ===== CLOCK =====
+    // ===== STEP =====
 
-    // Executes a clock cycle
+    // Executes the next instruction, one bus access per cycle
     // opcodes for operations are stored in the INSTRUCTIONS const
-    pub fn clock(&mut self) {
-        // Basic cycle emulation :
-        // cycle 0 does the operation and the others do nothing
-        if self.cycles == 0 {
-            // Get operation code
-            let opcode: u8 = self.read_bus(self.pc);
-
-            // Logs
-            if self.display_logs {
-                self.display_cpu_log(opcode);
-            }
-            
-            // Get instruction information for the operation code
-            let instruction: &CpuInstruction = &INSTRUCTIONS[opcode as usize];
-            self.require_add_cycle = instruction.add_cycle;
-            
-            // Execute the instruction
-            (instruction.execute)(self, instruction.adressing_mode);
-            
-            // Increase program counter
-            self.pc += 1;
-            
-            // Sets the correct number of cycles
-            self.cycles += instruction.cycles - 1;
+    pub fn step(&mut self) {
+        // The data bus keeps reading 0xFFFF while the CPU is halted
+        if self.halted {
+            self.tick();
+            return;
         }
-        else {
-            self.cycles -= 1;
+
+        // Logs
+        if self.display_logs || self.o_log_sender.is_some() {
+            let opcode: u8 = self.read_only_bus(self.pc);
+            self.display_cpu_log(opcode);
+        }
+        if self.o_p_code_data_logger.is_some() {
+            self.log_code_data();
+        }
+
+        // Get operation code
+        let opcode: u8 = self.read_bus(self.pc);
+
+        // Get instruction information for the operation code
+        let instruction: &CpuInstruction<B> = &Self::INSTRUCTIONS[opcode as usize];
+        self.require_add_cycle = instruction.add_cycle;
+
+        // Instructions without operand read the next byte and discard it
+        if let am::Implicit | am::Accumulator = instruction.adressing_mode {
+            self.read_bus(self.pc.wrapping_add(1));
+        }
+
+        // Execute the instruction
+        (instruction.execute)(self, instruction.adressing_mode);
+
+        // Increase program counter
+        self.pc = self.pc.wrapping_add(1);
+
+        // BRK has already been through the interrupt sequence, its handler starts first
+        if opcode != BRK_OPCODE {
+            if self.nmi_pending {
+                self.interrupt(Interrupt::NMI);
+            }
+            else if self.irq_pending {
+                self.interrupt(Interrupt::IRQ);
+            }
         }
-        self.total_clock += 1;
     }
 
     // ===== ADDRESSING MODES =====
@@ -212,11 +336,15 @@ impl CPU {
             am::ZeroPageX => {
                 self.pc += 1;
                 let address: u8 = self.read_bus(self.pc);
+                // Dummy read while the index is added
+                self.read_bus(address as u16);
                 (address as u16 + self.x as u16) % 0x100
             },
             am::ZeroPageY => {
                 self.pc += 1;
                 let address: u8 = self.read_bus(self.pc);
+                // Dummy read while the index is added
+                self.read_bus(address as u16);
                 (address as u16 + self.y as u16) % 0x100
             },
             am::Relative => {
@@ -236,11 +364,9 @@ impl CPU {
                 self.pc += 1;
                 let hi: u8 = self.read_bus(self.pc);
                 let address: u16 = lo as u16 + ((hi as u16) << 8);
-                let result: u32 = address as u32 + self.x as u32;
-                if (self.require_add_cycle) && ((result as u16 & 0xFF00) != (address & 0xFF00)) {
-                    self.cycles += 1;
-                }
-                result as u16
+                let result: u16 = address.wrapping_add(self.x as u16);
+                self.indexed_dummy_read(address, result);
+                result
             },
             am::AbsoluteY => {
                 self.pc += 1;
@@ -248,11 +374,8 @@ impl CPU {
                 self.pc += 1;
                 let hi: u8 = self.read_bus(self.pc);
                 let address: u16 = lo as u16 + ((hi as u16) << 8);
-                let addition: u32 = address as u32 + self.y as u32;
-                let result: u16 = (addition & 0x0000_FFFF) as u16;
-                if (self.require_add_cycle) && ((result & 0xFF00) != (address & 0xFF00)) {
-                    self.cycles += 1;
-                }
+                let result: u16 = address.wrapping_add(self.y as u16);
+                self.indexed_dummy_read(address, result);
                 result
             },
             am::Indirect => {
@@ -276,9 +399,14 @@ impl CPU {
             },
             am::IndirectX => {
                 self.pc += 1;
-                let ptr_lo: u16 = (self.read_bus(self.pc) as u16 + self.x as u16) % 0x100; // address in the 0x00 page
+                let ptr: u8 = self.read_bus(self.pc);
+                // Dummy read while the index is added
+                self.read_bus(ptr as u16);
+                let ptr_lo: u16 = (ptr as u16 + self.x as u16) % 0x100; // address in the 0x00 page
                 let ptr_hi: u16 = (ptr_lo + 1) % 0x100;
-                self.read_bus(ptr_lo) as u16 + ((self.read_bus(ptr_hi) as u16) << 8)
+                let address_lo: u8 = self.read_bus(ptr_lo);
+                let address_hi: u8 = self.read_bus(ptr_hi);
+                address_lo as u16 + ((address_hi as u16) << 8)
             },
             am::IndirectY => {
                 self.pc += 1;
@@ -287,10 +415,8 @@ impl CPU {
                 let address_lo: u8 = self.read_bus(ptr_lo);
                 let address_hi: u8 = self.read_bus(ptr_hi);
                 let address: u16 = address_lo as u16 + ((address_hi as u16) << 8);
-                let result: u16 = ((address as u32 + self.y as u32) % 0x10000) as u16;
-                if (self.require_add_cycle) && ((result & 0xFF00) != (address & 0xFF00)) {
-                    self.cycles += 1;
-                }
+                let result: u16 = address.wrapping_add(self.y as u16);
+                self.indexed_dummy_read(address, result);
                 result
             },
             am::NoMode => {
@@ -299,6 +425,81 @@ impl CPU {
         }
     }
 
+    // Indexed addressing modes first read the address before the carry is added to the high byte
+    // Instructions only reading their operand skip this read if no page is crossed
+    pub fn indexed_dummy_read(&mut self, address: u16, result: u16) {
+        let page_crossed: bool = (result & 0xFF00) != (address & 0xFF00);
+        if page_crossed || !self.require_add_cycle {
+            self.read_bus((address & 0xFF00) | (result & 0x00FF));
+        }
+    }
+
+    // Shift and rotate instructions work on the accumulator without reading the bus
+    pub fn read_operand(&mut self, mode: am, address: u16) -> u8 {
+        match mode {
+            am::Accumulator => self.a,
+            _ => self.read_bus(address)
+        }
+    }
+
+    // Common part of the branch instructions
+    // Takes one more cycle when the branch is taken, and another one if a page is crossed
+    pub fn branch(&mut self, condition: bool) {
+        let address: u16 = self.fetch_address(am::Relative);
+        let offset: i8 = self.read_bus(address) as i8;
+        if condition {
+            let next: u16 = self.pc.wrapping_add(1);
+            // The next opcode is read and discarded
+            self.read_bus(next);
+            let target: u16 = next.wrapping_add(offset as u16);
+            if (target & 0xFF00) != (next & 0xFF00) {
+                // The high byte of the program counter is fixed one cycle later
+                self.read_bus((next & 0xFF00) | (target & 0x00FF));
+            }
+            else if self.irq_pending && !self.previous_irq_pending {
+                // Interrupts aren't polled on the last cycle, the IRQ waits for the next instruction
+                self.irq_pending = false;
+            }
+            self.pc = target.wrapping_sub(1);
+        }
+    }
+
+    // ===== DECIMAL MODE =====
+
+    // A = A+M+C with binary coded decimal values, as done by a NMOS 6502
+    // Z is set from the binary result, N and V are set before the high digit is adjusted
+    pub fn add_decimal(&mut self, data: u8) {
+        let carry: u8 = self.get_flag(Flag::Carry) as u8;
+        let mut lo: u8 = (self.a & 0x0F) + (data & 0x0F) + carry;
+        if lo > 0x09 {
+            lo += 0x06;
+        }
+        let mut hi: u8 = (self.a >> 4) + (data >> 4) + (lo > 0x0F) as u8;
+        self.set_flag(Flag::Zero, self.a.wrapping_add(data).wrapping_add(carry) == 0x00);
+        self.set_flag(Flag::Negative, hi & 0x08 == 0x08);
+        self.set_flag(Flag::Overflow, !(self.a ^ data) & (self.a ^ (hi << 4)) & 0x80 == 0x80);
+        if hi > 0x09 {
+            hi += 0x06;
+        }
+        self.set_flag(Flag::Carry, hi > 0x0F);
+        self.a = (hi << 4) | (lo & 0x0F);
+    }
+
+    // Returns A-M-(1-C) with binary coded decimal values, as done by a NMOS 6502
+    pub fn subtract_decimal(&self, data: u8) -> u8 {
+        let borrow: i16 = 1 - self.get_flag(Flag::Carry) as i16;
+        let mut lo: i16 = (self.a & 0x0F) as i16 - (data & 0x0F) as i16 - borrow;
+        let mut hi: i16 = (self.a >> 4) as i16 - (data >> 4) as i16;
+        if lo < 0 {
+            lo -= 0x06;
+            hi -= 1;
+        }
+        if hi < 0 {
+            hi -= 0x06;
+        }
+        ((hi << 4) | (lo & 0x0F)) as u8
+    }
+
     // ===== INSTRUCTIONS =====
 
     // Add with carry
@@ -306,6 +507,10 @@ impl CPU {
     pub fn adc(&mut self, mode: am) {
         let address: u16 = self.fetch_address(mode);
         let data: u8 = self.read_bus(address);
+        if self.decimal_mode && self.get_flag(Flag::Decimal) {
+            self.add_decimal(data);
+            return;
+        }
         let result: u16 = self.a as u16 + data as u16 + self.get_flag(Flag::Carry) as u16;
         let previous_a: u8 = self.a;
         self.a = result as u8;
@@ -329,7 +534,7 @@ impl CPU {
     // A,Z,C,N = M*2 or M,Z,C,N = M*2
     pub fn asl(&mut self, mode: am) {
         let address: u16 = self.fetch_address(mode);
-        let data: u16 = self.read_bus(address) as u16;
+        let data: u16 = self.read_operand(mode, address) as u16;
         let result: u16;
         match mode {
             am::Accumulator => {
@@ -338,6 +543,7 @@ impl CPU {
             }
             _ => {
                 result = data << 1;
+                self.write_bus(address, data as u8);
                 self.write_bus(address, result as u8);
             }
         }
@@ -348,47 +554,20 @@ impl CPU {
 
     // Branch if carry clear
     // (C = 0) => pc += addr
-    pub fn bcc(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        if !self.get_flag(Flag::Carry) {
-            let data: i8 = self.read_bus(address) as i8;
-            let result: i16 = self.pc as i16 + data as i16;
-            self.cycles += 1;
-            if ((result + 1) as u16 & 0xFF00) != ((self.pc + 1) & 0xFF00) {
-                self.cycles += 1;
-            }
-            self.pc = result as u16;
-        }
+    pub fn bcc(&mut self, _: am) {
+        self.branch(!self.get_flag(Flag::Carry));
     }
 
     // Branch if carry set
     // (C = 1) => pc += addr
-    pub fn bcs(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        if self.get_flag(Flag::Carry) {
-            let data: i8 = self.read_bus(address) as i8;
-            let result: i16 = self.pc as i16 + data as i16;
-            self.cycles += 1;
-            if ((result + 1) as u16 & 0xFF00) != ((self.pc + 1) & 0xFF00) {
-                self.cycles += 1;
-            }
-            self.pc = result as u16;
-        }
+    pub fn bcs(&mut self, _: am) {
+        self.branch(self.get_flag(Flag::Carry));
     }
 
     // Branch if equal
     // (Z = 1) => pc += addr
-    pub fn beq(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        if self.get_flag(Flag::Zero) {
-            let data: i8 = self.read_bus(address) as i8;
-            let result: i16 = self.pc as i16 + data as i16;
-            self.cycles += 1;
-            if ((result + 1) as u16 & 0xFF00) != ((self.pc + 1) & 0xFF00) {
-                self.cycles += 1;
-            }
-            self.pc = result as u16;
-        }
+    pub fn beq(&mut self, _: am) {
+        self.branch(self.get_flag(Flag::Zero));
     }
 
     // Bit test
@@ -404,84 +583,41 @@ impl CPU {
 
     // Branch if minus
     // (N = 1) => pc += addr
-    pub fn bmi(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        if self.get_flag(Flag::Negative) {
-            let data: i8 = self.read_bus(address) as i8;
-            let result: i16 = self.pc as i16 + data as i16;
-            self.cycles += 1;
-            if ((result + 1) as u16 & 0xFF00) != ((self.pc + 1) & 0xFF00) {
-                self.cycles += 1;
-            }
-            self.pc = result as u16;
-        }
+    pub fn bmi(&mut self, _: am) {
+        self.branch(self.get_flag(Flag::Negative));
     }
 
     // Branch if not equal
     // (Z = 0) => pc += addr
-    pub fn bne(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        if !self.get_flag(Flag::Zero) {
-            let data: i8 = self.read_bus(address) as i8;
-            let result: i16 = self.pc as i16 + data as i16;
-            self.cycles += 1;
-            if ((result + 1) as u16 & 0xFF00) != ((self.pc + 1) & 0xFF00) {
-                self.cycles += 1;
-            }
-            self.pc = result as u16;
-        }
+    pub fn bne(&mut self, _: am) {
+        self.branch(!self.get_flag(Flag::Zero));
     }
 
     // Branch if positive
     // (N = 0) => pc += addr
-    pub fn bpl(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        if !self.get_flag(Flag::Negative) {
-            let data: i8 = self.read_bus(address) as i8;
-            let result: i16 = self.pc as i16 + data as i16;
-            self.cycles += 1;
-            if ((result + 1) as u16 & 0xFF00) != ((self.pc + 1) & 0xFF00) {
-                self.cycles += 1;
-            }
-            self.pc = result as u16;
-        }
+    pub fn bpl(&mut self, _: am) {
+        self.branch(!self.get_flag(Flag::Negative));
     }
 
     // Force interrupt
+    // The byte following the opcode is skipped
     pub fn brk(&mut self, _: am) {
-        self.pc += 1;
-        self.set_flag(Flag::Break, true);
-        self.interrupt(Interrupt::IRQ);
+        self.pc += 2;
+        self.enter_interrupt(Interrupt::BRK);
+        // The program counter is increased at the end of the step
+        self.pc -= 1;
     }
 
     // Branch if overflow clear
     // (V = 0) => pc += addr
-    pub fn bvc(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        if !self.get_flag(Flag::Overflow) {
-            let data: i8 = self.read_bus(address) as i8;
-            let result: i16 = self.pc as i16 + data as i16;
-            self.cycles += 1;
-            if ((result + 1) as u16 & 0xFF00) != (self.pc & 0xFF00) {
-                self.cycles += 1;
-            }
-            self.pc = result as u16;
-        }
+    pub fn bvc(&mut self, _: am) {
+        self.branch(!self.get_flag(Flag::Overflow));
     }
 
     // Branch if overflow set
     // (V = 1) => pc += addr
-    pub fn bvs(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        if self.get_flag(Flag::Overflow) {
-            let data: i8 = self.read_bus(address) as i8;
-            let result: i16 = self.pc as i16 + data as i16;
-            self.cycles += 1;
-            if ((result + 1) as u16 & 0xFF00) != (self.pc & 0xFF00) {
-                self.cycles += 1;
-            }
-            self.pc = result as u16;
-        }
+    pub fn bvs(&mut self, _: am) {
+        self.branch(self.get_flag(Flag::Overflow));
     }
 
     // Clear carry flag
@@ -556,6 +692,7 @@ impl CPU {
         else {
             result = 255;
         }
+        self.write_bus(address, data);
         self.write_bus(address, result);
         self.set_flag(Flag::Zero, result == 0);
         self.set_flag(Flag::Negative, (result & 0x80) == 0x80);
@@ -609,6 +746,7 @@ impl CPU {
         else {
             result = 0;
         }
+        self.write_bus(address, data);
         self.write_bus(address, result);
         self.set_flag(Flag::Zero, result == 0);
         self.set_flag(Flag::Negative, (result & 0x80) == 0x80);
@@ -648,11 +786,18 @@ impl CPU {
     }
 
     // Jump to subroutine
-    pub fn jsr(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
+    // The high byte of the address is read after the return address is pushed
+    pub fn jsr(&mut self, _: am) {
+        self.pc += 1;
+        let lo: u8 = self.read_bus(self.pc);
+        // Internal operation on the stack
+        self.read_bus(STACK_OFFSET + self.sp as u16);
+        self.pc += 1;
         self.push_to_stack((self.pc >> 8) as u8);
         self.push_to_stack(self.pc as u8);
-        self.pc = address - 1;
+        let hi: u8 = self.read_bus(self.pc);
+        let address: u16 = lo as u16 + ((hi as u16) << 8);
+        self.pc = address.wrapping_sub(1);
     }
 
     // Load accumulator
@@ -689,7 +834,7 @@ impl CPU {
     // A,C,Z,N = A/2 or M,C,Z,N = M/2
     pub fn lsr(&mut self, mode: am) {
         let address: u16 = self.fetch_address(mode);
-        let data: u16 = self.read_bus(address) as u16;
+        let data: u16 = self.read_operand(mode, address) as u16;
         let result: u8;
         let previous: u8;
         match mode {
@@ -701,6 +846,7 @@ impl CPU {
             _ => {
                 previous = data as u8;
                 result = ((data >> 1) & 0x00FF) as u8;
+                self.write_bus(address, data as u8);
                 self.write_bus(address, result);
             }
         }
@@ -711,7 +857,12 @@ impl CPU {
 
     // No operation
     pub fn nop(&mut self, mode: am) {
-        self.fetch_address(mode);
+        let address: u16 = self.fetch_address(mode);
+        // Unofficial NOPs with an operand still read it
+        if let am::Implicit = mode {
+            return;
+        }
+        self.read_bus(address);
     }
 
     // Logical inclusive or
@@ -739,6 +890,7 @@ impl CPU {
     // Pull accumulator
     // A <= stack
     pub fn pla(&mut self, _: am) {
+        self.read_bus(STACK_OFFSET + self.sp as u16);
         self.a = self.pop_from_stack();
         self.set_flag(Flag::Zero, self.a == 0x00);
         self.set_flag(Flag::Negative, self.a & 0x80 == 0x80);
@@ -747,6 +899,7 @@ impl CPU {
     // Pull processor status
     // status <= stack
     pub fn plp(&mut self, _: am) {
+        self.read_bus(STACK_OFFSET + self.sp as u16);
         let status: u8 = self.pop_from_stack();
         self.set_flag(Flag::Carry, status & (Flag::Carry as u8) == Flag::Carry as u8);
         self.set_flag(Flag::Zero, status & (Flag::Zero as u8) == Flag::Zero as u8);
@@ -760,7 +913,7 @@ impl CPU {
     // Rotate left
     pub fn rol(&mut self, mode: am) {
         let address: u16 = self.fetch_address(mode);
-        let data: u8 = self.read_bus(address);
+        let data: u8 = self.read_operand(mode, address);
         let result: u8;
         let previous_value: u8;
         match mode {
@@ -772,6 +925,7 @@ impl CPU {
             _ => {
                 previous_value = data;
                 result = (data << 1) + (self.get_flag(Flag::Carry) as u8);
+                self.write_bus(address, data);
                 self.write_bus(address, result);
             }
         }
@@ -783,7 +937,7 @@ impl CPU {
     // Rotate right
     pub fn ror(&mut self, mode: am) {
         let address: u16 = self.fetch_address(mode);
-        let data: u8 = self.read_bus(address);
+        let data: u8 = self.read_operand(mode, address);
         let result: u8;
         let previous_value: u8;
         match mode {
@@ -795,6 +949,7 @@ impl CPU {
             _ => {
                 previous_value = data;
                 result = (data >> 1) + (self.get_flag(Flag::Carry) as u8 * 0x80);
+                self.write_bus(address, data);
                 self.write_bus(address, result);
             }
         }
@@ -806,6 +961,7 @@ impl CPU {
     // Return from interrupt
     // status <= stack, pc <= stack
     pub fn rti(&mut self, _: am) {
+        self.read_bus(STACK_OFFSET + self.sp as u16);
         let status: u8 = self.pop_from_stack();
         self.set_flag(Flag::Carry, status & (Flag::Carry as u8) == Flag::Carry as u8);
         self.set_flag(Flag::Zero, status & (Flag::Zero as u8) == Flag::Zero as u8);
@@ -815,13 +971,16 @@ impl CPU {
         self.set_flag(Flag::Overflow, status & (Flag::Overflow as u8) == Flag::Overflow as u8);
         self.set_flag(Flag::Negative, status & (Flag::Negative as u8) == Flag::Negative as u8);
         let address: u16 = self.pop_from_stack() as u16 + ((self.pop_from_stack() as u16) << 8);
-        self.pc = address - 1;
+        self.pc = address.wrapping_sub(1);
     }
 
     // Return from subroutine
     // pc - 1 <= stack
     pub fn rts(&mut self, _: am) {
+        self.read_bus(STACK_OFFSET + self.sp as u16);
         let address: u16 = self.pop_from_stack() as u16 + ((self.pop_from_stack() as u16) << 8);
+        // The program counter is increased from the pulled address
+        self.read_bus(address);
         self.pc = address;
     }
 
@@ -833,12 +992,20 @@ impl CPU {
         let data: u8 = original_data ^ 0xFF; // Converts data into a negative value + 1
         let result: u16 = self.a as u16 + data as u16 + self.get_flag(Flag::Carry) as u16;
         let previous_a: u8 = self.a;
+        // The flags of a decimal substraction are the ones of the binary substraction
+        let decimal_result: Option<u8> = match self.decimal_mode && self.get_flag(Flag::Decimal) {
+            true => Some(self.subtract_decimal(original_data)),
+            false => None
+        };
         self.a = result as u8;
         self.set_flag(Flag::Carry, (result & 0x0100) == 0x0100);
         self.set_flag(Flag::Zero, self.a == 0x00);
         self.set_flag(Flag::Negative, (self.a & 0x80) == 0x80);
         // !!((A ^ Val) & (A ^ result) & 0x80) Only this, taken from Nintendulator could pass the nestest
         self.set_flag(Flag::Overflow, !!((previous_a ^ original_data) & (previous_a ^ (result as u8)) & 0x80) == 0x80);
+        if let Some(value) = decimal_result {
+            self.a = value;
+        }
     }
 
     // Set carry flag
@@ -939,14 +1106,12 @@ impl CPU {
         self.set_flag(Flag::Carry, self.a & 0x80 == 0x80);
     }
 
-    // Same as AND, with x transfered to a
-    // A = X
-    // A,Z,N = A & M
+    // Unstable, the accumulator is ORed with a constant first
+    // A,Z,N = (A | CONST) & X & M
     pub fn ane(&mut self, mode: am) {
-        self.a = self.x;
         let address: u16 = self.fetch_address(mode);
         let data: u8 = self.read_bus(address);
-        self.a &= data;
+        self.a = (self.a | ANE_MAGIC_CONSTANT) & self.x & data;
         self.set_flag(Flag::Zero, self.a == 0);
         self.set_flag(Flag::Negative, self.a & 0x80 == 0x80);
     }
@@ -981,6 +1146,7 @@ impl CPU {
     pub fn dcp(&mut self, mode: am) {
         let address: u16 = self.fetch_address(mode);
         let mut data: u8 = self.read_bus(address);
+        self.write_bus(address, data);
         if data != 0 {
             data -= 1;
         }
@@ -988,16 +1154,10 @@ impl CPU {
             data = 255;
         }
         self.write_bus(address, data);
-        let result: u8;
-        if self.a > data {
-            result = self.a - data;
-        }
-        else {
-            result = data - self.a;
-        }
+        let result: u8 = self.a.wrapping_sub(data);
         self.set_flag(Flag::Zero, result == 0x00);
         self.set_flag(Flag::Carry, self.a >= data);
-        self.set_flag(Flag::Negative, (self.a > data) || ((self.a == 0) && ((data & 0x80) == 0x80)));
+        self.set_flag(Flag::Negative, (result & 0x80) == 0x80);
     }
 
     // Same as INC + SBC
@@ -1013,6 +1173,7 @@ impl CPU {
         else {
             inc_data = 0;
         }
+        self.write_bus(address, original_data);
         self.write_bus(address, inc_data);
         let data: u8 = inc_data ^ 0xFF; // Converts data into a negative value + 1
         let result: u16 = self.a as u16 + data as u16 + self.get_flag(Flag::Carry) as u16;
@@ -1048,12 +1209,12 @@ impl CPU {
         self.set_flag(Flag::Negative, (data & 0x80) == 0x80);
     }
 
-    // Same as ORA #$EE + AND + TXA
+    // Unstable, the accumulator is ORed with a constant first
+    // A,X,Z,N = (A | CONST) & M
     pub fn lxa(&mut self, mode: am) {
         let address: u16 = self.fetch_address(mode);
         let data: u8 = self.read_bus(address);
-        self.a |= self.read_bus(0x00EE);
-        self.a &= data;
+        self.a = (self.a | LXA_MAGIC_CONSTANT) & data;
         self.x = self.a;
         self.set_flag(Flag::Zero, self.a == 0x00);
         self.set_flag(Flag::Negative, (self.a & 0x80) == 0x80);
@@ -1064,6 +1225,7 @@ impl CPU {
         let address: u16 = self.fetch_address(mode);
         let data: u8 = self.read_bus(address);
         let result: u8 = (data << 1) + (self.get_flag(Flag::Carry) as u8);
+        self.write_bus(address, data);
         self.write_bus(address, result);
         self.a &= result;
         self.set_flag(Flag::Carry, (data & 0x80) == 0x80);
@@ -1076,6 +1238,7 @@ impl CPU {
         let address: u16 = self.fetch_address(mode);
         let data: u8 = self.read_bus(address);
         let ror_data: u8 = (data >> 1) + (self.get_flag(Flag::Carry) as u8 * 0x80);
+        self.write_bus(address, data);
         self.write_bus(address, ror_data);
         let carry: u8 = data & 0x01;
         let result: u16 = self.a as u16 + ror_data as u16 + carry as u16;
@@ -1084,7 +1247,7 @@ impl CPU {
         self.set_flag(Flag::Carry, (result & 0x0100) == 0x0100);
         self.set_flag(Flag::Zero, self.a == 0x00);
         self.set_flag(Flag::Negative, (self.a & 0x80) == 0x80);
-        self.set_flag(Flag::Overflow, !(previous_a ^ ror_data) & (previous_a ^ (result as u8)) == 0x80);
+        self.set_flag(Flag::Overflow, (!(previous_a ^ ror_data) & (previous_a ^ (result as u8)) & 0x80) == 0x80);
     }
 
     // M = A & X
@@ -1113,32 +1276,43 @@ impl CPU {
 
     // M = A&X&(h[M]+1)
     pub fn sha(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        let result: u8 = self.a & self.x & (((address & 0xFF00) >> 8) + 1) as u8;
-        self.write_bus(address, result);
+        self.store_and_high_byte(mode, self.a & self.x);
     }
 
     // SP = A&X
     // M = A&X&(h[M]+1)
     pub fn shs(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
         self.sp = self.a & self.x;
-        let result: u8 = self.a & self.x & (((address & 0xFF00) >> 8) + 1) as u8;
-        self.write_bus(address, result);
+        self.store_and_high_byte(mode, self.a & self.x);
     }
 
     // M = X&(h[M]+1)
     pub fn shx(&mut self, mode: am) {
-        let address: u16 = self.fetch_address(mode);
-        let result: u8 = self.x & (((address & 0xFF00) >> 8) + 1) as u8;
-        self.write_bus(address, result);
+        self.store_and_high_byte(mode, self.x);
     }
 
     // M = Y&(h[M]+1)
     pub fn shy(&mut self, mode: am) {
+        self.store_and_high_byte(mode, self.y);
+    }
+
+    // Common part of SHA, SHS, SHX and SHY
+    // The value is ANDed with the high byte of the address before indexing, plus one
+    // When a page is crossed, the stored value also replaces the high byte of the address
+    pub fn store_and_high_byte(&mut self, mode: am, value: u8) {
+        let index: u8 = match mode {
+            am::AbsoluteX => self.x,
+            _ => self.y
+        };
         let address: u16 = self.fetch_address(mode);
-        let result: u8 = self.y & (((address & 0xFF00) >> 8) + 1) as u8;
-        self.write_bus(address, result);
+        let base_address: u16 = address.wrapping_sub(index as u16);
+        let result: u8 = value & ((base_address >> 8) as u8).wrapping_add(1);
+        if (base_address & 0xFF00) != (address & 0xFF00) {
+            self.write_bus(((result as u16) << 8) | (address & 0x00FF), result);
+        }
+        else {
+            self.write_bus(address, result);
+        }
     }
 
     // Same as ASL + ORA
@@ -1147,6 +1321,7 @@ impl CPU {
         let data: u16 = self.read_bus(address) as u16;
         let result: u16;
         result = (data as u16) << 1;
+        self.write_bus(address, data as u8);
         self.write_bus(address, result as u8);
         self.a |= result as u8;
         self.set_flag(Flag::Carry, (result & 0xFF00) > 0);
@@ -1159,6 +1334,7 @@ impl CPU {
         let address: u16 = self.fetch_address(mode);
         let data: u16 = self.read_bus(address) as u16;
         let result: u8 = ((data >> 1) & 0x00FF) as u8;
+        self.write_bus(address, data as u8);
         self.write_bus(address, result);
         self.a ^= result;
         self.set_flag(Flag::Carry, (data & 0x01) > 0);
@@ -1166,15 +1342,28 @@ impl CPU {
         self.set_flag(Flag::Negative, (self.a & 0x80) == 0x80);
     }
 
-    // Used for unvalid operation codes
-    pub fn err(&mut self, _: am) {
-        panic!("Encountered an unvalid opcode !");
+    // Halts the CPU, the program counter stays on the opcode
+    pub fn jam(&mut self, _: am) {
+        self.halted = true;
+        self.pc = self.pc.wrapping_sub(1);
     }
 
     // ===== DEBUGGING =====
 
     #[allow(dead_code)]
     pub fn display_cpu_log(&self, opcode: u8) {
+        let log: String = self.annotate_log(self.nintendulator_log(opcode));
+        if let Some(sender) = self.o_log_sender.as_ref() {
+            // The receiver may have stopped listening, the logs are not needed anymore then
+            let _ = sender.send(log.clone());
+        }
+        if self.display_logs {
+            println!("{}",log);
+        }
+    }
+
+    // Log of the instruction at the PC in the format of Nintendulator, used by the nestest log
+    pub fn nintendulator_log(&self, opcode: u8) -> String {
         let mut instruction_and_parameters_str: String = String::from(format!("{:02X} ",opcode));
         let mut instruction_parameters: Vec<u8> = vec![];
         for i in 0..INSTRUCTIONS[opcode as usize].bytes - 1 {
@@ -1188,31 +1377,120 @@ impl CPU {
             instruction_and_parameters_str.push_str(" ");
         }
         let cpu_log: String = String::from(format!("{:04X}  {} {}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X}"
-            ,self.pc,instruction_and_parameters_str,self.dissassemble(opcode, instruction_parameters),
+            ,self.pc,instruction_and_parameters_str,self.dissassemble(self.pc, opcode, instruction_parameters),
             self.a,self.x,self.y,self.p,self.sp));
         
-        let scanline: u16 = self.p_bus.lock().unwrap().p_ppu.lock().unwrap().scanline;
+        let (scanline, cycle): (u16, u16) = self.bus.ppu_position();
         let mut scanline_str: String = scanline.to_string();
         while scanline_str.len() < 3 {
             scanline_str = String::from(format!(" {}",scanline_str));
         }
-        let cycle: u16 = self.p_bus.lock().unwrap().p_ppu.lock().unwrap().cycles;
         let mut cycle_str: String = cycle.to_string();
         while cycle_str.len() < 3 {
             cycle_str = String::from(format!(" {}",cycle_str));
         }
         let ppu_log: String = String::from(format!("PPU:{},{}",scanline_str,cycle_str));
 
-        println!("{} {} CYC:{}",cpu_log,ppu_log,self.total_clock);
+        format!("{} {} CYC:{}",cpu_log,ppu_log,self.total_clock)
+    }
+
+    // Adds the comment of the instruction at the PC to its log
+    // The label of the instruction is on its own line, before it
+    pub fn annotate_log(&self, mut log: String) -> String {
+        if let Some(symbol) = self.symbol(self.pc) {
+            if !symbol.comment.is_empty() {
+                log = format!("{} ; {}",log,symbol.comment);
+            }
+            if !symbol.name.is_empty() {
+                log = format!("{}:\n{}",symbol.name,log);
+            }
+        }
+        log
+    }
+
+    // ===== CODE DATA LOGGER =====
+
+    // Marks the bytes of the instruction at the PC as code, and the byte read by the instruction as data
+    pub fn log_code_data(&self) {
+        let opcode: u8 = self.read_only_bus(self.pc);
+        let instruction: &CpuInstruction = &INSTRUCTIONS[opcode as usize];
+        let parameters: Vec<u8> = (1..instruction.bytes as u16).map(|offset| self.read_only_bus(self.pc.wrapping_add(offset))).collect();
+        let mut accesses: Vec<(u16, u8)> = (0..instruction.bytes as u16).map(|offset| (self.pc.wrapping_add(offset), PRG_CODE)).collect();
+        match (instruction.name, instruction.adressing_mode, self.operand_address(instruction.adressing_mode, &parameters)) {
+            ("JMP", am::Indirect, Some(target)) => accesses.push((target, PRG_CODE | PRG_INDIRECT_CODE)),
+            // The jumps, the stores and the NOPs don't read their operand
+            ("JMP" | "JSR" | "STA" | "STX" | "STY" | "SAX" | "SHA" | "SHS" | "SHX" | "SHY" | "NOP", _, _) => (),
+            (_, am::IndirectX | am::IndirectY, Some(address)) => accesses.push((address, PRG_DATA | PRG_INDIRECT_DATA)),
+            (_, _, Some(address)) => accesses.push((address, PRG_DATA)),
+            (_, _, None) => ()
+        }
+        // The bus isn't locked while the logger is
+        let offsets: Vec<(usize, u16, u8)> = accesses.iter()
+            .filter_map(|(address, flags)| self.bus.prg_rom_offset(*address).map(|offset| (offset, *address, *flags)))
+            .collect();
+        if let Some(p_code_data_logger) = self.o_p_code_data_logger.as_ref() {
+            let mut code_data_logger = p_code_data_logger.lock().unwrap();
+            for (offset, address, flags) in offsets {
+                code_data_logger.log_prg(offset, address, flags);
+            }
+        }
+    }
+
+    // Address of the operand of the instruction at the PC, computed from the current registers and memory
+    // None for the modes without a memory operand
+    pub fn operand_address(&self, mode: am, parameters: &[u8]) -> Option<u16> {
+        let absolute: u16 = match parameters.len() {
+            2 => parameters[0] as u16 + ((parameters[1] as u16) << 8),
+            1 => parameters[0] as u16,
+            _ => 0
+        };
+        let read_pointer = |pointer: u8| -> u16 {
+            self.read_only_bus(pointer as u16) as u16 + ((self.read_only_bus(pointer.wrapping_add(1) as u16) as u16) << 8)
+        };
+        match mode {
+            am::ZeroPage | am::Absolute => Some(absolute),
+            am::ZeroPageX => Some((absolute + self.x as u16) % 0x100),
+            am::ZeroPageY => Some((absolute + self.y as u16) % 0x100),
+            am::AbsoluteX => Some(absolute.wrapping_add(self.x as u16)),
+            am::AbsoluteY => Some(absolute.wrapping_add(self.y as u16)),
+            am::Indirect => {
+                // Hardware bug, the pointer doesn't cross pages
+                let hi_address: u16 = (absolute & 0xFF00) | (absolute.wrapping_add(1) & 0x00FF);
+                Some(self.read_only_bus(absolute) as u16 + ((self.read_only_bus(hi_address) as u16) << 8))
+            },
+            am::IndirectX => Some(read_pointer((absolute as u8).wrapping_add(self.x))),
+            am::IndirectY => Some(read_pointer(absolute as u8).wrapping_add(self.y as u16)),
+            _ => None
+        }
     }
 
     #[allow(dead_code)]
     pub fn read_only_bus(&self, address: u16) -> u8 {
-        self.p_bus.lock().unwrap().read_only(address)
+        self.bus.peek(address)
+    }
+
+    // Symbol of an address, the symbols of the ROM are found with the banks currently selected
+    pub fn symbol(&self, address: u16) -> Option<&Symbol> {
+        let symbols: &SymbolTable = self.o_symbols.as_ref()?;
+        let o_prg_rom_offset: Option<usize> = match address {
+            0x0000..=0x401F => None,
+            _ => self.bus.prg_rom_offset(address)
+        };
+        symbols.lookup(address, o_prg_rom_offset)
     }
 
+    // Name of the symbol of an operand, or its hexadecimal value when it has none
+    fn operand_name(&self, address: u16, hexadecimal: String) -> String {
+        match self.symbol(address) {
+            Some(symbol) if !symbol.name.is_empty() => symbol.name.clone(),
+            _ => hexadecimal
+        }
+    }
+
+    // Disassembles the instruction at the given address
+    // The effective addresses and the values are computed from the current registers and memory
     #[allow(dead_code)]
-    pub fn dissassemble(&self, opcode: u8, parameters: Vec<u8>) -> String {
+    pub fn dissassemble(&self, instruction_address: u16, opcode: u8, parameters: Vec<u8>) -> String {
         let mut dissassembly: String = String::from(INSTRUCTIONS[opcode as usize].name);
         dissassembly.push_str(" ");
         match INSTRUCTIONS[opcode as usize].adressing_mode {
@@ -1225,41 +1503,45 @@ impl CPU {
             am::Immediate => dissassembly.push_str(&format!("#${:02X}",parameters[0])),
             am::ZeroPage => {
                 let value: u8 = self.read_only_bus(parameters[0] as u16);
-                dissassembly.push_str(&format!("${:02X} = {:02X}",parameters[0],value));
+                dissassembly.push_str(&format!("{} = {:02X}",self.operand_name(parameters[0] as u16, format!("${:02X}",parameters[0])),value));
             },
             am::ZeroPageX => {
                 let address: u16 = (parameters[0] as u16 + self.x as u16) % 0x100;
                 let value: u8 = self.read_only_bus(address);
-                dissassembly.push_str(&format!("${:02X},X @ {:02X} = {:02X}",parameters[0],address,value));
+                dissassembly.push_str(&format!("{},X @ {:02X} = {:02X}",self.operand_name(parameters[0] as u16, format!("${:02X}",parameters[0])),address,value));
             },
             am::ZeroPageY => {
                 let address: u16 = (parameters[0] as u16 + self.y as u16) % 0x100;
                 let value: u8 = self.read_only_bus(address);
-                dissassembly.push_str(&format!("${:02X},Y @ {:02X} = {:02X}",parameters[0],address,value));
+                dissassembly.push_str(&format!("{},Y @ {:02X} = {:02X}",self.operand_name(parameters[0] as u16, format!("${:02X}",parameters[0])),address,value));
             },
             am::Relative => {
-                dissassembly.push_str(&format!("${:04X}",(self.pc as i16) + 2 + ((parameters[0] as i8) as i16)));
+                let address: u16 = instruction_address.wrapping_add(2).wrapping_add((parameters[0] as i8) as u16);
+                dissassembly.push_str(&self.operand_name(address, format!("${:04X}",address)));
             },
             am::Absolute => {
                 let address: u16 = parameters[0] as u16 + ((parameters[1] as u16) << 8) as u16;
                 // Don't print value if it's a JMP / JSR
+                let name: String = self.operand_name(address, format!("${:04X}",address));
                 if (opcode == 0x4C) || (opcode == 0x20) {
-                    dissassembly.push_str(&format!("${:02X}{:02X}",parameters[1],parameters[0]));
+                    dissassembly.push_str(&name);
                 }
                 else {
                     let value: u8 = self.read_only_bus(address);
-                    dissassembly.push_str(&format!("${:02X}{:02X} = {:02X}",parameters[1],parameters[0],value));
+                    dissassembly.push_str(&format!("{} = {:02X}",name,value));
                 }
             },
             am::AbsoluteX => {
                 let address: u16 = (parameters[0] as u32 + ((parameters[1] as u32) << 8) + self.x as u32) as u16;
                 let value: u8 = self.read_only_bus(address);
-                dissassembly.push_str(&format!("${:02X}{:02X},X @ {:04X} = {:02X}",parameters[1],parameters[0],address,value));
+                let base: u16 = parameters[0] as u16 + ((parameters[1] as u16) << 8);
+                dissassembly.push_str(&format!("{},X @ {:04X} = {:02X}",self.operand_name(base, format!("${:04X}",base)),address,value));
             },
             am::AbsoluteY => {
                 let address: u16 = ((parameters[0] as u32 + ((parameters[1] as u32) << 8) + self.y as u32) % 0x10000) as u16;
                 let value: u8 = self.read_only_bus(address);
-                dissassembly.push_str(&format!("${:02X}{:02X},Y @ {:04X} = {:02X}",parameters[1],parameters[0],address,value));
+                let base: u16 = parameters[0] as u16 + ((parameters[1] as u16) << 8);
+                dissassembly.push_str(&format!("{},Y @ {:04X} = {:02X}",self.operand_name(base, format!("${:04X}",base)),address,value));
             },
             am::Indirect => {
                 let ptr: u16 = parameters[0] as u16 + ((parameters[1] as u16) << 8);
@@ -1275,14 +1557,14 @@ impl CPU {
                     address_hi = self.read_only_bus(ptr + 1);
                 }
                 let address: u16 = address_lo as u16 + ((address_hi as u16) << 8);
-                dissassembly.push_str(&format!("(${:02X}{:02X}) = {:04X}",parameters[1],parameters[0],address));
+                dissassembly.push_str(&format!("({}) = {:04X}",self.operand_name(ptr, format!("${:04X}",ptr)),address));
             },
             am::IndirectX => {
                 let ptr_lo: u16 = (parameters[0] as u16 + self.x as u16) % 0x100;
                 let ptr_hi: u16 = (ptr_lo + 1) % 0x100;
                 let address: u16 = self.read_only_bus(ptr_lo) as u16 + ((self.read_only_bus(ptr_hi) as u16) << 8);
                 let value: u8 = self.read_only_bus(address);
-                dissassembly.push_str(&format!("(${:02X},X) @ {:02X} = {:04X} = {:02X}",parameters[0],ptr_lo,address,value));
+                dissassembly.push_str(&format!("({},X) @ {:02X} = {:04X} = {:02X}",self.operand_name(parameters[0] as u16, format!("${:02X}",parameters[0])),ptr_lo,address,value));
             },
             am::IndirectY => {
                 let ptr_lo: u16 = (parameters[0] as u16) % 0x100;
@@ -1290,7 +1572,7 @@ impl CPU {
                 let address_ptr: u16 = self.read_only_bus(ptr_lo) as u16 + ((self.read_only_bus(ptr_hi) as u16) << 8);
                 let address: u16 = ((address_ptr as u32 + self.y as u32) % 0x10000) as u16;
                 let value: u8 = self.read_only_bus(address);
-                dissassembly.push_str(&format!("(${:02X}),Y = {:04X} @ {:04X} = {:02X}",parameters[0],address_ptr,address,value));
+                dissassembly.push_str(&format!("({}),Y = {:04X} @ {:04X} = {:02X}",self.operand_name(parameters[0] as u16, format!("${:02X}",parameters[0])),address_ptr,address,value));
             },
             am::NoMode => {
                 panic!("No mode specified when trying to fetch data !");
@@ -1301,4 +1583,740 @@ impl CPU {
         }
         dissassembly
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::{mapper::{Mapper, Mirroring}, mapper_000::Mapper0};
+    use crate::input::{controller::ControllerInput, device::InputState};
+    use crate::ppu::ppu::PPU;
+
+    const PROGRAM_ADDRESS: u16 = 0x0200;
+
+    // Klaus Dormann's 6502 functional test, loaded as a 64KB image
+    // The test is skipped when the path of 6502_functional_test.bin isn't given
+    const FUNCTIONAL_TEST_VARIABLE: &str = "KLAUS_FUNCTIONAL_TEST";
+    const FUNCTIONAL_TEST_START: u16 = 0x0400;
+    // The test loops on this address when every test passed, on the failing test otherwise
+    const FUNCTIONAL_TEST_SUCCESS: u16 = 0x3469;
+
+    // 64KB of RAM, as seen by a stock 6502
+    struct FlatBus {
+        data: Vec<u8>
+    }
+
+    impl CpuBus for FlatBus {
+        fn read(&mut self, address: u16) -> u8 {
+            self.data[address as usize]
+        }
+
+        fn write(&mut self, address: u16, value: u8) {
+            self.data[address as usize] = value;
+        }
+
+        fn peek(&self, address: u16) -> u8 {
+            self.data[address as usize]
+        }
+    }
+
+    // 64KB of RAM, with NMI and IRQ lines asserted from a given cycle
+    // The first cycle of the test is cycle 1, a line asserted on a cycle is sampled at its end
+    struct InterruptBus {
+        data: Vec<u8>,
+        cycle: u64,
+        o_nmi_cycle: Option<u64>,
+        o_irq_cycle: Option<u64>
+    }
+
+    impl CpuBus for InterruptBus {
+        fn read(&mut self, address: u16) -> u8 {
+            self.data[address as usize]
+        }
+
+        fn write(&mut self, address: u16, value: u8) {
+            self.data[address as usize] = value;
+        }
+
+        fn peek(&self, address: u16) -> u8 {
+            self.data[address as usize]
+        }
+
+        fn tick(&mut self) {
+            self.cycle += 1;
+        }
+
+        fn interrupt_lines(&self) -> (bool, bool) {
+            (
+                self.o_nmi_cycle.is_some_and(|cycle| self.cycle >= cycle),
+                self.o_irq_cycle.is_some_and(|cycle| self.cycle >= cycle)
+            )
+        }
+    }
+
+    // Handlers of the interrupts, filled with NOPs
+    const IRQ_HANDLER: u16 = 0x0300;
+    const NMI_HANDLER: u16 = 0x0400;
+
+    // Creates a CPU running the given program from RAM, with the interrupt lines driven on the given cycles
+    fn create_interrupt_cpu(program: &[u8], p: u8, o_nmi_cycle: Option<u64>, o_irq_cycle: Option<u64>) -> CPU<InterruptBus> {
+        let mut data: Vec<u8> = vec![0;0x10000];
+        let start: usize = PROGRAM_ADDRESS as usize;
+        data[start..start + program.len()].copy_from_slice(program);
+        data[IRQ_HANDLER as usize..IRQ_HANDLER as usize + 0x10].fill(0xEA);
+        data[NMI_HANDLER as usize..NMI_HANDLER as usize + 0x10].fill(0xEA);
+        data[0xFFFA..0xFFFC].copy_from_slice(&NMI_HANDLER.to_le_bytes());
+        data[0xFFFE..].copy_from_slice(&IRQ_HANDLER.to_le_bytes());
+        let mut cpu: CPU<InterruptBus> = CPU::new(InterruptBus { data, cycle: 0, o_nmi_cycle, o_irq_cycle }, false);
+        cpu.pc = PROGRAM_ADDRESS;
+        cpu.sp = 0xFD;
+        cpu.p = p;
+        cpu
+    }
+
+    // Returns the program counter and the status register pushed by the last interrupt, when the stack started at $FD
+    fn pushed_state(cpu: &CPU<InterruptBus>) -> (u16, u8) {
+        let pc: u16 = u16::from_le_bytes([cpu.bus.data[0x01FC], cpu.bus.data[0x01FD]]);
+        (pc, cpu.bus.data[0x01FB])
+    }
+
+    // 64KB of RAM recording every access, a read as (address, None) and a write as (address, Some(value))
+    struct AccessBus {
+        data: Vec<u8>,
+        accesses: Vec<(u16, Option<u8>)>
+    }
+
+    impl CpuBus for AccessBus {
+        fn read(&mut self, address: u16) -> u8 {
+            self.accesses.push((address, None));
+            self.data[address as usize]
+        }
+
+        fn write(&mut self, address: u16, value: u8) {
+            self.accesses.push((address, Some(value)));
+            self.data[address as usize] = value;
+        }
+
+        fn peek(&self, address: u16) -> u8 {
+            self.data[address as usize]
+        }
+    }
+
+    // Runs the first instruction of the given program and returns its bus accesses, one per cycle
+    fn instruction_accesses(program: &[u8], x: u8, y: u8, data: &[(u16, u8)]) -> Vec<(u16, Option<u8>)> {
+        let mut memory: Vec<u8> = vec![0;0x10000];
+        let start: usize = PROGRAM_ADDRESS as usize;
+        memory[start..start + program.len()].copy_from_slice(program);
+        for (address, value) in data.iter() {
+            memory[*address as usize] = *value;
+        }
+        let mut cpu: CPU<AccessBus> = CPU::new(AccessBus { data: memory, accesses: Vec::new() }, false);
+        cpu.pc = PROGRAM_ADDRESS;
+        cpu.sp = 0xFD;
+        cpu.p = 0x24;
+        cpu.x = x;
+        cpu.y = y;
+        assert_eq!(run_instruction(&mut cpu), cpu.bus.accesses.len() as u64);
+        cpu.bus.accesses
+    }
+
+    // Creates a NMOS 6502 running the given program from RAM
+    fn create_6502(program: &[u8]) -> CPU<FlatBus> {
+        let mut data: Vec<u8> = vec![0;0x10000];
+        let start: usize = PROGRAM_ADDRESS as usize;
+        data[start..start + program.len()].copy_from_slice(program);
+        let mut cpu: CPU<FlatBus> = CPU::new(FlatBus { data }, false);
+        cpu.decimal_mode = true;
+        cpu.pc = PROGRAM_ADDRESS;
+        cpu.p = 0x24;
+        cpu
+    }
+
+    // Creates a CPU running the given program from RAM, with an empty cartridge
+    fn create_cpu(program: &[u8]) -> CPU {
+        let mapper: Box<dyn Mapper> = Box::new(Mapper0::new(vec![[0;16*1024]], vec![[0;8*1024]], Mirroring::Vertical));
+        let p_ppu: Arc<Mutex<PPU>> = Arc::new(Mutex::new(PPU::new(None)));
+        p_ppu.lock().unwrap().ppu_bus.o_p_mapper = Some(mapper.clone());
+        let p_bus: Arc<Mutex<Bus>> = Arc::new(Mutex::new(Bus::new(p_ppu)));
+        p_bus.lock().unwrap().o_p_mapper = Some(mapper);
+        let start: usize = PROGRAM_ADDRESS as usize;
+        p_bus.lock().unwrap().data[start..start + program.len()].copy_from_slice(program);
+        let mut cpu: CPU = CPU::new(p_bus, false);
+        cpu.pc = PROGRAM_ADDRESS;
+        cpu.p = 0x24;
+        cpu
+    }
+
+    fn write_ram(cpu: &CPU, address: u16, value: u8) {
+        cpu.bus.lock().unwrap().data[address as usize] = value;
+    }
+
+    // Runs one instruction and returns the number of cycles it took
+    fn run_instruction<B: CpuBus>(cpu: &mut CPU<B>) -> u64 {
+        let start: u64 = cpu.total_clock;
+        cpu.step();
+        cpu.total_clock - start
+    }
+
+    #[test]
+    fn slo() {
+        let mut cpu: CPU = create_cpu(&[0x07, 0x10]);
+        write_ram(&cpu, 0x10, 0x81);
+        cpu.a = 0x01;
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x10), 0x02);
+        assert_eq!(cpu.a, 0x03);
+        assert!(cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn rla() {
+        let mut cpu: CPU = create_cpu(&[0x27, 0x10]);
+        write_ram(&cpu, 0x10, 0x81);
+        cpu.a = 0xFF;
+        cpu.set_flag(Flag::Carry, true);
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x10), 0x03);
+        assert_eq!(cpu.a, 0x03);
+        assert!(cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn sre() {
+        let mut cpu: CPU = create_cpu(&[0x47, 0x10]);
+        write_ram(&cpu, 0x10, 0x03);
+        cpu.a = 0x10;
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x10), 0x01);
+        assert_eq!(cpu.a, 0x11);
+        assert!(cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn rra() {
+        let mut cpu: CPU = create_cpu(&[0x67, 0x10]);
+        write_ram(&cpu, 0x10, 0x02);
+        cpu.a = 0x7F;
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x10), 0x01);
+        assert_eq!(cpu.a, 0x80);
+        assert!(cpu.get_flag(Flag::Overflow));
+        assert!(cpu.get_flag(Flag::Negative));
+        assert!(!cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn sax() {
+        let mut cpu: CPU = create_cpu(&[0x87, 0x10]);
+        cpu.a = 0xF0;
+        cpu.x = 0x3C;
+        assert_eq!(run_instruction(&mut cpu), 3);
+        assert_eq!(cpu.read_only_bus(0x10), 0x30);
+    }
+
+    #[test]
+    fn lax() {
+        let mut cpu: CPU = create_cpu(&[0xA7, 0x10]);
+        write_ram(&cpu, 0x10, 0x80);
+        assert_eq!(run_instruction(&mut cpu), 3);
+        assert_eq!((cpu.a, cpu.x), (0x80, 0x80));
+        assert!(cpu.get_flag(Flag::Negative));
+    }
+
+    #[test]
+    fn dcp() {
+        let mut cpu: CPU = create_cpu(&[0xC7, 0x10, 0xC7, 0x10]);
+        write_ram(&cpu, 0x10, 0x02);
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x10), 0x01);
+        assert!(cpu.get_flag(Flag::Negative));
+        assert!(!cpu.get_flag(Flag::Carry));
+        assert!(!cpu.get_flag(Flag::Zero));
+        run_instruction(&mut cpu);
+        assert!(!cpu.get_flag(Flag::Negative));
+        assert!(cpu.get_flag(Flag::Carry));
+        assert!(cpu.get_flag(Flag::Zero));
+    }
+
+    #[test]
+    fn isb() {
+        let mut cpu: CPU = create_cpu(&[0xE7, 0x10]);
+        write_ram(&cpu, 0x10, 0x0F);
+        cpu.a = 0x20;
+        cpu.set_flag(Flag::Carry, true);
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x10), 0x10);
+        assert_eq!(cpu.a, 0x10);
+        assert!(cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn anc() {
+        let mut cpu: CPU = create_cpu(&[0x0B, 0x80]);
+        cpu.a = 0xFF;
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!(cpu.a, 0x80);
+        assert!(cpu.get_flag(Flag::Carry));
+        assert!(cpu.get_flag(Flag::Negative));
+    }
+
+    #[test]
+    fn asr() {
+        let mut cpu: CPU = create_cpu(&[0x4B, 0x03]);
+        cpu.a = 0xFF;
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!(cpu.a, 0x01);
+        assert!(cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn arr() {
+        let mut cpu: CPU = create_cpu(&[0x6B, 0xFF]);
+        cpu.a = 0xC0;
+        cpu.set_flag(Flag::Carry, true);
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!(cpu.a, 0xE0);
+        assert!(cpu.get_flag(Flag::Carry));
+        assert!(!cpu.get_flag(Flag::Overflow));
+        assert!(cpu.get_flag(Flag::Negative));
+    }
+
+    #[test]
+    fn ane() {
+        let mut cpu: CPU = create_cpu(&[0x8B, 0xFF]);
+        cpu.a = 0x00;
+        cpu.x = 0x3C;
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!(cpu.a, ANE_MAGIC_CONSTANT & 0x3C);
+    }
+
+    #[test]
+    fn lxa() {
+        let mut cpu: CPU = create_cpu(&[0xAB, 0x5A]);
+        cpu.a = 0x00;
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!((cpu.a, cpu.x), (LXA_MAGIC_CONSTANT & 0x5A, LXA_MAGIC_CONSTANT & 0x5A));
+    }
+
+    #[test]
+    fn sbx() {
+        let mut cpu: CPU = create_cpu(&[0xCB, 0x10]);
+        cpu.a = 0xF0;
+        cpu.x = 0x3F;
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!(cpu.x, 0x20);
+        assert!(cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn sha() {
+        let mut cpu: CPU = create_cpu(&[0x9F, 0x00, 0x03]);
+        cpu.a = 0xFF;
+        cpu.x = 0xFF;
+        cpu.y = 0x10;
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x0310), 0x04);
+    }
+
+    #[test]
+    fn sha_page_crossing() {
+        let mut cpu: CPU = create_cpu(&[0x93, 0x40]);
+        write_ram(&cpu, 0x40, 0xF0);
+        write_ram(&cpu, 0x41, 0x03);
+        write_ram(&cpu, 0x0010, 0xAA);
+        write_ram(&cpu, 0x0410, 0xAA);
+        cpu.a = 0x02;
+        cpu.x = 0xFF;
+        cpu.y = 0x20;
+        assert_eq!(run_instruction(&mut cpu), 6);
+        // The value 0x00 replaces the high byte of 0x0410
+        assert_eq!(cpu.read_only_bus(0x0010), 0x00);
+        assert_eq!(cpu.read_only_bus(0x0410), 0xAA);
+    }
+
+    #[test]
+    fn shx() {
+        let mut cpu: CPU = create_cpu(&[0x9E, 0x00, 0x03]);
+        cpu.x = 0xFF;
+        cpu.y = 0x05;
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x0305), 0x04);
+    }
+
+    #[test]
+    fn shy() {
+        let mut cpu: CPU = create_cpu(&[0x9C, 0x00, 0x03]);
+        cpu.x = 0x01;
+        cpu.y = 0x07;
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.read_only_bus(0x0301), 0x04);
+    }
+
+    #[test]
+    fn shs() {
+        let mut cpu: CPU = create_cpu(&[0x9B, 0x00, 0x03]);
+        write_ram(&cpu, 0x0302, 0xAA);
+        cpu.a = 0xF3;
+        cpu.x = 0x3F;
+        cpu.y = 0x02;
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.sp, 0x33);
+        assert_eq!(cpu.read_only_bus(0x0302), 0x00);
+    }
+
+    #[test]
+    fn las() {
+        let mut cpu: CPU = create_cpu(&[0xBB, 0x00, 0x03]);
+        write_ram(&cpu, 0x0300, 0xF0);
+        cpu.sp = 0x3F;
+        assert_eq!(run_instruction(&mut cpu), 4);
+        assert_eq!((cpu.a, cpu.x, cpu.sp), (0x30, 0x30, 0x30));
+    }
+
+    #[test]
+    fn nop() {
+        let mut cpu: CPU = create_cpu(&[0x1A, 0x80, 0xFF, 0x04, 0x10, 0x14, 0x10, 0x0C, 0x00, 0x03, 0x1C, 0xFF, 0x03]);
+        cpu.x = 0x01;
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!(run_instruction(&mut cpu), 3);
+        assert_eq!(run_instruction(&mut cpu), 4);
+        assert_eq!(run_instruction(&mut cpu), 4);
+        // Page crossing
+        assert_eq!(run_instruction(&mut cpu), 5);
+        assert_eq!(cpu.pc, PROGRAM_ADDRESS + 13);
+        assert_eq!((cpu.a, cpu.x, cpu.y, cpu.p), (0x00, 0x01, 0x00, 0x24));
+    }
+
+    #[test]
+    fn usbc() {
+        let mut cpu: CPU = create_cpu(&[0xEB, 0x01]);
+        cpu.a = 0x10;
+        cpu.set_flag(Flag::Carry, true);
+        assert_eq!(run_instruction(&mut cpu), 2);
+        assert_eq!(cpu.a, 0x0F);
+        assert!(cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn jam() {
+        let mut cpu: CPU = create_cpu(&[0x02, 0xEA]);
+        run_instruction(&mut cpu);
+        assert!(cpu.halted);
+        for _ in 0..10 {
+            assert_eq!(run_instruction(&mut cpu), 1);
+        }
+        assert_eq!(cpu.pc, PROGRAM_ADDRESS);
+    }
+
+    #[test]
+    fn reset() {
+        // A JAM is left by a reset, which keeps A, X and Y and decrements the stack pointer 3 times
+        let mut cpu: CPU = create_cpu(&[0xA9, 0x12, 0xA2, 0x34, 0x02]);
+        for _ in 0..3 {
+            run_instruction(&mut cpu);
+        }
+        assert!(cpu.halted);
+        let start: u64 = cpu.total_clock;
+        cpu.reset();
+        assert_eq!(cpu.total_clock - start, 7);
+        assert!(!cpu.halted);
+        assert_eq!((cpu.a, cpu.x, cpu.sp), (0x12, 0x34, 0xFA));
+        assert_eq!(cpu.p & 0x04, 0x04);
+        assert_eq!(cpu.pc, 0x0000);
+    }
+
+    #[test]
+    fn decimal_mode() {
+        // SED ; CLC ; LDA #$09 ; ADC #$01 ; SEC ; SBC #$11 ; CLC ; LDA #$99 ; ADC #$01
+        let program: [u8;14] = [0xF8, 0x18, 0xA9, 0x09, 0x69, 0x01, 0x38, 0xE9, 0x11, 0x18, 0xA9, 0x99, 0x69, 0x01];
+        let mut cpu: CPU<FlatBus> = create_6502(&program);
+        for _ in 0..4 {
+            run_instruction(&mut cpu);
+        }
+        assert_eq!(cpu.a, 0x10);
+        for _ in 0..2 {
+            run_instruction(&mut cpu);
+        }
+        assert_eq!(cpu.a, 0x99);
+        assert!(!cpu.get_flag(Flag::Carry));
+        for _ in 0..3 {
+            run_instruction(&mut cpu);
+        }
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.get_flag(Flag::Carry));
+
+        // The decimal flag is ignored by the 2A03
+        let mut cpu: CPU = create_cpu(&program);
+        for _ in 0..4 {
+            run_instruction(&mut cpu);
+        }
+        assert_eq!(cpu.a, 0x0A);
+    }
+
+    #[test]
+    fn functional_test() {
+        let path: String = match std::env::var(FUNCTIONAL_TEST_VARIABLE) {
+            Ok(path) => path,
+            Err(_) => {
+                eprintln!("{} is not set, skipping the functional test", FUNCTIONAL_TEST_VARIABLE);
+                return;
+            }
+        };
+        let data: Vec<u8> = std::fs::read(&path).unwrap_or_else(|why| std::panic!("Couldn't read {}: {}", path, why));
+        assert_eq!(data.len(), 0x10000, "The functional test must be a 64KB image");
+        let mut cpu: CPU<FlatBus> = CPU::new(FlatBus { data }, false);
+        cpu.decimal_mode = true;
+        cpu.pc = FUNCTIONAL_TEST_START;
+        loop {
+            let pc: u16 = cpu.pc;
+            cpu.step();
+            if cpu.pc == pc {
+                break;
+            }
+        }
+        assert_eq!(cpu.pc, FUNCTIONAL_TEST_SUCCESS, "Trapped at ${:04X}", cpu.pc);
+    }
+
+    #[test]
+    fn oam_dma() {
+        // The DMA starts on the read following the write, and takes 513 or 514 cycles depending on the alignment
+        for (start_clock, dma_cycles) in [(0, 514), (1, 513)].iter() {
+            let mut cpu: CPU = create_cpu(&[0x8D, 0x14, 0x40, 0xEA]);
+            for i in 0..=255u8 {
+                write_ram(&cpu, 0x0300 + i as u16, i);
+            }
+            cpu.a = 0x03;
+            cpu.total_clock = *start_clock;
+            assert_eq!(run_instruction(&mut cpu), 4);
+            assert_eq!(run_instruction(&mut cpu), 2 + dma_cycles);
+            let p_ppu: Arc<Mutex<PPU>> = cpu.bus.lock().unwrap().p_ppu.clone();
+            assert_eq!(p_ppu.lock().unwrap().oam.read_primary(0x00), 0x00);
+            assert_eq!(p_ppu.lock().unwrap().oam.read_primary(0xFF), 0xFF);
+        }
+    }
+
+    #[test]
+    fn dmc_dma() {
+        // Halt cycle, dummy cycle, alignment cycle if needed, then the sample is read
+        for (start_clock, dma_cycles) in [(0, 3), (1, 4)].iter() {
+            let mut cpu: CPU = create_cpu(&[0xEA]);
+            cpu.total_clock = *start_clock;
+            {
+                let mut bus = cpu.bus.lock().unwrap();
+                bus.write(0x4010, 0x8F);
+                bus.write(0x4012, 0x00);
+                bus.write(0x4013, 0x00);
+                bus.write(0x4015, 0x10);
+            }
+            assert_eq!(run_instruction(&mut cpu), 2 + dma_cycles);
+            let bus = cpu.bus.lock().unwrap();
+            assert_eq!(bus.dmc.bytes_remaining, 0);
+            assert_eq!(bus.dmc.o_sample_buffer, Some(0x00));
+            // The end of the sample asserts the IRQ line
+            assert!(bus.irq_line());
+        }
+    }
+
+    #[test]
+    fn dmc_dma_deletes_controller_bit() {
+        let cpu_read = |dmc_dma: bool| -> u8 {
+            let mut cpu: CPU = create_cpu(&[]);
+            {
+                let mut bus = cpu.bus.lock().unwrap();
+                let mut input_state: InputState = InputState::new();
+                input_state.controllers[0] = ControllerInput::A as u8;
+                bus.update_inputs(&input_state);
+                bus.write(0x4016, 0x01);
+                bus.write(0x4016, 0x00);
+                if dmc_dma {
+                    bus.write(0x4015, 0x10);
+                }
+            }
+            cpu.read_bus(0x4016) & 0x01
+        };
+        assert_eq!(cpu_read(false), 1);
+        // The halt cycle reads the A button, the CPU reads the B button
+        assert_eq!(cpu_read(true), 0);
+    }
+
+    #[test]
+    fn open_bus() {
+        // The last byte on the data bus is the high byte of the address
+        let mut cpu: CPU = create_cpu(&[0xAD, 0x00, 0x50, 0xAD, 0x00, 0x40, 0xAD, 0x18, 0x40]);
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.a, 0x50);
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.a, 0x40);
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.a, 0x40);
+    }
+
+    #[test]
+    fn bus_accesses() {
+        const PC: u16 = PROGRAM_ADDRESS;
+        // NOP reads the next byte
+        assert_eq!(instruction_accesses(&[0xEA], 0, 0, &[]), vec![(PC, None), (PC + 1, None)]);
+        // LDA $10F0,X reads the wrong page before the carry is added
+        assert_eq!(instruction_accesses(&[0xBD, 0xF0, 0x10], 0x20, 0, &[]), vec![(PC, None), (PC + 1, None), (PC + 2, None), (0x1010, None), (0x1110, None)]);
+        // Without a page crossing, the address is read once
+        assert_eq!(instruction_accesses(&[0xBD, 0x00, 0x10], 0x20, 0, &[]), vec![(PC, None), (PC + 1, None), (PC + 2, None), (0x1020, None)]);
+        // STA $1000,X always makes the dummy read
+        assert_eq!(instruction_accesses(&[0x9D, 0x00, 0x10], 0x20, 0, &[]), vec![(PC, None), (PC + 1, None), (PC + 2, None), (0x1020, None), (0x1020, Some(0x00))]);
+        // INC $10 writes the old value back before the new one
+        assert_eq!(instruction_accesses(&[0xE6, 0x10], 0, 0, &[(0x10, 0x41)]), vec![(PC, None), (PC + 1, None), (0x10, None), (0x10, Some(0x41)), (0x10, Some(0x42))]);
+        // LDA $10,X reads the zero page address before adding X, and wraps in the zero page
+        assert_eq!(instruction_accesses(&[0xB5, 0x10], 0xF8, 0, &[]), vec![(PC, None), (PC + 1, None), (0x10, None), (0x08, None)]);
+        // LDA ($10),Y reads the pointer, then the wrong page before the carry is added
+        assert_eq!(instruction_accesses(&[0xB1, 0x10], 0, 0x20, &[(0x10, 0xF0), (0x11, 0x10)]), vec![(PC, None), (PC + 1, None), (0x10, None), (0x11, None), (0x1010, None), (0x1110, None)]);
+    }
+
+    #[test]
+    fn interrupt_polling() {
+        // The lines are polled at the end of the next-to-last cycle of NOP
+        for (o_nmi_cycle, o_irq_cycle, handler) in [(Some(1), None, NMI_HANDLER), (None, Some(1), IRQ_HANDLER)].iter() {
+            let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0xEA, 0xEA], 0x20, *o_nmi_cycle, *o_irq_cycle);
+            assert_eq!(run_instruction(&mut cpu), 9);
+            assert_eq!(cpu.pc, *handler);
+            assert_eq!(pushed_state(&cpu).0, PROGRAM_ADDRESS + 1);
+        }
+        // An interrupt asserted on the last cycle waits for the next instruction
+        for (o_nmi_cycle, o_irq_cycle, handler) in [(Some(2), None, NMI_HANDLER), (None, Some(2), IRQ_HANDLER)].iter() {
+            let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0xEA, 0xEA], 0x20, *o_nmi_cycle, *o_irq_cycle);
+            assert_eq!(run_instruction(&mut cpu), 2);
+            assert_eq!(cpu.pc, PROGRAM_ADDRESS + 1);
+            assert_eq!(run_instruction(&mut cpu), 9);
+            assert_eq!(cpu.pc, *handler);
+            assert_eq!(pushed_state(&cpu).0, PROGRAM_ADDRESS + 2);
+        }
+        // The IRQ is ignored while the interrupt disable flag is set
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0xEA, 0xEA], 0x24, None, Some(1));
+        run_instruction(&mut cpu);
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, PROGRAM_ADDRESS + 2);
+    }
+
+    #[test]
+    fn interrupt_flag_latency() {
+        // CLI : the flag is cleared after the lines are polled, the IRQ is taken after the next instruction
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0x58, 0xEA], 0x24, None, Some(1));
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, PROGRAM_ADDRESS + 1);
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+
+        // SEI : the IRQ polled before the flag is set is taken, with the flag set on the stack
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0x78, 0xEA], 0x20, None, Some(1));
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+        assert_eq!(pushed_state(&cpu), (PROGRAM_ADDRESS + 1, 0x24));
+
+        // PLP behaves like CLI
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0x28, 0xEA], 0x24, None, Some(1));
+        cpu.bus.data[0x01FE] = 0x20;
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, PROGRAM_ADDRESS + 1);
+        assert!(!cpu.get_flag(Flag::InterruptDisable));
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+    }
+
+    #[test]
+    fn interrupt_hijacking() {
+        // An NMI polled before BRK pushes the status register uses the NMI vector, B stays set on the stack
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0x00, 0x00, 0xEA], 0x20, Some(4), None);
+        assert_eq!(run_instruction(&mut cpu), 7);
+        assert_eq!(cpu.pc, NMI_HANDLER);
+        assert_eq!(pushed_state(&cpu), (PROGRAM_ADDRESS + 2, 0x30));
+        assert!(cpu.get_flag(Flag::InterruptDisable));
+        // The NMI isn't taken a second time
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, NMI_HANDLER + 1);
+
+        // A later NMI runs after the first instruction of the BRK handler
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0x00, 0x00, 0xEA], 0x20, Some(5), None);
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, NMI_HANDLER);
+
+        // Same for an IRQ, B is cleared on the stack
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0xEA, 0xEA], 0x20, Some(6), Some(1));
+        assert_eq!(run_instruction(&mut cpu), 9);
+        assert_eq!(cpu.pc, NMI_HANDLER);
+        assert_eq!(pushed_state(&cpu), (PROGRAM_ADDRESS + 1, 0x20));
+    }
+
+    #[test]
+    fn branch_interrupt_delay() {
+        // BEQ +0 taken without crossing a page : the lines aren't polled on its last cycle
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0xF0, 0x00, 0xEA, 0xEA], 0x22, None, Some(2));
+        assert_eq!(run_instruction(&mut cpu), 3);
+        assert_eq!(cpu.pc, PROGRAM_ADDRESS + 2);
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+        assert_eq!(pushed_state(&cpu).0, PROGRAM_ADDRESS + 3);
+
+        // An IRQ polled on the first cycles isn't delayed
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0xF0, 0x00, 0xEA, 0xEA], 0x22, None, Some(1));
+        run_instruction(&mut cpu);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+        assert_eq!(pushed_state(&cpu).0, PROGRAM_ADDRESS + 2);
+
+        // BEQ -4 crosses a page and polls on its next-to-last cycle
+        let mut cpu: CPU<InterruptBus> = create_interrupt_cpu(&[0xF0, 0xFC], 0x22, None, Some(3));
+        assert_eq!(run_instruction(&mut cpu), 4 + 7);
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+        assert_eq!(pushed_state(&cpu).0, PROGRAM_ADDRESS - 2);
+    }
+
+    #[test]
+    fn vblank_suppression() {
+        let read_status = |cycles: u16| -> (u8, bool) {
+            let cpu: CPU = create_cpu(&[]);
+            let mut bus = cpu.bus.lock().unwrap();
+            let p_ppu: Arc<Mutex<PPU>> = bus.p_ppu.clone();
+            {
+                let mut ppu = p_ppu.lock().unwrap();
+                ppu.warming_up = false;
+                ppu.registers.ctrl = 0x80;
+                ppu.scanline = 241;
+                while ppu.cycles != cycles {
+                    ppu.clock();
+                }
+            }
+            let status: u8 = bus.read(0x2002);
+            let mut ppu = p_ppu.lock().unwrap();
+            for _ in 0..3 {
+                ppu.clock();
+            }
+            (status & 0x80, ppu.registers.nmi_line())
+        };
+        // Reading $2002 just before the flag is set keeps it cleared for this frame, and no NMI occurs
+        assert_eq!(read_status(1), (0x00, false));
+        // Reading it after the flag is set clears it, the NMI edge has already been seen by the CPU
+        assert_eq!(read_status(2), (0x80, false));
+        assert_eq!(read_status(0), (0x00, true));
+    }
+
+    #[test]
+    fn frame_irq() {
+        // LDA #$00 ; STA $4017 ; JMP $0205
+        let mut cpu: CPU = create_cpu(&[0xA9, 0x00, 0x8D, 0x17, 0x40, 0x4C, 0x05, 0x02]);
+        run_instruction(&mut cpu);
+        run_instruction(&mut cpu);
+        let start: u64 = cpu.total_clock;
+        while !cpu.bus.lock().unwrap().irq_line() && cpu.total_clock - start < 30000 {
+            run_instruction(&mut cpu);
+        }
+        assert!((29800..30000).contains(&(cpu.total_clock - start)));
+        // Reading $4015 returns and clears the flag
+        assert_eq!(cpu.bus.lock().unwrap().read(0x4015) & 0x40, 0x40);
+        assert!(!cpu.bus.lock().unwrap().irq_line());
+        // The IRQ is inhibited by bit 6 of $4017
+        cpu.bus.lock().unwrap().write(0x4017, 0x40);
+        for _ in 0..10000 {
+            run_instruction(&mut cpu);
+        }
+        assert!(!cpu.bus.lock().unwrap().irq_line());
+    }
+}
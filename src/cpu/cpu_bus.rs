@@ -0,0 +1,109 @@
+// Interface between the CPU and the memory it is connected to
+// The NES bus implements it, other 6502 systems only need read, write and peek
+
+// ===== IMPORTS =====
+
+use std::sync::{Arc, Mutex};
+
+use crate::{bus::Bus, debugger::AccessKind};
+
+// ===== CPU BUS TRAIT =====
+
+pub trait CpuBus {
+    // Reads a value, with the side effects of a CPU read
+    fn read(&mut self, address: u16) -> u8;
+    fn write(&mut self, address: u16, value: u8);
+    // Reads a value without side effects, used by the logs and the debuggers
+    fn peek(&self, address: u16) -> u8;
+
+    // Called before each CPU cycle, clocks the other components of the system
+    fn tick(&mut self) {}
+
+    // NMI and IRQ lines, sampled at the end of each cycle
+    fn interrupt_lines(&self) -> (bool, bool) {
+        (false, false)
+    }
+
+    // A pending DMA halts the CPU on its next read
+    fn dma_pending(&self) -> bool {
+        false
+    }
+    // Called at the beginning of each cycle where the CPU is halted, before the tick
+    fn start_dma_cycle(&mut self) {}
+    // Transfer of a cycle where the CPU is halted on a read of the given address
+    fn dma_cycle(&mut self, _address: u16, _get_cycle: bool) {}
+
+    // Scanline and dot of the PPU, displayed in the logs
+    fn ppu_position(&self) -> (u16, u16) {
+        (0, 0)
+    }
+
+    // Offset in the ROM of the byte mapped at this address, used to find the symbols of banked code
+    fn prg_rom_offset(&self, _address: u16) -> Option<usize> {
+        None
+    }
+}
+
+// ===== NES BUS =====
+
+// The bus is shared with the rest of the NES, it is locked on each access
+// The accesses of the CPU are checked against the watchpoints of the debugger
+impl CpuBus for Arc<Mutex<Bus>> {
+    fn read(&mut self, address: u16) -> u8 {
+        let mut bus = self.lock().unwrap();
+        let value: u8 = bus.read(address);
+        if !bus.watchpoints.is_empty() {
+            bus.check_watchpoints(AccessKind::Read, address, value);
+        }
+        if let Some(history) = bus.o_access_history.as_mut() {
+            history.record_read(address as usize);
+        }
+        value
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        let mut bus = self.lock().unwrap();
+        bus.write(address, value);
+        if !bus.watchpoints.is_empty() {
+            bus.check_watchpoints(AccessKind::Write, address, value);
+        }
+        if let Some(history) = bus.o_access_history.as_mut() {
+            history.record_write(address as usize);
+        }
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        self.lock().unwrap().read_only(address)
+    }
+
+    fn tick(&mut self) {
+        self.lock().unwrap().tick();
+    }
+
+    fn interrupt_lines(&self) -> (bool, bool) {
+        let bus = self.lock().unwrap();
+        (bus.nmi_line(), bus.irq_line())
+    }
+
+    fn dma_pending(&self) -> bool {
+        self.lock().unwrap().dma.is_pending()
+    }
+
+    fn start_dma_cycle(&mut self) {
+        self.lock().unwrap().dma.start_cycle();
+    }
+
+    fn dma_cycle(&mut self, address: u16, get_cycle: bool) {
+        self.lock().unwrap().dma_cycle(address, get_cycle);
+    }
+
+    fn ppu_position(&self) -> (u16, u16) {
+        let bus = self.lock().unwrap();
+        let ppu = bus.p_ppu.lock().unwrap();
+        (ppu.scanline, ppu.cycles)
+    }
+
+    fn prg_rom_offset(&self, address: u16) -> Option<usize> {
+        self.lock().unwrap().prg_rom_offset(address)
+    }
+}
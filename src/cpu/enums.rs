@@ -38,5 +38,7 @@ pub enum Flag {
 pub enum Interrupt {
     IRQ,
     NMI,
-    Reset
+    Reset,
+    // Software interrupt, uses the IRQ handler
+    BRK
 }
\ No newline at end of file
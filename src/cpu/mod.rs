@@ -1,3 +1,4 @@
 pub mod cpu;
+pub mod cpu_bus;
 pub mod instructions;
 pub mod enums;
\ No newline at end of file
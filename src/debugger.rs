@@ -0,0 +1,661 @@
+// Interactive debugger running in the terminal
+// The emulation pauses before an instruction on a breakpoint, a watchpoint, a pause request or at the end of a step
+// The commands are then read from the standard input until the emulation is resumed
+
+// ===== IMPORTS =====
+
+use std::{io::{self, Write}, sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}}};
+
+use crate::{bus::Bus, cpu::{cpu::CPU, enums::Flag, instructions::INSTRUCTIONS}, ppu::ppu::PPU, symbols::SymbolLocation};
+
+// ===== CONSTANTS =====
+
+// Number of instructions disassembled before and after the PC
+const DISASSEMBLY_BEFORE: usize = 4;
+const DISASSEMBLY_AFTER: usize = 8;
+// Number of extra bytes decoded before them, for the decoding to fall in step with the code
+const DISASSEMBLY_SYNC_LENGTH: u32 = 0x20;
+
+// Number of bytes of a memory dump when no length is given, and number of bytes per line
+const DUMP_LENGTH: u32 = 0x80;
+const DUMP_LINE_LENGTH: u32 = 0x10;
+
+const HELP: &str = "\
+Values are hexadecimal, an empty line repeats the last command
+Addresses may also be symbol names, the breakpoints on ROM symbols only stop in their bank
+c                       continue
+s [N]                   step N instructions (1 by default)
+sl                      step to the next scanline
+f                       step to the next frame
+b [ADDR]                add a breakpoint, or list the breakpoints
+bd [ADDR]               delete a breakpoint, or all of them
+w [r|w|x START [END]]   add a read, write or execute watchpoint, or list the watchpoints
+wd [INDEX]              delete a watchpoint, or all of them
+r                       display the registers
+set REG VALUE           set a register (a, x, y, sp, pc, p) or a flag (n, v, d, i, z, c)
+m ADDR [LENGTH]         dump the memory seen by the CPU
+d [ADDR]                disassemble around the PC, or from an address
+q                       quit the emulator";
+
+// ===== ENUMS =====
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccessKind {
+    Read,
+    Write,
+    Execute
+}
+
+// Stop condition of a step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepMode {
+    // Number of instructions left to run
+    Instructions(u32),
+    // Scanline and frame at the beginning of the step
+    Scanline(u16),
+    Frame(u64)
+}
+
+// Reason of a pause of the emulation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    PauseRequest,
+    Breakpoint(u16),
+    Watchpoint(WatchpointHit),
+    StepEnd
+}
+
+// ===== WATCHPOINTS =====
+
+// Pauses the emulation when the CPU accesses an address of the range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Watchpoint {
+    pub kind: AccessKind,
+    pub start: u16,
+    pub end: u16
+}
+
+impl Watchpoint {
+    pub fn contains(&self, kind: AccessKind, address: u16) -> bool {
+        self.kind == kind && address >= self.start && address <= self.end
+    }
+}
+
+// Access of the CPU that hit a watchpoint
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WatchpointHit {
+    pub watchpoint: Watchpoint,
+    pub address: u16,
+    pub value: u8
+}
+
+// ===== DEBUGGER STRUCT =====
+
+pub struct Debugger {
+    pub p_cpu: Arc<Mutex<CPU>>,
+    pub p_bus: Arc<Mutex<Bus>>,
+    pub p_ppu: Arc<Mutex<PPU>>,
+
+    // The read and write watchpoints are stored in the bus
+    pub breakpoints: Vec<u16>,
+    // Breakpoints on offsets of the PRG ROM, they only stop when their bank is mapped
+    pub prg_rom_breakpoints: Vec<usize>,
+
+    // Is the emulation stopped in the debugger ?
+    pub paused: bool,
+    // Step being run, the emulation pauses when it ends
+    pub o_step: Option<StepMode>,
+    // Set by the frontend to pause the emulation
+    pub p_pause_request: Arc<AtomicBool>,
+    // Set by the quit command, the frontend saves its files and exits
+    pub p_quit_request: Arc<AtomicBool>,
+
+    // Run again when an empty line is entered
+    pub last_command: String
+}
+
+impl Debugger {
+    pub fn new(p_cpu: Arc<Mutex<CPU>>, p_bus: Arc<Mutex<Bus>>, p_ppu: Arc<Mutex<PPU>>) -> Self {
+        Debugger {
+            p_cpu,
+            p_bus,
+            p_ppu,
+
+            breakpoints: vec![],
+            prg_rom_breakpoints: vec![],
+
+            paused: false,
+            o_step: None,
+            p_pause_request: Arc::new(AtomicBool::new(false)),
+            p_quit_request: Arc::new(AtomicBool::new(false)),
+
+            last_command: String::new()
+        }
+    }
+
+    // ===== EMULATION HOOK =====
+
+    // Called before each instruction
+    // Reads and runs the commands while the emulation is paused
+    pub fn before_instruction(&mut self) {
+        if let Some(reason) = self.check_stop() {
+            match reason {
+                StopReason::PauseRequest => println!("Paused"),
+                StopReason::Breakpoint(address) => println!("Breakpoint at {}", self.address_name(address)),
+                StopReason::Watchpoint(hit) => println!("Watchpoint {} : ${:04X} = ${:02X}", watchpoint_name(&hit.watchpoint), hit.address, hit.value),
+                StopReason::StepEnd => ()
+            }
+            self.paused = true;
+            self.o_step = None;
+        }
+        if self.paused {
+            self.print_disassembly(None);
+            self.prompt();
+        }
+    }
+
+    // Checks the stop conditions, returns the reason of the pause if the emulation must be paused
+    pub fn check_stop(&mut self) -> Option<StopReason> {
+        let pc: u16 = self.p_cpu.lock().unwrap().pc;
+        if self.p_pause_request.swap(false, Ordering::Relaxed) {
+            return Some(StopReason::PauseRequest);
+        }
+        {
+            let mut bus = self.p_bus.lock().unwrap();
+            if let Some(hit) = bus.o_watchpoint_hit.take() {
+                return Some(StopReason::Watchpoint(hit));
+            }
+            if let Some(watchpoint) = bus.watchpoints.iter().find(|watchpoint| watchpoint.contains(AccessKind::Execute, pc)) {
+                return Some(StopReason::Watchpoint(WatchpointHit {
+                    watchpoint: *watchpoint,
+                    address: pc,
+                    value: bus.read_only(pc)
+                }));
+            }
+            if !self.prg_rom_breakpoints.is_empty() {
+                if let Some(offset) = bus.prg_rom_offset(pc) {
+                    if self.prg_rom_breakpoints.contains(&offset) {
+                        return Some(StopReason::Breakpoint(pc));
+                    }
+                }
+            }
+        }
+        if self.breakpoints.contains(&pc) {
+            return Some(StopReason::Breakpoint(pc));
+        }
+        let step_ended: bool = match self.o_step {
+            Some(StepMode::Instructions(count)) if count > 1 => {
+                self.o_step = Some(StepMode::Instructions(count - 1));
+                false
+            },
+            Some(StepMode::Instructions(_)) => true,
+            Some(StepMode::Scanline(scanline)) => self.p_ppu.lock().unwrap().scanline != scanline,
+            Some(StepMode::Frame(frame)) => self.p_ppu.lock().unwrap().frame_count != frame,
+            None => false
+        };
+        match step_ended {
+            true => Some(StopReason::StepEnd),
+            false => None
+        }
+    }
+
+    // Reads the commands from the standard input until the emulation is resumed
+    pub fn prompt(&mut self) {
+        loop {
+            print!("(nes) ");
+            let _ = io::stdout().flush();
+            let mut line: String = String::new();
+            match io::stdin().read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    println!("No more commands, the debugger is detached");
+                    self.detach();
+                    return;
+                },
+                Ok(_) => ()
+            }
+            let command: String = match line.trim() {
+                "" => self.last_command.clone(),
+                command => String::from(command)
+            };
+            self.last_command = command.clone();
+            match self.execute(&command) {
+                Err(why) => println!("{}", why),
+                Ok(true) => {
+                    self.paused = false;
+                    return;
+                },
+                Ok(false) => ()
+            }
+        }
+    }
+
+    // Removes the stop conditions and resumes the emulation
+    fn detach(&mut self) {
+        self.breakpoints.clear();
+        self.prg_rom_breakpoints.clear();
+        self.p_bus.lock().unwrap().watchpoints.clear();
+        self.o_step = None;
+        self.paused = false;
+    }
+
+    // ===== COMMANDS =====
+
+    // Runs a command, returns true when the emulation must be resumed
+    pub fn execute(&mut self, command: &str) -> Result<bool, String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        if words.is_empty() {
+            return Ok(false);
+        }
+        match words[0] {
+            "c" | "continue" => return Ok(true),
+            "s" | "step" => {
+                let count: u32 = match words.get(1) {
+                    Some(word) => word.parse().map_err(|_| format!("Invalid count : {}", word))?,
+                    None => 1
+                };
+                self.o_step = Some(StepMode::Instructions(count.max(1)));
+                return Ok(true);
+            },
+            "sl" | "scanline" => {
+                self.o_step = Some(StepMode::Scanline(self.p_ppu.lock().unwrap().scanline));
+                return Ok(true);
+            },
+            "f" | "frame" => {
+                self.o_step = Some(StepMode::Frame(self.p_ppu.lock().unwrap().frame_count));
+                return Ok(true);
+            },
+            "b" | "break" => match words.get(1) {
+                Some(word) => match self.resolve(word)? {
+                    SymbolLocation::Cpu(address) => {
+                        if !self.breakpoints.contains(&address) {
+                            self.breakpoints.push(address);
+                        }
+                    },
+                    SymbolLocation::PrgRom(offset) => {
+                        if !self.prg_rom_breakpoints.contains(&offset) {
+                            self.prg_rom_breakpoints.push(offset);
+                        }
+                    }
+                },
+                None => {
+                    for address in self.breakpoints.iter() {
+                        println!("{}", self.address_name(*address));
+                    }
+                    for offset in self.prg_rom_breakpoints.iter() {
+                        println!("PRG ROM ${:05X}", offset);
+                    }
+                }
+            },
+            "bd" | "delete" => match words.get(1) {
+                Some(word) => match self.resolve(word)? {
+                    SymbolLocation::Cpu(address) => self.breakpoints.retain(|breakpoint| *breakpoint != address),
+                    SymbolLocation::PrgRom(offset) => self.prg_rom_breakpoints.retain(|breakpoint| *breakpoint != offset)
+                },
+                None => {
+                    self.breakpoints.clear();
+                    self.prg_rom_breakpoints.clear();
+                }
+            },
+            "w" | "watch" => self.watch(&words[1..])?,
+            "wd" => {
+                let mut bus = self.p_bus.lock().unwrap();
+                match words.get(1) {
+                    Some(word) => {
+                        let index: usize = word.parse().map_err(|_| format!("Invalid index : {}", word))?;
+                        if index >= bus.watchpoints.len() {
+                            return Err(format!("No watchpoint {}", index));
+                        }
+                        bus.watchpoints.remove(index);
+                    },
+                    None => bus.watchpoints.clear()
+                }
+            },
+            "r" | "regs" => self.print_registers(),
+            "set" => match (words.get(1), words.get(2)) {
+                (Some(register), Some(value)) => self.set_register(register, parse_value(value)?)?,
+                _ => return Err(String::from("Usage : set REG VALUE"))
+            },
+            "m" | "mem" => match words.get(1) {
+                Some(word) => {
+                    let length: u32 = match words.get(2) {
+                        Some(length) => parse_value(length)? as u32,
+                        None => DUMP_LENGTH
+                    };
+                    self.dump_memory(self.parse_address(word)?, length);
+                },
+                None => return Err(String::from("Usage : m ADDR [LENGTH]"))
+            },
+            "d" | "dis" => match words.get(1) {
+                Some(word) => self.print_disassembly(Some(self.parse_address(word)?)),
+                None => self.print_disassembly(None)
+            },
+            "h" | "help" => println!("{}", HELP),
+            "q" | "quit" => {
+                // The emulation runs until the frontend exits, it must not stop again
+                self.p_quit_request.store(true, Ordering::Relaxed);
+                self.detach();
+                return Ok(true);
+            },
+            word => return Err(format!("Unknown command : {}, type h for help", word))
+        }
+        Ok(false)
+    }
+
+    // Adds a watchpoint, or lists them when no argument is given
+    fn watch(&mut self, arguments: &[&str]) -> Result<(), String> {
+        if arguments.is_empty() {
+            for (index, watchpoint) in self.p_bus.lock().unwrap().watchpoints.iter().enumerate() {
+                println!("{} : {}", index, watchpoint_name(watchpoint));
+            }
+            return Ok(());
+        }
+        let kind: AccessKind = match arguments[0] {
+            "r" => AccessKind::Read,
+            "w" => AccessKind::Write,
+            "x" => AccessKind::Execute,
+            kind => return Err(format!("Invalid access : {}, must be r, w or x", kind))
+        };
+        let start: u16 = match arguments.get(1) {
+            Some(word) => self.parse_address(word)?,
+            None => return Err(String::from("Usage : w r|w|x START [END]"))
+        };
+        let end: u16 = match arguments.get(2) {
+            Some(word) => self.parse_address(word)?,
+            None => start
+        };
+        if end < start {
+            return Err(format!("Invalid range : ${:04X}-${:04X}", start, end));
+        }
+        self.p_bus.lock().unwrap().watchpoints.push(Watchpoint { kind, start, end });
+        Ok(())
+    }
+
+    // Location of a symbol, or of an hexadecimal address
+    // A value with a $ or 0x prefix is always an address
+    fn resolve(&self, word: &str) -> Result<SymbolLocation, String> {
+        if !word.starts_with('$') && !word.starts_with("0x") {
+            let cpu = self.p_cpu.lock().unwrap();
+            if let Some(location) = cpu.o_symbols.as_ref().and_then(|symbols| symbols.find(word)) {
+                return Ok(location);
+            }
+        }
+        Ok(SymbolLocation::Cpu(parse_value(word)?))
+    }
+
+    // CPU address of a symbol or an hexadecimal address, the ROM symbols must be in a mapped bank
+    fn parse_address(&self, word: &str) -> Result<u16, String> {
+        match self.resolve(word)? {
+            SymbolLocation::Cpu(address) => Ok(address),
+            SymbolLocation::PrgRom(offset) => self.p_bus.lock().unwrap().prg_rom_address(offset)
+                .ok_or(format!("{} is in a bank that isn't mapped (PRG ROM ${:05X})", word, offset))
+        }
+    }
+
+    // Hexadecimal address followed by its symbol
+    fn address_name(&self, address: u16) -> String {
+        match self.p_cpu.lock().unwrap().symbol(address) {
+            Some(symbol) if !symbol.name.is_empty() => format!("${:04X} ({})", address, symbol.name),
+            _ => format!("${:04X}", address)
+        }
+    }
+
+    fn set_register(&mut self, register: &str, value: u16) -> Result<(), String> {
+        let mut cpu = self.p_cpu.lock().unwrap();
+        if register == "pc" {
+            cpu.pc = value;
+            return Ok(());
+        }
+        if value > 0xFF {
+            return Err(format!("Invalid value for {} : {:X}", register, value));
+        }
+        match register {
+            "a" => cpu.a = value as u8,
+            "x" => cpu.x = value as u8,
+            "y" => cpu.y = value as u8,
+            "sp" => cpu.sp = value as u8,
+            "p" => cpu.p = value as u8,
+            "n" => cpu.set_flag(Flag::Negative, value != 0),
+            "v" => cpu.set_flag(Flag::Overflow, value != 0),
+            "d" => cpu.set_flag(Flag::Decimal, value != 0),
+            "i" => cpu.set_flag(Flag::InterruptDisable, value != 0),
+            "z" => cpu.set_flag(Flag::Zero, value != 0),
+            "c" => cpu.set_flag(Flag::Carry, value != 0),
+            _ => return Err(format!("Unknown register : {}", register))
+        }
+        Ok(())
+    }
+
+    // ===== DISPLAY =====
+
+    pub fn print_registers(&self) {
+        let cpu = self.p_cpu.lock().unwrap();
+        let ppu = self.p_ppu.lock().unwrap();
+        // Flags are displayed in upper case when they are set
+        let flags: String = "NV-BDIZC".chars().enumerate().map(|(bit, name)| {
+            match cpu.p & (0x80 >> bit) != 0 {
+                true => name,
+                false => name.to_ascii_lowercase()
+            }
+        }).collect();
+        println!("PC:{:04X} A:{:02X} X:{:02X} Y:{:02X} P:{:02X} [{}] SP:{:02X} CYC:{} PPU:{},{} FRAME:{}",
+            cpu.pc, cpu.a, cpu.x, cpu.y, cpu.p, flags, cpu.sp, cpu.total_clock, ppu.scanline, ppu.cycles, ppu.frame_count);
+    }
+
+    // Displays 16 bytes per line, as seen by the CPU without side effects
+    pub fn dump_memory(&self, start: u16, length: u32) {
+        let bus = self.p_bus.lock().unwrap();
+        for line_start in (0..length).step_by(DUMP_LINE_LENGTH as usize) {
+            let address: u16 = start.wrapping_add(line_start as u16);
+            let bytes: Vec<u8> = (0..DUMP_LINE_LENGTH.min(length - line_start))
+                .map(|offset| bus.read_only(address.wrapping_add(offset as u16)))
+                .collect();
+            let hex: String = bytes.iter().map(|byte| format!("{:02X} ", byte)).collect();
+            let text: String = bytes.iter().map(|&byte| match byte.is_ascii_graphic() {
+                true => byte as char,
+                false => '.'
+            }).collect();
+            println!("{:04X}: {:<48} {}", address, hex, text);
+        }
+    }
+
+    // Disassembles the instructions around the PC, or from the given address
+    // The PC is marked with '>' and the breakpoints with '*'
+    pub fn print_disassembly(&self, o_start: Option<u16>) {
+        let cpu = self.p_cpu.lock().unwrap();
+        // Around the PC, the count starts when the PC is reached
+        let (mut address, mut lines_left): (u16, usize) = match o_start {
+            Some(start) => (start, DISASSEMBLY_BEFORE + DISASSEMBLY_AFTER + 1),
+            None => (previous_instruction(&cpu, cpu.pc, DISASSEMBLY_BEFORE), usize::MAX)
+        };
+        loop {
+            let opcode: u8 = cpu.read_only_bus(address);
+            let length: u16 = INSTRUCTIONS[opcode as usize].bytes as u16;
+            let parameters: Vec<u8> = (1..length).map(|offset| cpu.read_only_bus(address.wrapping_add(offset))).collect();
+            let bytes: String = (0..length).map(|offset| format!("{:02X} ", cpu.read_only_bus(address.wrapping_add(offset)))).collect();
+            let marker: char;
+            if address == cpu.pc {
+                marker = '>';
+            }
+            else if self.breakpoints.contains(&address) || self.is_prg_rom_breakpoint(address) {
+                marker = '*';
+            }
+            else {
+                marker = ' ';
+            }
+            let mut line: String = format!("{} {:04X}  {:<9} {}", marker, address, bytes, cpu.dissassemble(address, opcode, parameters).trim_end());
+            if let Some(symbol) = cpu.symbol(address) {
+                if !symbol.name.is_empty() {
+                    println!("{}:", symbol.name);
+                }
+                if !symbol.comment.is_empty() {
+                    line = format!("{:<48} ; {}", line, symbol.comment);
+                }
+            }
+            println!("{}", line);
+            if o_start.is_none() && address == cpu.pc {
+                lines_left = DISASSEMBLY_AFTER + 1;
+            }
+            lines_left -= 1;
+            if lines_left == 0 {
+                break;
+            }
+            address = address.wrapping_add(length);
+        }
+    }
+
+    fn is_prg_rom_breakpoint(&self, address: u16) -> bool {
+        if self.prg_rom_breakpoints.is_empty() {
+            return false;
+        }
+        match self.p_bus.lock().unwrap().prg_rom_offset(address) {
+            Some(offset) => self.prg_rom_breakpoints.contains(&offset),
+            None => false
+        }
+    }
+}
+
+// ===== HELPER FUNCTIONS =====
+
+// Parses an hexadecimal value, with an optional $ or 0x prefix
+pub fn parse_value(word: &str) -> Result<u16, String> {
+    u16::from_str_radix(word.trim_start_matches('$').trim_start_matches("0x"), 16).map_err(|_| format!("Invalid value : {}", word))
+}
+
+fn watchpoint_name(watchpoint: &Watchpoint) -> String {
+    let kind: &str = match watchpoint.kind {
+        AccessKind::Read => "read",
+        AccessKind::Write => "write",
+        AccessKind::Execute => "execute"
+    };
+    format!("{} ${:04X}-${:04X}", kind, watchpoint.start, watchpoint.end)
+}
+
+// Returns the address of the instruction decoded `count` instructions before the address
+// The decoding starts from the farthest address that falls exactly on the given address
+fn previous_instruction(cpu: &CPU, address: u16, count: usize) -> u16 {
+    for distance in (1..=(count as u32) * 3 + DISASSEMBLY_SYNC_LENGTH).rev() {
+        if distance > address as u32 {
+            continue;
+        }
+        let mut starts: Vec<u32> = vec![];
+        let mut current: u32 = address as u32 - distance;
+        while current < address as u32 {
+            starts.push(current);
+            current += INSTRUCTIONS[cpu.read_only_bus(current as u16) as usize].bytes as u32;
+        }
+        if current == address as u32 {
+            return starts[starts.len().saturating_sub(count)] as u16;
+        }
+    }
+    address
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{cartridge::{mapper::{Mapper, Mirroring}, mapper_000::Mapper0}, symbols::SymbolTable};
+
+    const PROGRAM_ADDRESS: u16 = 0x0200;
+
+    // Creates a debugger attached to a CPU running the given program from RAM, with an empty cartridge
+    pub(crate) fn create_debugger(program: &[u8]) -> Debugger {
+        let mapper: Box<dyn Mapper> = Box::new(Mapper0::new(vec![[0;16*1024]], vec![[0;8*1024]], Mirroring::Vertical));
+        let p_ppu: Arc<Mutex<PPU>> = Arc::new(Mutex::new(PPU::new(None)));
+        p_ppu.lock().unwrap().ppu_bus.o_p_mapper = Some(mapper.clone());
+        let p_bus: Arc<Mutex<Bus>> = Arc::new(Mutex::new(Bus::new(p_ppu.clone())));
+        p_bus.lock().unwrap().o_p_mapper = Some(mapper);
+        let start: usize = PROGRAM_ADDRESS as usize;
+        p_bus.lock().unwrap().data[start..start + program.len()].copy_from_slice(program);
+        let p_cpu: Arc<Mutex<CPU>> = Arc::new(Mutex::new(CPU::new(p_bus.clone(), false)));
+        p_cpu.lock().unwrap().pc = PROGRAM_ADDRESS;
+        Debugger::new(p_cpu, p_bus, p_ppu)
+    }
+
+    // Runs instructions until the debugger pauses, returns the PC
+    fn run_until_pause(debugger: &mut Debugger) -> u16 {
+        loop {
+            debugger.p_cpu.lock().unwrap().step();
+            if debugger.check_stop().is_some() {
+                return debugger.p_cpu.lock().unwrap().pc;
+            }
+        }
+    }
+
+    #[test]
+    fn breakpoints_and_steps() {
+        let mut debugger: Debugger = create_debugger(&[0xEA;16]);
+        assert_eq!(debugger.execute("b $0206"), Ok(false));
+        assert_eq!(debugger.execute("s 3"), Ok(true));
+        assert_eq!(run_until_pause(&mut debugger), 0x0203);
+        debugger.o_step = None;
+        assert_eq!(run_until_pause(&mut debugger), 0x0206);
+        assert_eq!(debugger.execute("bd 206"), Ok(false));
+        assert!(debugger.breakpoints.is_empty());
+    }
+
+    #[test]
+    fn watchpoints() {
+        // STA $10 ; LDA $25 ; NOP ; NOP
+        let mut debugger: Debugger = create_debugger(&[0x85, 0x10, 0xA5, 0x25, 0xEA, 0xEA]);
+        assert_eq!(debugger.execute("w r 20 2F"), Ok(false));
+        assert_eq!(debugger.execute("w x 205"), Ok(false));
+        assert!(debugger.execute("w r 30 20").is_err());
+        assert_eq!(run_until_pause(&mut debugger), 0x0204);
+        assert_eq!(run_until_pause(&mut debugger), 0x0205);
+        assert_eq!(debugger.execute("wd 0"), Ok(false));
+        assert_eq!(debugger.p_bus.lock().unwrap().watchpoints, vec![Watchpoint { kind: AccessKind::Execute, start: 0x0205, end: 0x0205 }]);
+    }
+
+    #[test]
+    fn symbols() {
+        // JMP $8010, the ROM is mirrored at $C000
+        let mut debugger: Debugger = create_debugger(&[0x4C, 0x10, 0x80]);
+        let mut symbols: SymbolTable = SymbolTable::new();
+        symbols.parse_nl("$0200#start#", None).unwrap();
+        symbols.parse_nl("$8010#handler#Main loop", Some(0)).unwrap();
+        debugger.p_cpu.lock().unwrap().o_symbols = Some(Arc::new(symbols));
+        assert_eq!(debugger.execute("b handler"), Ok(false));
+        assert_eq!(debugger.prg_rom_breakpoints, vec![0x10]);
+        assert_eq!(debugger.parse_address("handler"), Ok(0x8010));
+        assert_eq!(debugger.parse_address("$0010"), Ok(0x0010));
+        assert!(debugger.parse_address("missing").is_err());
+        assert_eq!(debugger.p_cpu.lock().unwrap().dissassemble(0x0200, 0x4C, vec![0x10, 0x80]).trim_end(), "JMP handler");
+        assert_eq!(run_until_pause(&mut debugger), 0x8010);
+        assert_eq!(debugger.address_name(0xC010), "$C010 (handler)");
+        assert_eq!(debugger.execute("bd handler"), Ok(false));
+        assert!(debugger.prg_rom_breakpoints.is_empty());
+    }
+
+    #[test]
+    fn quit() {
+        let mut debugger: Debugger = create_debugger(&[0xEA;16]);
+        assert_eq!(debugger.execute("b $0204"), Ok(false));
+        assert_eq!(debugger.execute("w w 10"), Ok(false));
+        assert_eq!(debugger.execute("q"), Ok(true));
+        assert!(debugger.p_quit_request.load(Ordering::Relaxed));
+        assert!(debugger.breakpoints.is_empty());
+        assert!(debugger.p_bus.lock().unwrap().watchpoints.is_empty());
+    }
+
+    #[test]
+    fn register_edits() {
+        let mut debugger: Debugger = create_debugger(&[]);
+        assert_eq!(debugger.execute("set a 42"), Ok(false));
+        assert_eq!(debugger.execute("set pc C000"), Ok(false));
+        assert_eq!(debugger.execute("set c 1"), Ok(false));
+        assert!(debugger.execute("set x 100").is_err());
+        assert!(debugger.execute("set q 1").is_err());
+        let cpu = debugger.p_cpu.lock().unwrap();
+        assert_eq!((cpu.a, cpu.pc), (0x42, 0xC000));
+        assert!(cpu.get_flag(Flag::Carry));
+    }
+
+    #[test]
+    fn disassembly_before_pc() {
+        // LDA #$01 ; STA $0300 ; NOP ; PC ; the decoding from $01FD would land in the middle of STA
+        let debugger: Debugger = create_debugger(&[0xA9, 0x01, 0x8D, 0x00, 0x03, 0xEA, 0xEA]);
+        let cpu = debugger.p_cpu.lock().unwrap();
+        assert_eq!(previous_instruction(&cpu, 0x0206, 3), 0x0200);
+        assert_eq!(previous_instruction(&cpu, 0x0206, 1), 0x0205);
+    }
+}
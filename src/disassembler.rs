@@ -0,0 +1,572 @@
+// Static disassembler of the PRG ROM, writes one ca65 source file per 16KB bank
+// The code is found by following the reset, NMI and IRQ vectors, then the jumps and the branches recursively
+// A Code/Data Logger file and symbol files can be given to improve the separation of the code and the data, and to name the labels
+
+// ===== IMPORTS =====
+
+use std::{collections::{BTreeMap, BTreeSet}, fmt::Write};
+
+use crate::cartridge::cartridge::INesHeader;
+use crate::code_data_logger::{CodeDataLogger, PRG_CODE, PRG_DATA};
+use crate::cpu::{enums::AdressingMode as am, instructions::{CpuInstruction, INSTRUCTIONS}};
+use crate::symbols::SymbolTable;
+
+// ===== CONSTANTS =====
+
+pub const PRG_BANK_SIZE: usize = 0x4000;
+
+// Offsets of the NMI, reset and IRQ vectors in the last bank
+const VECTORS: [(usize, &str);3] = [(0x3FFA, "nmi"), (0x3FFC, "reset"), (0x3FFE, "irq")];
+
+// Number of bytes per .byte line
+const BYTES_PER_LINE: usize = 16;
+
+// ===== ENUMS =====
+
+// What each byte of the PRG ROM has been found to be
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ByteKind {
+    Unknown,
+    Data,
+    Opcode,
+    Operand
+}
+
+// ===== DISASSEMBLER STRUCT =====
+
+pub struct Disassembler {
+    pub prg_rom: Vec<u8>,
+    // CPU address at which each bank is mapped ($8000 or $C000)
+    pub bank_addresses: Vec<u16>,
+    pub kinds: Vec<ByteKind>,
+    // PRG ROM offsets referenced by the code, they get a label
+    pub labels: BTreeSet<usize>,
+    o_code_data_logger: Option<CodeDataLogger>,
+    symbols: SymbolTable
+}
+
+impl Disassembler {
+    // The last bank is mapped at $C000 and the others at $8000, as with the mappers 0 to 3 after power on
+    // The Code/Data Logger gives the actual window of the banks it has seen
+    pub fn new(prg_rom: Vec<u8>, o_code_data_logger: Option<CodeDataLogger>, symbols: SymbolTable) -> Self {
+        let banks: usize = prg_rom.len() / PRG_BANK_SIZE;
+        let bank_addresses: Vec<u16> = (0..banks).map(|bank| {
+            let o_flags: Option<u8> = o_code_data_logger.as_ref().and_then(|code_data_logger| {
+                code_data_logger.prg_flags[bank*PRG_BANK_SIZE..(bank+1)*PRG_BANK_SIZE].iter().copied().find(|flags| *flags & (PRG_CODE | PRG_DATA) != 0)
+            });
+            match o_flags {
+                // Windows $C000 and $E000
+                Some(flags) if flags & 0x08 != 0 => 0xC000,
+                Some(_) => 0x8000,
+                None if bank == banks - 1 => 0xC000,
+                None => 0x8000
+            }
+        }).collect();
+        let kinds: Vec<ByteKind> = vec![ByteKind::Unknown;prg_rom.len()];
+        Disassembler {
+            prg_rom,
+            bank_addresses,
+            kinds,
+            labels: BTreeSet::new(),
+            o_code_data_logger,
+            symbols
+        }
+    }
+
+    pub fn banks(&self) -> usize {
+        self.bank_addresses.len()
+    }
+
+    fn word(&self, offset: usize) -> u16 {
+        self.prg_rom[offset] as u16 + ((self.prg_rom[offset + 1] as u16) << 8)
+    }
+
+    // ===== ANALYSIS =====
+
+    // Separates the code from the data
+    pub fn analyse(&mut self) {
+        if self.banks() == 0 {
+            return;
+        }
+        // The bytes only read as data by the game are never traced as code
+        if let Some(code_data_logger) = self.o_code_data_logger.as_ref() {
+            for (offset, flags) in code_data_logger.prg_flags.iter().enumerate().take(self.kinds.len()) {
+                if flags & (PRG_CODE | PRG_DATA) == PRG_DATA {
+                    self.kinds[offset] = ByteKind::Data;
+                }
+            }
+        }
+        // The vectors are in the last bank
+        let last_bank: usize = self.banks() - 1;
+        for (vector_offset, _) in VECTORS.iter() {
+            let offset: usize = last_bank*PRG_BANK_SIZE + vector_offset;
+            let address: u16 = self.word(offset);
+            for kind in self.kinds[offset..offset + 2].iter_mut() {
+                *kind = ByteKind::Data;
+            }
+            if let Some(target) = self.prg_rom_offset(address, last_bank) {
+                self.labels.insert(target);
+                self.trace(target);
+            }
+        }
+        // Then the code executed during the logging that couldn't be reached statically (jump tables, RTS tricks...)
+        let code_offsets: Vec<usize> = match self.o_code_data_logger.as_ref() {
+            Some(code_data_logger) => (0..self.kinds.len()).filter(|offset| code_data_logger.prg_flags[*offset] & PRG_CODE != 0).collect(),
+            None => Vec::new()
+        };
+        for offset in code_offsets {
+            if self.kinds[offset] == ByteKind::Unknown {
+                self.trace(offset);
+            }
+        }
+    }
+
+    // Follows the code from an offset until the end of each path (RTS, RTI, JMP, BRK or a byte already known)
+    fn trace(&mut self, start: usize) {
+        let mut to_trace: Vec<usize> = vec![start];
+        while let Some(mut offset) = to_trace.pop() {
+            let bank: usize = offset / PRG_BANK_SIZE;
+            loop {
+                if self.kinds[offset] != ByteKind::Unknown {
+                    break;
+                }
+                let opcode: u8 = self.prg_rom[offset];
+                let instruction: &CpuInstruction = &INSTRUCTIONS[opcode as usize];
+                let length: usize = instruction.bytes as usize;
+                // The unofficial opcodes are most likely data, unless the game has been seen executing them
+                if !is_official(opcode) && !self.logged_as_code(offset) {
+                    break;
+                }
+                if (offset % PRG_BANK_SIZE) + length > PRG_BANK_SIZE || self.kinds[offset + 1..offset + length].iter().any(|kind| *kind != ByteKind::Unknown) {
+                    break;
+                }
+                self.kinds[offset] = ByteKind::Opcode;
+                for kind in self.kinds[offset + 1..offset + length].iter_mut() {
+                    *kind = ByteKind::Operand;
+                }
+
+                let address: u16 = self.address(offset);
+                if let Some(target_address) = operand_address(instruction.adressing_mode, address, &self.prg_rom[offset + 1..offset + length]) {
+                    if let Some(target) = self.prg_rom_offset(target_address, bank) {
+                        self.labels.insert(target);
+                        // JMP and JSR to an absolute address, and the branches, go on at the target
+                        if let (am::Relative, _) | (am::Absolute, 0x4C) | (am::Absolute, 0x20) = (instruction.adressing_mode, opcode) {
+                            to_trace.push(target);
+                        }
+                    }
+                }
+                // End of the path
+                if let "JMP" | "RTS" | "RTI" | "BRK" | "JAM" = instruction.name {
+                    break;
+                }
+                offset += length;
+                if offset % PRG_BANK_SIZE == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn logged_as_code(&self, offset: usize) -> bool {
+        match self.o_code_data_logger.as_ref() {
+            Some(code_data_logger) => code_data_logger.prg_flags.get(offset).is_some_and(|flags| flags & PRG_CODE != 0),
+            None => false
+        }
+    }
+
+    // ===== ADDRESSES =====
+
+    // CPU address of a byte of the PRG ROM
+    pub fn address(&self, offset: usize) -> u16 {
+        self.bank_addresses[offset / PRG_BANK_SIZE] + (offset % PRG_BANK_SIZE) as u16
+    }
+
+    // Offset in the PRG ROM of a CPU address seen from a bank
+    // The address is in the bank itself, or in the only bank mapped at this address
+    pub fn prg_rom_offset(&self, address: u16, from_bank: usize) -> Option<usize> {
+        if address < 0x8000 {
+            return None;
+        }
+        let bank_address: u16 = address & 0xC000;
+        let bank: usize = match self.bank_addresses[from_bank] == bank_address {
+            true => from_bank,
+            false => {
+                let mut banks = (0..self.banks()).filter(|bank| self.bank_addresses[*bank] == bank_address);
+                match (banks.next(), banks.next()) {
+                    (Some(bank), None) => bank,
+                    _ => return None
+                }
+            }
+        };
+        Some(bank*PRG_BANK_SIZE + (address - bank_address) as usize)
+    }
+
+    // ===== SOURCE FILES =====
+
+    // ca65 source of a bank, in the segment BANK_XX
+    pub fn bank_source(&self, bank: usize) -> String {
+        let start: usize = bank*PRG_BANK_SIZE;
+        let end: usize = start + PRG_BANK_SIZE;
+
+        // Names of the labels of the bank
+        let mut label_names: BTreeMap<usize, String> = BTreeMap::new();
+        for offset in start..end {
+            if let Some(name) = self.label_name(offset) {
+                if !label_names.values().any(|other| *other == name) {
+                    label_names.insert(offset, name);
+                }
+            }
+        }
+        let mut source_file: SourceFile = SourceFile {
+            disassembler: self,
+            bank,
+            label_names,
+            constants: BTreeMap::new()
+        };
+
+        let mut body: String = String::new();
+        let mut data: Vec<u8> = Vec::new();
+        let mut data_address: u16 = 0;
+        for offset in start..end {
+            let address: u16 = self.address(offset);
+            let o_label: Option<&String> = source_file.label_names.get(&offset);
+            let o_comment: Option<String> = self.comment(offset);
+            let is_instruction: bool = self.kinds[offset] == ByteKind::Opcode;
+            // The vectors are written as words, their second byte is skipped like the operands
+            let vector_byte: Option<usize> = match bank == self.banks() - 1 {
+                true => VECTORS.iter().map(|(vector_offset, _)| start + vector_offset).find(|vector| offset == *vector || offset == *vector + 1).map(|vector| offset - vector),
+                false => None
+            };
+            if self.kinds[offset] == ByteKind::Operand || vector_byte == Some(1) {
+                continue;
+            }
+
+            // A line of data ends at the next label, comment, instruction or vector
+            if o_label.is_some() || o_comment.is_some() || is_instruction || vector_byte.is_some() || data.len() == BYTES_PER_LINE {
+                write_data(&mut body, &data, data_address);
+                data.clear();
+            }
+            if let Some(label) = o_label {
+                let _ = writeln!(body, "{}:", label);
+            }
+            let line: String = match (is_instruction, vector_byte) {
+                (true, _) => {
+                    let length: usize = INSTRUCTIONS[self.prg_rom[offset] as usize].bytes as usize;
+                    source_file.instruction(offset, &self.prg_rom[offset..offset + length])
+                },
+                (false, Some(_)) => format!(".word {}", source_file.operand(self.word(offset), false)),
+                (false, None) => {
+                    if data.is_empty() {
+                        data_address = address;
+                    }
+                    data.push(self.prg_rom[offset]);
+                    continue;
+                }
+            };
+            let _ = write!(body, "    {}", line);
+            let _ = match o_comment {
+                Some(comment) => writeln!(body, "{}; ${:04X} {}", padding(&line), address, comment),
+                None => writeln!(body, "{}; ${:04X}", padding(&line), address)
+            };
+        }
+        write_data(&mut body, &data, data_address);
+
+        let mut source: String = String::new();
+        let _ = writeln!(source, "; Bank {} of the PRG ROM, mapped at ${:04X}", bank, self.bank_addresses[bank]);
+        let _ = writeln!(source, "; Disassembled by nes-disasm, assembled with ca65");
+        let _ = writeln!(source);
+        // The symbols outside of the bank are defined before the code, so that ca65 knows the size of the zero page operands
+        if !source_file.constants.is_empty() {
+            for (name, address) in source_file.constants.iter() {
+                let _ = match *address < 0x100 {
+                    true => writeln!(source, "{} = ${:02X}", name, address),
+                    false => writeln!(source, "{} = ${:04X}", name, address)
+                };
+            }
+            let _ = writeln!(source);
+        }
+        let _ = writeln!(source, ".segment \"BANK_{:02X}\"", bank);
+        let _ = writeln!(source);
+        source.push_str(&body);
+        source
+    }
+
+    // Name of the label of a byte, given by the symbols or built from its address
+    fn label_name(&self, offset: usize) -> Option<String> {
+        if self.kinds[offset] == ByteKind::Operand {
+            return None;
+        }
+        let address: u16 = self.address(offset);
+        if let Some(symbol) = self.symbols.lookup(address, Some(offset)) {
+            if is_identifier(&symbol.name) {
+                return Some(symbol.name.clone());
+            }
+        }
+        if !self.labels.contains(&offset) {
+            return None;
+        }
+        let vectors_start: usize = (self.banks() - 1)*PRG_BANK_SIZE;
+        match VECTORS.iter().find(|(vector_offset, _)| {
+            let vector: u16 = self.word(vectors_start + vector_offset);
+            self.prg_rom_offset(vector, self.banks() - 1) == Some(offset)
+        }) {
+            Some((_, name)) => Some(String::from(*name)),
+            None => Some(format!("L{:04X}", address))
+        }
+    }
+
+    fn comment(&self, offset: usize) -> Option<String> {
+        let symbol = self.symbols.lookup(self.address(offset), Some(offset))?;
+        match symbol.comment.is_empty() {
+            true => None,
+            false => Some(symbol.comment.replace('\n', " "))
+        }
+    }
+
+    // ld65 configuration placing the header, the banks and the CHR ROM in the iNES file
+    pub fn linker_config(&self, chr_rom_size: usize) -> String {
+        let mut config: String = String::from("MEMORY {\n    HEADER: start = $0000, size = $0010, file = %O, fill = yes;\n");
+        for (bank, address) in self.bank_addresses.iter().enumerate() {
+            let _ = writeln!(config, "    PRG_{:02X}: start = ${:04X}, size = $4000, file = %O, fill = yes;", bank, address);
+        }
+        if chr_rom_size > 0 {
+            let _ = writeln!(config, "    CHR: start = $0000, size = ${:X}, file = %O, fill = yes;", chr_rom_size);
+        }
+        config.push_str("}\n\nSEGMENTS {\n    HEADER: load = HEADER, type = ro;\n");
+        for bank in 0..self.banks() {
+            let _ = writeln!(config, "    BANK_{:02X}: load = PRG_{:02X}, type = ro;", bank, bank);
+        }
+        if chr_rom_size > 0 {
+            config.push_str("    CHR: load = CHR, type = ro;\n");
+        }
+        config.push_str("}\n");
+        config
+    }
+}
+
+// ===== SOURCE FILE STRUCT =====
+
+// Names used in the source file of a bank
+struct SourceFile<'a> {
+    disassembler: &'a Disassembler,
+    bank: usize,
+    label_names: BTreeMap<usize, String>,
+    // Symbols used by the bank and defined outside of it
+    constants: BTreeMap<String, u16>
+}
+
+impl<'a> SourceFile<'a> {
+    fn instruction(&mut self, offset: usize, bytes: &[u8]) -> String {
+        let opcode: u8 = bytes[0];
+        let instruction: &CpuInstruction = &INSTRUCTIONS[opcode as usize];
+        // ca65 doesn't assemble the unofficial opcodes of the 6502
+        if !is_official(opcode) {
+            let values: Vec<String> = bytes.iter().map(|byte| format!("${:02X}", byte)).collect();
+            return format!(".byte {} ; {}", values.join(", "), instruction.name);
+        }
+        let parameters: &[u8] = &bytes[1..];
+        let word: u16 = match parameters.len() {
+            2 => parameters[0] as u16 + ((parameters[1] as u16) << 8),
+            1 => parameters[0] as u16,
+            _ => 0
+        };
+        let operand: String = match instruction.adressing_mode {
+            am::Implicit => String::new(),
+            // TAX and TXA are implicit too
+            am::Accumulator => match opcode {
+                0xAA | 0x8A => String::new(),
+                _ => String::from("A")
+            },
+            am::Immediate => format!("#${:02X}", word),
+            am::ZeroPage => self.operand(word, true),
+            am::ZeroPageX => format!("{},X", self.operand(word, true)),
+            am::ZeroPageY => format!("{},Y", self.operand(word, true)),
+            am::Relative => {
+                let address: u16 = self.disassembler.address(offset).wrapping_add(2).wrapping_add((parameters[0] as i8) as u16);
+                self.operand(address, false)
+            },
+            // The a: prefix keeps the absolute addressing of the addresses in the zero page
+            am::Absolute => self.absolute_operand(word),
+            am::AbsoluteX => format!("{},X", self.absolute_operand(word)),
+            am::AbsoluteY => format!("{},Y", self.absolute_operand(word)),
+            am::Indirect => format!("({})", self.operand(word, false)),
+            am::IndirectX => format!("({},X)", self.operand(word, true)),
+            am::IndirectY => format!("({}),Y", self.operand(word, true)),
+            am::NoMode => String::new()
+        };
+        match operand.is_empty() {
+            true => String::from(instruction.name),
+            false => format!("{} {}", instruction.name, operand)
+        }
+    }
+
+    fn absolute_operand(&mut self, address: u16) -> String {
+        let operand: String = self.operand(address, false);
+        match address < 0x100 {
+            true => format!("a:{}", operand),
+            false => operand
+        }
+    }
+
+    // Name of an address : a label of the bank, a symbol, or its hexadecimal value
+    fn operand(&mut self, address: u16, zero_page: bool) -> String {
+        let o_offset: Option<usize> = self.disassembler.prg_rom_offset(address, self.bank);
+        if let Some(offset) = o_offset {
+            if let Some(label) = self.label_names.get(&offset) {
+                return label.clone();
+            }
+        }
+        let o_prg_rom_offset: Option<usize> = match address {
+            0x0000..=0x7FFF => None,
+            _ => o_offset
+        };
+        if let Some(symbol) = self.disassembler.symbols.lookup(address, o_prg_rom_offset) {
+            let name: &String = &symbol.name;
+            let is_label: bool = self.label_names.values().any(|label| label == name);
+            let defined_elsewhere: bool = self.constants.get(name).is_some_and(|value| *value != address);
+            if is_identifier(name) && !is_label && !defined_elsewhere {
+                self.constants.insert(name.clone(), address);
+                return name.clone();
+            }
+        }
+        match zero_page {
+            true => format!("${:02X}", address),
+            false => format!("${:04X}", address)
+        }
+    }
+}
+
+// ===== HELPER FUNCTIONS =====
+
+// Official opcodes of the 6502, the others are written as bytes
+pub fn is_official(opcode: u8) -> bool {
+    match INSTRUCTIONS[opcode as usize].name {
+        "NOP" => opcode == 0xEA,
+        "SBC" => opcode != 0xEB,
+        "JAM" | "SLO" | "RLA" | "SRE" | "RRA" | "SAX" | "LAX" | "DCP" | "ISB" | "ANC" | "ASR" | "ARR" | "ANE" | "LXA" | "SBX" | "SHA" | "SHS" | "SHY" | "SHX" | "LAS" => false,
+        _ => true
+    }
+}
+
+// Address of the operand of an instruction, known without executing it
+fn operand_address(mode: am, address: u16, parameters: &[u8]) -> Option<u16> {
+    match mode {
+        am::Relative => Some(address.wrapping_add(2).wrapping_add((parameters[0] as i8) as u16)),
+        am::Absolute | am::AbsoluteX | am::AbsoluteY | am::Indirect => Some(parameters[0] as u16 + ((parameters[1] as u16) << 8)),
+        _ => None
+    }
+}
+
+// ca65 identifiers : letters, digits and underscores, not starting with a digit, and not a register name
+fn is_identifier(name: &str) -> bool {
+    let mut characters = name.chars();
+    match characters.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => (),
+        _ => return false
+    }
+    characters.all(|character| character.is_ascii_alphanumeric() || character == '_') && !matches!(name.to_ascii_uppercase().as_str(), "A" | "X" | "Y" | "S")
+}
+
+fn padding(line: &str) -> String {
+    " ".repeat(32usize.saturating_sub(line.len()).max(1))
+}
+
+fn write_data(body: &mut String, data: &[u8], address: u16) {
+    if data.is_empty() {
+        return;
+    }
+    let values: Vec<String> = data.iter().map(|byte| format!("${:02X}", byte)).collect();
+    let line: String = format!(".byte {}", values.join(","));
+    let _ = writeln!(body, "    {}{}; ${:04X}", line, padding(&line), address);
+}
+
+// ca65 source of the iNES header, and of the CHR ROM included from a binary file
+pub fn header_source(header: &INesHeader, o_chr_file: Option<&str>) -> String {
+    let mut source: String = String::from("; iNES header and CHR ROM\n\n.segment \"HEADER\"\n\n");
+    let _ = writeln!(
+        source,
+        "    .byte \"NES\", $1A, ${:02X}, ${:02X}, ${:02X}, ${:02X}, ${:02X}",
+        header.n_prg_rom, header.n_chr_rom, header.control_1, header.control_2, header.n_ram_banks
+    );
+    let unused: Vec<String> = header.unused.iter().map(|byte| format!("${:02X}", byte)).collect();
+    let _ = writeln!(source, "    .byte {}", unused.join(", "));
+    if let Some(chr_file) = o_chr_file {
+        let _ = writeln!(source, "\n.segment \"CHR\"\n\n    .incbin \"{}\"", chr_file);
+    }
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols::{Symbol, SymbolLocation};
+
+    // 16KB bank mapped at $C000, with the program at $C000 and the vectors pointing to it
+    fn create_prg_rom(program: &[u8]) -> Vec<u8> {
+        let mut prg_rom: Vec<u8> = vec![0xFF;PRG_BANK_SIZE];
+        prg_rom[..program.len()].copy_from_slice(program);
+        prg_rom[0x3FFA..].copy_from_slice(&[0x00, 0xC0, 0x00, 0xC0, 0x00, 0xC0]);
+        prg_rom
+    }
+
+    // Symbol table with labels in the PRG ROM
+    fn prg_rom_symbols(symbols: &[(usize, &str)]) -> SymbolTable {
+        let mut table: SymbolTable = SymbolTable::new();
+        for (offset, name) in symbols.iter() {
+            table.symbols.insert(SymbolLocation::PrgRom(*offset), Symbol { name: String::from(*name), comment: String::from("entry") });
+        }
+        table
+    }
+
+    #[test]
+    fn code_and_data() {
+        // reset: LDA $0010 ; BNE $C00B ; JSR $C00C ; JMP $C000 ; $C00B: data ; $C00C: RTS
+        let program: [u8;13] = [0xAD, 0x10, 0x00, 0xD0, 0x06, 0x20, 0x0C, 0xC0, 0x4C, 0x00, 0xC0, 0xFF, 0x60];
+        let mut prg_rom: Vec<u8> = create_prg_rom(&program);
+        // nmi and irq point to the subroutine
+        prg_rom[0x3FFA..].copy_from_slice(&[0x0C, 0xC0, 0x00, 0xC0, 0x0C, 0xC0]);
+        let mut disassembler: Disassembler = Disassembler::new(prg_rom, None, SymbolTable::new());
+        disassembler.analyse();
+        assert_eq!(disassembler.bank_addresses, vec![0xC000]);
+        assert_eq!(disassembler.kinds[0..3], [ByteKind::Opcode, ByteKind::Operand, ByteKind::Operand]);
+        assert_eq!(disassembler.kinds[0x0B], ByteKind::Unknown);
+        assert_eq!(disassembler.kinds[0x0C], ByteKind::Opcode);
+
+        let source: String = disassembler.bank_source(0);
+        assert!(source.contains(".segment \"BANK_00\""));
+        assert!(source.contains("reset:\n    LDA a:$0010"));
+        assert!(source.contains("    BNE LC00B"));
+        assert!(source.contains("    JSR nmi"));
+        assert!(source.contains("    JMP reset"));
+        assert!(source.contains("LC00B:\n    .byte $FF "));
+        assert!(source.contains("nmi:\n    RTS"));
+        assert!(source.contains("    .word nmi "));
+        assert!(source.contains("    .word reset "));
+    }
+
+    #[test]
+    fn code_data_logger_and_symbols() {
+        // reset: JMP ($0200), the game jumps to $C010 through the pointer
+        let mut prg_rom: Vec<u8> = create_prg_rom(&[0x6C, 0x00, 0x02]);
+        prg_rom[0x10] = 0x85;
+        prg_rom[0x11] = 0x20;
+        prg_rom[0x12] = 0x60;
+        let mut code_data_logger: CodeDataLogger = CodeDataLogger::new(PRG_BANK_SIZE, 0);
+        for offset in 0x10..0x13 {
+            code_data_logger.log_prg(offset, 0xC000 + offset as u16, PRG_CODE);
+        }
+        code_data_logger.log_prg(0x20, 0xC020, PRG_DATA);
+        let mut symbols: SymbolTable = prg_rom_symbols(&[(0x10, "handler")]);
+        symbols.symbols.insert(SymbolLocation::Cpu(0x20), Symbol { name: String::from("counter"), comment: String::new() });
+        let mut disassembler: Disassembler = Disassembler::new(prg_rom, Some(code_data_logger), symbols);
+        disassembler.analyse();
+        assert_eq!(disassembler.kinds[0x10], ByteKind::Opcode);
+        assert_eq!(disassembler.kinds[0x20], ByteKind::Data);
+
+        let source: String = disassembler.bank_source(0);
+        assert!(source.contains("counter = $20\n"));
+        assert!(source.contains("handler:\n    STA counter"));
+        assert!(source.contains("; $C010 entry"));
+        assert!(source.contains("    JMP ($0200)"));
+    }
+}
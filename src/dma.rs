@@ -0,0 +1,79 @@
+// Represents the DMA unit of the 2A03
+// OAM DMA copies a page of CPU memory to the PPU OAM, DMC DMA fetches the samples of the DMC
+// Both halt the CPU on its next read cycle, then alternate get (read) and put (write) cycles
+
+// ===== DMA STRUCT =====
+
+pub struct DMA {
+    // Requested by a write to $4014, the page is the high byte of the source address
+    pub oam_pending: bool,
+    pub oam_page: u8,
+    // Number of get and put cycles of the OAM DMA, and byte read on the last get cycle
+    pub oam_count: u16,
+    pub oam_data: u8,
+
+    // Requested by the DMC when its sample buffer is empty
+    pub dmc_pending: bool,
+    // The DMC DMA reads after a halt cycle and a dummy cycle
+    // Cycles of an OAM DMA count as such when both transfers overlap
+    pub dmc_halt: bool,
+    pub dmc_dummy: bool,
+    // The DMC DMA reads on the current cycle if it is a get cycle
+    pub dmc_read: bool
+}
+
+impl Default for DMA {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DMA {
+    pub fn new() -> Self {
+        DMA {
+            oam_pending: false,
+            oam_page: 0,
+            oam_count: 0,
+            oam_data: 0,
+
+            dmc_pending: false,
+            dmc_halt: false,
+            dmc_dummy: false,
+            dmc_read: false
+        }
+    }
+
+    pub fn request_oam(&mut self, page: u8) {
+        self.oam_pending = true;
+        self.oam_page = page;
+        self.oam_count = 0;
+    }
+
+    pub fn request_dmc(&mut self) {
+        if !self.dmc_pending {
+            self.dmc_pending = true;
+            self.dmc_halt = true;
+            self.dmc_dummy = true;
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.oam_pending || self.dmc_pending
+    }
+
+    // Returns true when the DMC DMA can read on the next get cycle
+    pub fn dmc_ready(&self) -> bool {
+        self.dmc_pending && !self.dmc_halt && !self.dmc_dummy
+    }
+
+    // Called at the beginning of each cycle where the CPU is halted
+    pub fn start_cycle(&mut self) {
+        self.dmc_read = self.dmc_ready();
+        if self.dmc_halt {
+            self.dmc_halt = false;
+        }
+        else if self.dmc_dummy {
+            self.dmc_dummy = false;
+        }
+    }
+}
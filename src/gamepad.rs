@@ -0,0 +1,456 @@
+// Gamepad support of the frontend
+// The gamepads are read through a GamepadSource, so that they can be replaced by a virtual gamepad
+// The gilrs source is only available with the "gamepad" feature
+
+// ===== IMPORTS =====
+
+use std::{collections::VecDeque, fs, path::Path};
+
+use log::{info, warn};
+
+use nes_emulator::input::controller::ControllerInput;
+
+// ===== CONSTANTS =====
+
+pub const MAX_GAMEPADS: usize = 4;
+pub const DEFAULT_DEADZONE: f32 = 0.5;
+
+// ===== ENUMS =====
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight
+}
+
+// The Y axes are positive upwards
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadAxis {
+    LeftStickX = 0,
+    LeftStickY = 1,
+    DPadX = 2,
+    DPadY = 3
+}
+
+// The usize is the identifier of the gamepad given by the source
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    Connected(usize),
+    Disconnected(usize),
+    Button(usize, GamepadButton, bool),
+    Axis(usize, GamepadAxis, f32)
+}
+
+// ===== TRAIT =====
+
+pub trait GamepadSource {
+    // Returns the next pending event, or None if there are no more events for now
+    fn next_event(&mut self) -> Option<GamepadEvent>;
+}
+
+// ===== VIRTUAL GAMEPAD =====
+
+// Source with events pushed by hand
+// Used when gamepad support is disabled, and for testing
+pub struct VirtualGamepad {
+    pub events: VecDeque<GamepadEvent>
+}
+
+impl VirtualGamepad {
+    pub fn new() -> Self {
+        VirtualGamepad {
+            events: VecDeque::new()
+        }
+    }
+}
+
+impl GamepadSource for VirtualGamepad {
+    fn next_event(&mut self) -> Option<GamepadEvent> {
+        self.events.pop_front()
+    }
+}
+
+// ===== GILRS SOURCE =====
+
+#[cfg(feature = "gamepad")]
+pub struct GilrsSource {
+    pub gilrs: gilrs::Gilrs,
+    // Gamepads already connected when the source is created
+    pub pending: VecDeque<GamepadEvent>
+}
+
+#[cfg(feature = "gamepad")]
+impl GilrsSource {
+    pub fn new() -> Result<Self, String> {
+        let gilrs: gilrs::Gilrs = gilrs::Gilrs::new().map_err(|why| why.to_string())?;
+        let pending: VecDeque<GamepadEvent> = gilrs.gamepads().map(|(id, _)| GamepadEvent::Connected(usize::from(id))).collect();
+        Ok(GilrsSource {
+            gilrs,
+            pending
+        })
+    }
+
+    fn convert_button(button: gilrs::Button) -> Option<GamepadButton> {
+        match button {
+            gilrs::Button::South => Some(GamepadButton::South),
+            gilrs::Button::East => Some(GamepadButton::East),
+            gilrs::Button::North => Some(GamepadButton::North),
+            gilrs::Button::West => Some(GamepadButton::West),
+            gilrs::Button::LeftTrigger => Some(GamepadButton::LeftTrigger),
+            gilrs::Button::RightTrigger => Some(GamepadButton::RightTrigger),
+            gilrs::Button::Select => Some(GamepadButton::Select),
+            gilrs::Button::Start => Some(GamepadButton::Start),
+            gilrs::Button::DPadUp => Some(GamepadButton::DPadUp),
+            gilrs::Button::DPadDown => Some(GamepadButton::DPadDown),
+            gilrs::Button::DPadLeft => Some(GamepadButton::DPadLeft),
+            gilrs::Button::DPadRight => Some(GamepadButton::DPadRight),
+            _ => None
+        }
+    }
+
+    fn convert_axis(axis: gilrs::Axis) -> Option<GamepadAxis> {
+        match axis {
+            gilrs::Axis::LeftStickX => Some(GamepadAxis::LeftStickX),
+            gilrs::Axis::LeftStickY => Some(GamepadAxis::LeftStickY),
+            gilrs::Axis::DPadX => Some(GamepadAxis::DPadX),
+            gilrs::Axis::DPadY => Some(GamepadAxis::DPadY),
+            _ => None
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadSource for GilrsSource {
+    fn next_event(&mut self) -> Option<GamepadEvent> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+        // Skips the events that are not used by the emulator
+        loop {
+            let event: gilrs::Event = self.gilrs.next_event()?;
+            let id: usize = usize::from(event.id);
+            let o_converted: Option<GamepadEvent> = match event.event {
+                gilrs::EventType::Connected => Some(GamepadEvent::Connected(id)),
+                gilrs::EventType::Disconnected => Some(GamepadEvent::Disconnected(id)),
+                gilrs::EventType::ButtonPressed(button, _) => Self::convert_button(button).map(|button| GamepadEvent::Button(id, button, true)),
+                gilrs::EventType::ButtonReleased(button, _) => Self::convert_button(button).map(|button| GamepadEvent::Button(id, button, false)),
+                gilrs::EventType::AxisChanged(axis, value, _) => Self::convert_axis(axis).map(|axis| GamepadEvent::Axis(id, axis, value)),
+                _ => None
+            };
+            if o_converted.is_some() {
+                return o_converted;
+            }
+        }
+    }
+}
+
+// Returns the gilrs source if it is available, or an empty virtual gamepad
+pub fn create_gamepad_source() -> Box<dyn GamepadSource> {
+    #[cfg(feature = "gamepad")]
+    {
+        match GilrsSource::new() {
+            Ok(source) => return Box::new(source),
+            Err(why) => warn!("Couldn't initialize gamepads: {}", why)
+        }
+    }
+    Box::new(VirtualGamepad::new())
+}
+
+// ===== MAPPING =====
+
+#[derive(Debug, Clone)]
+pub struct GamepadMapping {
+    pub buttons: Vec<(GamepadButton, ControllerInput)>,
+    // Axis values below this threshold are ignored
+    pub deadzone: f32
+}
+
+impl GamepadMapping {
+    // NES layout : A is the right face button, B the bottom one
+    pub fn new() -> Self {
+        GamepadMapping {
+            buttons: vec![
+                (GamepadButton::East, ControllerInput::A),
+                (GamepadButton::South, ControllerInput::B),
+                (GamepadButton::North, ControllerInput::A),
+                (GamepadButton::West, ControllerInput::B),
+                (GamepadButton::Select, ControllerInput::Select),
+                (GamepadButton::Start, ControllerInput::Start),
+                (GamepadButton::DPadUp, ControllerInput::Up),
+                (GamepadButton::DPadDown, ControllerInput::Down),
+                (GamepadButton::DPadLeft, ControllerInput::Left),
+                (GamepadButton::DPadRight, ControllerInput::Right)
+            ],
+            deadzone: DEFAULT_DEADZONE
+        }
+    }
+
+    // Loads a mapping from a config file
+    // Each line is either "deadzone = <value>" or "<gamepad button> = <NES button>"
+    // The buttons given in the file replace the default mapping of these buttons
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content: String = fs::read_to_string(path).map_err(|why| format!("Couldn't read {}: {}", path.display(), why))?;
+        Self::from_config(&content)
+    }
+
+    pub fn from_config(content: &str) -> Result<Self, String> {
+        let mut mapping: GamepadMapping = GamepadMapping::new();
+        for (index, line) in content.lines().enumerate() {
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(format!("Line {}: expected \"key = value\"", index + 1))
+            };
+            if key == "deadzone" {
+                match value.parse::<f32>() {
+                    Ok(deadzone) if (0.0..1.0).contains(&deadzone) => mapping.deadzone = deadzone,
+                    _ => return Err(format!("Line {}: invalid deadzone {}", index + 1, value))
+                }
+                continue;
+            }
+            let button: GamepadButton = parse_gamepad_button(key).ok_or(format!("Line {}: unknown gamepad button {}", index + 1, key))?;
+            let input: ControllerInput = parse_controller_input(value).ok_or(format!("Line {}: unknown NES button {}", index + 1, value))?;
+            mapping.buttons.retain(|(mapped, _)| *mapped != button);
+            mapping.buttons.push((button, input));
+        }
+        Ok(mapping)
+    }
+
+    // Returns the NES buttons held for a given axis position
+    pub fn axis_to_buttons(&self, axis: GamepadAxis, value: f32) -> u8 {
+        let (negative, positive) = match axis {
+            GamepadAxis::LeftStickX | GamepadAxis::DPadX => (ControllerInput::Left, ControllerInput::Right),
+            GamepadAxis::LeftStickY | GamepadAxis::DPadY => (ControllerInput::Down, ControllerInput::Up)
+        };
+        if value <= -self.deadzone {
+            negative as u8
+        }
+        else if value >= self.deadzone {
+            positive as u8
+        }
+        else {
+            0
+        }
+    }
+}
+
+fn parse_gamepad_button(name: &str) -> Option<GamepadButton> {
+    match name.to_lowercase().as_str() {
+        "south" => Some(GamepadButton::South),
+        "east" => Some(GamepadButton::East),
+        "north" => Some(GamepadButton::North),
+        "west" => Some(GamepadButton::West),
+        "lefttrigger" => Some(GamepadButton::LeftTrigger),
+        "righttrigger" => Some(GamepadButton::RightTrigger),
+        "select" => Some(GamepadButton::Select),
+        "start" => Some(GamepadButton::Start),
+        "dpadup" => Some(GamepadButton::DPadUp),
+        "dpaddown" => Some(GamepadButton::DPadDown),
+        "dpadleft" => Some(GamepadButton::DPadLeft),
+        "dpadright" => Some(GamepadButton::DPadRight),
+        _ => None
+    }
+}
+
+fn parse_controller_input(name: &str) -> Option<ControllerInput> {
+    match name.to_lowercase().as_str() {
+        "a" => Some(ControllerInput::A),
+        "b" => Some(ControllerInput::B),
+        "select" => Some(ControllerInput::Select),
+        "start" => Some(ControllerInput::Start),
+        "up" => Some(ControllerInput::Up),
+        "down" => Some(ControllerInput::Down),
+        "left" => Some(ControllerInput::Left),
+        "right" => Some(ControllerInput::Right),
+        _ => None
+    }
+}
+
+// ===== GAMEPADS STRUCT =====
+
+// State of a gamepad assigned to a player
+#[derive(Debug, Clone, Copy)]
+pub struct GamepadState {
+    pub id: usize,
+    pub buttons: u8,
+    // NES buttons held by each axis
+    pub axes: [u8;4]
+}
+
+pub struct Gamepads {
+    pub source: Box<dyn GamepadSource>,
+    pub mapping: GamepadMapping,
+    // Gamepad of each player, given in connection order
+    pub players: [Option<GamepadState>;MAX_GAMEPADS]
+}
+
+impl Gamepads {
+    pub fn new(source: Box<dyn GamepadSource>, mapping: GamepadMapping) -> Self {
+        Gamepads {
+            source,
+            mapping,
+            players: [None;MAX_GAMEPADS]
+        }
+    }
+
+    // Handles every pending event of the source
+    pub fn poll(&mut self) {
+        while let Some(event) = self.source.next_event() {
+            self.process_event(event);
+        }
+    }
+
+    pub fn process_event(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected(id) => {
+                if self.find_player(id).is_some() {
+                    return;
+                }
+                match self.players.iter().position(|o_state| o_state.is_none()) {
+                    Some(player) => {
+                        self.players[player] = Some(GamepadState { id, buttons: 0, axes: [0;4] });
+                        info!("Gamepad {} connected as player {}", id, player + 1);
+                    },
+                    None => warn!("Gamepad {} ignored, {} gamepads are already connected", id, MAX_GAMEPADS)
+                }
+            },
+            GamepadEvent::Disconnected(id) => {
+                if let Some(player) = self.find_player(id) {
+                    self.players[player] = None;
+                    info!("Gamepad {} of player {} disconnected", id, player + 1);
+                }
+            },
+            GamepadEvent::Button(id, button, pressed) => {
+                let mut input: u8 = 0;
+                for (mapped, controller_input) in self.mapping.buttons.iter() {
+                    if *mapped == button {
+                        input |= *controller_input as u8;
+                    }
+                }
+                if let Some(state) = self.find_state(id) {
+                    if pressed {
+                        state.buttons |= input;
+                    }
+                    else {
+                        state.buttons &= !input;
+                    }
+                }
+            },
+            GamepadEvent::Axis(id, axis, value) => {
+                let buttons: u8 = self.mapping.axis_to_buttons(axis, value);
+                if let Some(state) = self.find_state(id) {
+                    state.axes[axis as usize] = buttons;
+                }
+            }
+        }
+    }
+
+    // Returns the NES buttons held on the gamepad of each player
+    pub fn read_controllers(&self) -> [u8;MAX_GAMEPADS] {
+        let mut controllers: [u8;MAX_GAMEPADS] = [0;MAX_GAMEPADS];
+        for (player, o_state) in self.players.iter().enumerate() {
+            if let Some(state) = o_state {
+                controllers[player] = state.axes.iter().fold(state.buttons, |buttons, axis| buttons | axis);
+            }
+        }
+        controllers
+    }
+
+    fn find_player(&self, id: usize) -> Option<usize> {
+        self.players.iter().position(|o_state| matches!(o_state, Some(state) if state.id == id))
+    }
+
+    fn find_state(&mut self, id: usize) -> Option<&mut GamepadState> {
+        self.players.iter_mut().flatten().find(|state| state.id == id)
+    }
+}
+
+// ===== TESTS =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_gamepads(events: Vec<GamepadEvent>) -> Gamepads {
+        let mut source: VirtualGamepad = VirtualGamepad::new();
+        source.events.extend(events);
+        let mut gamepads: Gamepads = Gamepads::new(Box::new(source), GamepadMapping::new());
+        gamepads.poll();
+        gamepads
+    }
+
+    #[test]
+    fn default_mapping() {
+        let gamepads: Gamepads = create_gamepads(vec![
+            GamepadEvent::Connected(7),
+            GamepadEvent::Button(7, GamepadButton::East, true),
+            GamepadEvent::Button(7, GamepadButton::Start, true),
+            GamepadEvent::Button(7, GamepadButton::DPadLeft, true),
+            GamepadEvent::Button(7, GamepadButton::DPadLeft, false)
+        ]);
+        assert_eq!(gamepads.read_controllers(), [ControllerInput::A as u8 | ControllerInput::Start as u8, 0, 0, 0]);
+    }
+
+    #[test]
+    fn hotplug() {
+        let mut gamepads: Gamepads = create_gamepads(vec![
+            GamepadEvent::Connected(1),
+            GamepadEvent::Connected(2),
+            GamepadEvent::Button(2, GamepadButton::South, true),
+            GamepadEvent::Disconnected(1)
+        ]);
+        assert_eq!(gamepads.read_controllers(), [0, ControllerInput::B as u8, 0, 0]);
+
+        // The first free player is given to the new gamepad
+        gamepads.process_event(GamepadEvent::Connected(3));
+        gamepads.process_event(GamepadEvent::Button(3, GamepadButton::Select, true));
+        assert_eq!(gamepads.read_controllers(), [ControllerInput::Select as u8, ControllerInput::B as u8, 0, 0]);
+
+        // Events of unknown gamepads are ignored
+        gamepads.process_event(GamepadEvent::Button(1, GamepadButton::Start, true));
+        assert_eq!(gamepads.read_controllers(), [ControllerInput::Select as u8, ControllerInput::B as u8, 0, 0]);
+    }
+
+    #[test]
+    fn axis_deadzone() {
+        let mut gamepads: Gamepads = create_gamepads(vec![
+            GamepadEvent::Connected(0),
+            GamepadEvent::Axis(0, GamepadAxis::LeftStickX, 0.3),
+            GamepadEvent::Axis(0, GamepadAxis::LeftStickY, 0.9)
+        ]);
+        assert_eq!(gamepads.read_controllers()[0], ControllerInput::Up as u8);
+
+        gamepads.process_event(GamepadEvent::Axis(0, GamepadAxis::LeftStickX, -0.6));
+        gamepads.process_event(GamepadEvent::Axis(0, GamepadAxis::LeftStickY, 0.0));
+        assert_eq!(gamepads.read_controllers()[0], ControllerInput::Left as u8);
+    }
+
+    #[test]
+    fn config_remapping() {
+        let mapping: GamepadMapping = GamepadMapping::from_config("# Comment\nsouth = A\nRightTrigger = start\ndeadzone = 0.25\n").unwrap();
+        assert_eq!(mapping.deadzone, 0.25);
+        let mut gamepads: Gamepads = Gamepads::new(Box::new(VirtualGamepad::new()), mapping);
+        gamepads.process_event(GamepadEvent::Connected(0));
+        gamepads.process_event(GamepadEvent::Button(0, GamepadButton::South, true));
+        gamepads.process_event(GamepadEvent::Button(0, GamepadButton::RightTrigger, true));
+        assert_eq!(gamepads.read_controllers()[0], ControllerInput::A as u8 | ControllerInput::Start as u8);
+
+        assert!(GamepadMapping::from_config("south = C").is_err());
+        assert!(GamepadMapping::from_config("deadzone = 2").is_err());
+    }
+}
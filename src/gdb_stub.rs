@@ -0,0 +1,535 @@
+// Server of the GDB remote serial protocol, used to debug the games from GDB frontends
+// The breakpoints, the watchpoints and the steps are handled by a debugger, as in the command line debugger
+// The emulation waits for a connection before the first instruction, and runs while GDB is detached
+// The registers are a, x, y, ps (8 bits), pc and sp (16 bits, in page 1), described in target.xml
+
+// ===== IMPORTS =====
+
+use std::{io::{ErrorKind, Read, Write}, net::{TcpListener, TcpStream}, sync::{Arc, Mutex}};
+
+use log::{info, warn};
+
+use crate::{bus::{Bus, STACK_OFFSET}, cpu::cpu::CPU, debugger::{AccessKind, Debugger, StepMode, StopReason, Watchpoint}, ppu::ppu::PPU};
+
+// ===== CONSTANTS =====
+
+// Number of instructions run between two checks of the connection
+const POLL_INTERVAL: u32 = 0x1000;
+
+// Sent by GDB to pause the emulation
+const INTERRUPT_BYTE: u8 = 0x03;
+
+// Signals of the stop replies
+const SIGINT: u8 = 2;
+const SIGTRAP: u8 = 5;
+
+const TARGET_XML: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0">
+  <feature name="org.nesmulator.m6502.core">
+    <reg name="a" bitsize="8" type="int" regnum="0"/>
+    <reg name="x" bitsize="8" type="int"/>
+    <reg name="y" bitsize="8" type="int"/>
+    <reg name="ps" bitsize="8" type="int"/>
+    <reg name="pc" bitsize="16" type="code_ptr"/>
+    <reg name="sp" bitsize="16" type="data_ptr"/>
+  </feature>
+</target>"#;
+
+// ===== GDB STUB STRUCT =====
+
+pub struct GdbStub {
+    pub debugger: Debugger,
+
+    pub listener: TcpListener,
+    pub o_stream: Option<TcpStream>,
+
+    // Number of instructions run since the connection was checked
+    pub poll_counter: u32
+}
+
+impl GdbStub {
+    // Listens on the local port, the emulation is paused until GDB connects
+    pub fn new(port: u16, p_cpu: Arc<Mutex<CPU>>, p_bus: Arc<Mutex<Bus>>, p_ppu: Arc<Mutex<PPU>>) -> Result<Self, String> {
+        let listener: TcpListener = TcpListener::bind(("127.0.0.1", port)).map_err(|why| format!("Couldn't listen on port {}: {}", port, why))?;
+        listener.set_nonblocking(true).map_err(|why| why.to_string())?;
+        let mut debugger: Debugger = Debugger::new(p_cpu, p_bus, p_ppu);
+        debugger.paused = true;
+        Ok(GdbStub {
+            debugger,
+
+            listener,
+            o_stream: None,
+
+            poll_counter: 0
+        })
+    }
+
+    // ===== EMULATION HOOK =====
+
+    // Called before each instruction
+    // Serves the requests of GDB while the emulation is paused
+    pub fn before_instruction(&mut self) {
+        self.poll_counter += 1;
+        if self.poll_counter >= POLL_INTERVAL {
+            self.poll_counter = 0;
+            self.poll();
+        }
+        if let Some(reason) = self.debugger.check_stop() {
+            self.debugger.paused = true;
+            self.debugger.o_step = None;
+            self.send_packet(&stop_reply(reason));
+        }
+        if self.debugger.paused {
+            self.serve();
+        }
+    }
+
+    // Accepts a connection, or checks if GDB asks for a pause, without blocking
+    fn poll(&mut self) {
+        match self.o_stream.as_mut() {
+            None => {
+                if let Ok((stream, address)) = self.listener.accept() {
+                    info!("GDB connected from {}", address);
+                    self.o_stream = Some(stream);
+                    // GDB expects the target to be stopped when it connects
+                    self.debugger.paused = true;
+                }
+            },
+            Some(stream) => {
+                let mut byte: [u8;1] = [0];
+                match stream.read(&mut byte) {
+                    Ok(0) => self.disconnect(),
+                    Ok(_) => {
+                        if byte[0] == INTERRUPT_BYTE {
+                            self.debugger.p_pause_request.store(true, std::sync::atomic::Ordering::Relaxed);
+                        }
+                    },
+                    Err(why) if why.kind() == ErrorKind::WouldBlock => (),
+                    Err(_) => self.disconnect()
+                }
+            }
+        }
+    }
+
+    // Reads and handles the packets until the emulation is resumed
+    fn serve(&mut self) {
+        while self.debugger.paused {
+            if self.o_stream.is_none() {
+                self.wait_for_connection();
+            }
+            let packet: String = match self.read_packet() {
+                Some(packet) => packet,
+                None => {
+                    self.disconnect();
+                    continue;
+                }
+            };
+            let (o_reply, resume): (Option<String>, bool) = self.handle_packet(&packet);
+            if let Some(reply) = o_reply {
+                self.send_packet(&reply);
+            }
+            if resume {
+                self.debugger.paused = false;
+            }
+        }
+        // The connection is checked without blocking while the emulation runs
+        if let Some(stream) = self.o_stream.as_ref() {
+            let _ = stream.set_nonblocking(true);
+        }
+    }
+
+    fn wait_for_connection(&mut self) {
+        info!("Waiting for GDB on {:?}", self.listener.local_addr());
+        let _ = self.listener.set_nonblocking(false);
+        match self.listener.accept() {
+            Ok((stream, address)) => {
+                info!("GDB connected from {}", address);
+                self.o_stream = Some(stream);
+            },
+            Err(why) => warn!("Couldn't accept the GDB connection: {}", why)
+        }
+        let _ = self.listener.set_nonblocking(true);
+    }
+
+    // The breakpoints and the watchpoints of GDB are removed, the emulation goes on
+    fn disconnect(&mut self) {
+        info!("GDB disconnected");
+        self.o_stream = None;
+        self.debugger.breakpoints.clear();
+        self.debugger.p_bus.lock().unwrap().watchpoints.clear();
+        self.debugger.o_step = None;
+        self.debugger.paused = false;
+    }
+
+    // ===== PACKETS =====
+
+    // Handles a packet, returns the reply and true if the emulation must be resumed
+    // The unsupported packets get an empty reply
+    pub fn handle_packet(&mut self, packet: &str) -> (Option<String>, bool) {
+        // The packets are sliced by byte index, the binary packets aren't supported
+        if !packet.is_ascii() {
+            warn!("GDB packet with non ASCII data ignored");
+            return (Some(String::from("E01")), false);
+        }
+        let command: char = match packet.chars().next() {
+            Some(command) => command,
+            None => return (Some(String::new()), false)
+        };
+        let arguments: &str = &packet[1..];
+        let reply: Result<String, String> = match command {
+            '?' => Ok(format!("S{:02x}", SIGTRAP)),
+            'g' => Ok(self.read_registers()),
+            'G' => self.write_registers(arguments),
+            'p' => self.read_register(arguments),
+            'P' => self.write_register(arguments),
+            'm' => self.read_memory(arguments),
+            'M' => self.write_memory(arguments),
+            'c' | 's' => {
+                if !arguments.is_empty() {
+                    match u16::from_str_radix(arguments, 16) {
+                        Ok(address) => self.debugger.p_cpu.lock().unwrap().pc = address,
+                        Err(_) => return (Some(String::from("E01")), false)
+                    }
+                }
+                if command == 's' {
+                    self.debugger.o_step = Some(StepMode::Instructions(1));
+                }
+                return (None, true);
+            },
+            'Z' | 'z' => self.set_breakpoint(command == 'Z', arguments),
+            'H' => Ok(String::from("OK")),
+            'D' => {
+                self.send_packet("OK");
+                self.disconnect();
+                return (None, true);
+            },
+            'k' => {
+                self.disconnect();
+                return (None, true);
+            },
+            'q' => Ok(self.query(arguments)),
+            _ => Ok(String::new())
+        };
+        match reply {
+            Ok(reply) => (Some(reply), false),
+            Err(why) => {
+                warn!("GDB packet {} failed: {}", packet, why);
+                (Some(String::from("E01")), false)
+            }
+        }
+    }
+
+    fn query(&self, query: &str) -> String {
+        if query.starts_with("Supported") {
+            return String::from("PacketSize=1000;qXfer:features:read+");
+        }
+        if let Some(range) = query.strip_prefix("Xfer:features:read:target.xml:") {
+            return read_chunk(TARGET_XML, range);
+        }
+        match query {
+            "Attached" => String::from("1"),
+            "C" => String::from("QC1"),
+            "fThreadInfo" => String::from("m1"),
+            "sThreadInfo" => String::from("l"),
+            _ => String::new()
+        }
+    }
+
+    // ===== REGISTERS =====
+
+    fn read_registers(&self) -> String {
+        let cpu = self.debugger.p_cpu.lock().unwrap();
+        let sp: u16 = STACK_OFFSET + cpu.sp as u16;
+        let bytes: [u8;8] = [cpu.a, cpu.x, cpu.y, cpu.p, cpu.pc as u8, (cpu.pc >> 8) as u8, sp as u8, (sp >> 8) as u8];
+        to_hex(&bytes)
+    }
+
+    fn write_registers(&mut self, arguments: &str) -> Result<String, String> {
+        let bytes: Vec<u8> = from_hex(arguments)?;
+        if bytes.len() < 8 {
+            return Err(String::from("Missing registers"));
+        }
+        let mut cpu = self.debugger.p_cpu.lock().unwrap();
+        cpu.a = bytes[0];
+        cpu.x = bytes[1];
+        cpu.y = bytes[2];
+        cpu.p = bytes[3];
+        cpu.pc = bytes[4] as u16 + ((bytes[5] as u16) << 8);
+        cpu.sp = bytes[6];
+        Ok(String::from("OK"))
+    }
+
+    fn read_register(&self, arguments: &str) -> Result<String, String> {
+        let registers: Vec<u8> = from_hex(&self.read_registers())?;
+        match usize::from_str_radix(arguments, 16).map_err(|why| why.to_string())? {
+            index @ 0..=3 => Ok(to_hex(&registers[index..index + 1])),
+            4 => Ok(to_hex(&registers[4..6])),
+            5 => Ok(to_hex(&registers[6..8])),
+            index => Err(format!("Invalid register {}", index))
+        }
+    }
+
+    fn write_register(&mut self, arguments: &str) -> Result<String, String> {
+        let (index, value) = arguments.split_once('=').ok_or("Missing value")?;
+        let bytes: Vec<u8> = from_hex(value)?;
+        if bytes.is_empty() {
+            return Err(String::from("Missing value"));
+        }
+        let mut cpu = self.debugger.p_cpu.lock().unwrap();
+        match usize::from_str_radix(index, 16).map_err(|why| why.to_string())? {
+            0 => cpu.a = bytes[0],
+            1 => cpu.x = bytes[0],
+            2 => cpu.y = bytes[0],
+            3 => cpu.p = bytes[0],
+            4 => cpu.pc = bytes[0] as u16 + ((*bytes.get(1).unwrap_or(&0) as u16) << 8),
+            5 => cpu.sp = bytes[0],
+            index => return Err(format!("Invalid register {}", index))
+        }
+        Ok(String::from("OK"))
+    }
+
+    // ===== MEMORY =====
+
+    // The memory is read without side effects
+    fn read_memory(&self, arguments: &str) -> Result<String, String> {
+        let (address, length): (u16, u32) = parse_range(arguments)?;
+        let bus = self.debugger.p_bus.lock().unwrap();
+        let bytes: Vec<u8> = (0..length).map(|offset| bus.read_only(address.wrapping_add(offset as u16))).collect();
+        Ok(to_hex(&bytes))
+    }
+
+    // The memory is written as the CPU would do it, only in the internal RAM and the RAM of the cartridge
+    fn write_memory(&mut self, arguments: &str) -> Result<String, String> {
+        let (range, data) = arguments.split_once(':').ok_or("Missing data")?;
+        let (address, length): (u16, u32) = parse_range(range)?;
+        let bytes: Vec<u8> = from_hex(data)?;
+        if bytes.len() as u32 != length {
+            return Err(String::from("Invalid data length"));
+        }
+        if let Some(offset) = (0..length).find(|offset| !Bus::is_ram(address.wrapping_add(*offset as u16))) {
+            return Err(format!("${:04X} isn't RAM", address.wrapping_add(offset as u16)));
+        }
+        let mut bus = self.debugger.p_bus.lock().unwrap();
+        for (offset, byte) in bytes.iter().enumerate() {
+            bus.write(address.wrapping_add(offset as u16), *byte);
+        }
+        Ok(String::from("OK"))
+    }
+
+    // ===== BREAKPOINTS =====
+
+    // Z0 and Z1 are breakpoints, Z2, Z3 and Z4 are write, read and access watchpoints
+    fn set_breakpoint(&mut self, insert: bool, arguments: &str) -> Result<String, String> {
+        let fields: Vec<&str> = arguments.split(',').collect();
+        if fields.len() < 3 {
+            return Err(String::from("Missing fields"));
+        }
+        let address: u16 = u16::from_str_radix(fields[1], 16).map_err(|why| why.to_string())?;
+        let length: u16 = u16::from_str_radix(fields[2], 16).map_err(|why| why.to_string())?.max(1);
+        let kinds: Vec<AccessKind> = match fields[0] {
+            "0" | "1" => {
+                self.debugger.breakpoints.retain(|breakpoint| *breakpoint != address);
+                if insert {
+                    self.debugger.breakpoints.push(address);
+                }
+                return Ok(String::from("OK"));
+            },
+            "2" => vec![AccessKind::Write],
+            "3" => vec![AccessKind::Read],
+            "4" => vec![AccessKind::Read, AccessKind::Write],
+            _ => return Ok(String::new())
+        };
+        let mut bus = self.debugger.p_bus.lock().unwrap();
+        for kind in kinds {
+            let watchpoint: Watchpoint = Watchpoint { kind, start: address, end: address.saturating_add(length - 1) };
+            bus.watchpoints.retain(|other| *other != watchpoint);
+            if insert {
+                bus.watchpoints.push(watchpoint);
+            }
+        }
+        Ok(String::from("OK"))
+    }
+
+    // ===== CONNECTION =====
+
+    // Reads the next packet and acknowledges it, returns None when GDB is disconnected
+    // The acknowledgments and the interrupts received while paused are ignored
+    fn read_packet(&mut self) -> Option<String> {
+        let stream: &mut TcpStream = self.o_stream.as_mut()?;
+        stream.set_nonblocking(false).ok()?;
+        loop {
+            while read_byte(stream)? != b'$' {}
+            let mut data: Vec<u8> = vec![];
+            loop {
+                match read_byte(stream)? {
+                    b'#' => break,
+                    byte => data.push(byte)
+                }
+            }
+            let checksum: [u8;2] = [read_byte(stream)?, read_byte(stream)?];
+            let expected: Option<u8> = std::str::from_utf8(&checksum).ok().and_then(|checksum| u8::from_str_radix(checksum, 16).ok());
+            if expected != Some(data.iter().fold(0, |sum: u8, byte| sum.wrapping_add(*byte))) {
+                stream.write_all(b"-").ok()?;
+                continue;
+            }
+            stream.write_all(b"+").ok()?;
+            return Some(String::from_utf8_lossy(&data).into_owned());
+        }
+    }
+
+    fn send_packet(&mut self, data: &str) {
+        if let Some(stream) = self.o_stream.as_mut() {
+            let checksum: u8 = data.bytes().fold(0, |sum: u8, byte| sum.wrapping_add(byte));
+            if let Err(why) = stream.write_all(format!("${}#{:02x}", data, checksum).as_bytes()) {
+                warn!("Couldn't send the GDB packet: {}", why);
+            }
+        }
+    }
+}
+
+// ===== HELPER FUNCTIONS =====
+
+fn stop_reply(reason: StopReason) -> String {
+    match reason {
+        StopReason::PauseRequest => format!("S{:02x}", SIGINT),
+        StopReason::Watchpoint(hit) => {
+            let kind: &str = match hit.watchpoint.kind {
+                AccessKind::Read => "rwatch",
+                AccessKind::Write => "watch",
+                AccessKind::Execute => return format!("S{:02x}", SIGTRAP)
+            };
+            format!("T{:02x}{}:{:04x};", SIGTRAP, kind, hit.address)
+        },
+        StopReason::Breakpoint(_) | StopReason::StepEnd => format!("S{:02x}", SIGTRAP)
+    }
+}
+
+fn read_byte(stream: &mut TcpStream) -> Option<u8> {
+    let mut byte: [u8;1] = [0];
+    match stream.read(&mut byte) {
+        Ok(1) => Some(byte[0]),
+        _ => None
+    }
+}
+
+// Parses "ADDR,LENGTH"
+fn parse_range(range: &str) -> Result<(u16, u32), String> {
+    let (address, length) = range.split_once(',').ok_or("Missing length")?;
+    let address: u16 = u16::from_str_radix(address, 16).map_err(|why| why.to_string())?;
+    let length: u32 = u32::from_str_radix(length, 16).map_err(|why| why.to_string())?;
+    Ok((address, length.min(0x10000)))
+}
+
+// Returns the part of a document asked with "OFFSET,LENGTH", 'l' marks the last part
+fn read_chunk(document: &str, range: &str) -> String {
+    let (offset, length): (usize, usize) = match range.split_once(',') {
+        Some((offset, length)) => (usize::from_str_radix(offset, 16).unwrap_or(0), usize::from_str_radix(length, 16).unwrap_or(0)),
+        None => return String::from("E01")
+    };
+    let start: usize = offset.min(document.len());
+    let end: usize = (offset + length).min(document.len());
+    match end == document.len() {
+        true => format!("l{}", &document[start..end]),
+        false => format!("m{}", &document[start..end])
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return Err(format!("Invalid hexadecimal data : {}", hex));
+    }
+    (0..hex.len()).step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).map_err(|_| format!("Invalid hexadecimal data : {}", hex)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debugger::tests::create_debugger;
+
+    fn create_gdb_stub(program: &[u8]) -> GdbStub {
+        let debugger: Debugger = create_debugger(program);
+        GdbStub::new(0, debugger.p_cpu, debugger.p_bus, debugger.p_ppu).unwrap()
+    }
+
+    fn reply(gdb_stub: &mut GdbStub, packet: &str) -> String {
+        gdb_stub.handle_packet(packet).0.unwrap()
+    }
+
+    #[test]
+    fn registers() {
+        let mut gdb_stub: GdbStub = create_gdb_stub(&[0xEA]);
+        assert_eq!(reply(&mut gdb_stub, "P0=42"), "OK");
+        assert_eq!(reply(&mut gdb_stub, "P4=3412"), "OK");
+        assert_eq!(reply(&mut gdb_stub, "p0"), "42");
+        assert_eq!(reply(&mut gdb_stub, "p4"), "3412");
+        let registers: String = reply(&mut gdb_stub, "g");
+        assert_eq!(&registers[..2], "42");
+        assert_eq!(&registers[8..12], "3412");
+        assert_eq!(&registers[14..], "01");
+        assert_eq!(reply(&mut gdb_stub, "G0102030400c0fd01"), "OK");
+        let cpu = gdb_stub.debugger.p_cpu.lock().unwrap();
+        assert_eq!((cpu.a, cpu.x, cpu.y, cpu.p, cpu.pc, cpu.sp), (1, 2, 3, 4, 0xC000, 0xFD));
+    }
+
+    #[test]
+    fn memory() {
+        let mut gdb_stub: GdbStub = create_gdb_stub(&[0xA9, 0x01]);
+        assert_eq!(reply(&mut gdb_stub, "m200,2"), "a901");
+        assert_eq!(reply(&mut gdb_stub, "M10,3:010203"), "OK");
+        assert_eq!(reply(&mut gdb_stub, "m10,3"), "010203");
+        assert_eq!(reply(&mut gdb_stub, "M10,2:01"), "E01");
+        // The registers and the ROM can't be written
+        assert_eq!(reply(&mut gdb_stub, "M7fff,2:0102"), "E01");
+        assert_eq!(reply(&mut gdb_stub, "m7fff,1"), "00");
+        assert_eq!(reply(&mut gdb_stub, "M2000,1:80"), "E01");
+        assert_eq!(gdb_stub.debugger.p_ppu.lock().unwrap().registers.ctrl, 0);
+        assert_eq!(reply(&mut gdb_stub, "M6000,1:42"), "OK");
+    }
+
+    #[test]
+    fn non_ascii_packets() {
+        let mut gdb_stub: GdbStub = create_gdb_stub(&[0xEA]);
+        assert_eq!(reply(&mut gdb_stub, "é"), "E01");
+        assert_eq!(reply(&mut gdb_stub, "M10,1:é"), "E01");
+        assert_eq!(from_hex("0é"), Err(String::from("Invalid hexadecimal data : 0é")));
+    }
+
+    #[test]
+    fn breakpoints_and_steps() {
+        let mut gdb_stub: GdbStub = create_gdb_stub(&[0xEA, 0xEA, 0x8D, 0x10, 0x00, 0xEA]);
+        assert_eq!(reply(&mut gdb_stub, "Z0,201,1"), "OK");
+        assert_eq!(gdb_stub.debugger.breakpoints, vec![0x0201]);
+        assert_eq!(reply(&mut gdb_stub, "z0,201,1"), "OK");
+        assert!(gdb_stub.debugger.breakpoints.is_empty());
+
+        assert_eq!(gdb_stub.handle_packet("s"), (None, true));
+        gdb_stub.debugger.p_cpu.lock().unwrap().step();
+        assert_eq!(gdb_stub.debugger.check_stop(), Some(StopReason::StepEnd));
+        gdb_stub.debugger.o_step = None;
+
+        assert_eq!(reply(&mut gdb_stub, "Z2,10,1"), "OK");
+        assert_eq!(gdb_stub.handle_packet("c"), (None, true));
+        gdb_stub.debugger.p_cpu.lock().unwrap().step();
+        gdb_stub.debugger.p_cpu.lock().unwrap().step();
+        let reason: StopReason = gdb_stub.debugger.check_stop().unwrap();
+        assert_eq!(stop_reply(reason), "T05watch:0010;");
+        assert_eq!(reply(&mut gdb_stub, "z2,10,1"), "OK");
+        assert!(gdb_stub.debugger.p_bus.lock().unwrap().watchpoints.is_empty());
+    }
+
+    #[test]
+    fn queries() {
+        let mut gdb_stub: GdbStub = create_gdb_stub(&[0xEA]);
+        assert_eq!(reply(&mut gdb_stub, "qSupported:multiprocess+"), "PacketSize=1000;qXfer:features:read+");
+        assert_eq!(reply(&mut gdb_stub, "qXfer:features:read:target.xml:0,10"), format!("m{}", &TARGET_XML[..0x10]));
+        assert_eq!(reply(&mut gdb_stub, "qXfer:features:read:target.xml:0,1000"), format!("l{}", TARGET_XML));
+        assert_eq!(reply(&mut gdb_stub, "vMustReplyEmpty"), "");
+    }
+}
@@ -0,0 +1,207 @@
+// Golden frame regression tests
+// A ROM is run for a number of frames with the inputs of a movie, then the frame buffer is hashed
+// and compared to the hash stored in a manifest
+//
+// Manifest format, one test per line, "#" starts a comment :
+// <name> <rom path> <frames> <fm2 input or -> <expected hash or ->
+// The ROM path is relative to the ROMs directory, the input path to the directory of the manifest
+// The test ROMs shipped with the repository are next to the manifest
+
+// ===== IMPORTS =====
+
+use std::{fs::File, path::Path};
+
+use crate::{cartridge::cartridge::Cartridge, headless::Headless, movie::Movie};
+use crate::ppu::{palette::ARGBColor, ppu::{FRAME_HEIGHT, FRAME_WIDTH}};
+
+// ===== CONSTANTS =====
+
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+const DIFF_COLOR: ARGBColor = ARGBColor::new(255, 255, 0, 0);
+
+// ===== STRUCT =====
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenFrame {
+    pub name: String,
+    pub rom: String,
+    pub frames: u64,
+    pub o_input: Option<String>,
+    // None until the test is blessed
+    pub o_hash: Option<u64>
+}
+
+impl GoldenFrame {
+    // Runs the ROM and returns the last frame buffer
+    pub fn run(&self, roms_dir: &Path, inputs_dir: &Path) -> Result<Vec<ARGBColor>, String> {
+        let mut headless: Headless = Headless::new(Cartridge::new(&roms_dir.join(&self.rom)), false);
+        if let Some(input) = self.o_input.as_ref() {
+            headless.set_movie(Movie::load_fm2(&inputs_dir.join(input))?);
+        }
+        for _ in 0..self.frames {
+            headless.run_frame();
+        }
+        Ok(headless.frame_buffer())
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.name,
+            self.rom,
+            self.frames,
+            self.o_input.as_deref().unwrap_or("-"),
+            self.o_hash.map_or(String::from("-"), |hash| format!("{:016x}", hash))
+        )
+    }
+}
+
+// ===== MANIFEST =====
+
+pub fn parse_manifest(content: &str) -> Result<Vec<GoldenFrame>, String> {
+    let mut golden_frames: Vec<GoldenFrame> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line: &str = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() != 5 {
+            return Err(format!("Line {}: expected 5 columns, found {}", index + 1, columns.len()));
+        }
+        let frames: u64 = columns[2].parse().map_err(|_| format!("Line {}: invalid number of frames {}", index + 1, columns[2]))?;
+        let o_hash: Option<u64> = match columns[4] {
+            "-" => None,
+            hash => Some(u64::from_str_radix(hash, 16).map_err(|_| format!("Line {}: invalid hash {}", index + 1, hash))?)
+        };
+        golden_frames.push(GoldenFrame {
+            name: columns[0].to_string(),
+            rom: columns[1].to_string(),
+            frames,
+            o_input: if columns[3] == "-" { None } else { Some(columns[3].to_string()) },
+            o_hash
+        });
+    }
+    Ok(golden_frames)
+}
+
+// Rewrites the lines of the tests in the manifest, keeping the comments
+pub fn update_manifest(content: &str, golden_frames: &[GoldenFrame]) -> String {
+    let mut golden_frames_iter = golden_frames.iter();
+    let mut updated: String = String::new();
+    for line in content.lines() {
+        let trimmed: &str = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            updated += line;
+        }
+        else if let Some(golden_frame) = golden_frames_iter.next() {
+            updated += &golden_frame.to_line();
+        }
+        updated.push('\n');
+    }
+    updated
+}
+
+// ===== FRAMES =====
+
+// FNV-1a hash of the RGB values of a frame
+pub fn hash_frame(frame_buffer: &[ARGBColor]) -> u64 {
+    let mut hash: u64 = FNV_OFFSET_BASIS;
+    for color in frame_buffer.iter() {
+        for byte in [color.red, color.green, color.blue].iter() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+// Reads a frame saved with headless::save_png
+pub fn load_png(path: &Path) -> Result<Vec<ARGBColor>, String> {
+    let file: File = File::open(path).map_err(|why| format!("Couldn't open {}: {}", path.display(), why))?;
+    let mut reader = png::Decoder::new(file).read_info().map_err(|why| format!("Couldn't read {}: {}", path.display(), why))?;
+    let mut data: Vec<u8> = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).map_err(|why| format!("Couldn't read {}: {}", path.display(), why))?;
+    if info.width as usize != FRAME_WIDTH || info.height as usize != FRAME_HEIGHT || info.color_type != png::ColorType::Rgb {
+        return Err(format!("{} is not a RGB frame of {}x{} pixels", path.display(), FRAME_WIDTH, FRAME_HEIGHT));
+    }
+    Ok(data[..info.buffer_size()].chunks(3).map(|rgb| ARGBColor::new(255, rgb[0], rgb[1], rgb[2])).collect())
+}
+
+// Differing pixels are red, the others are the darkened expected pixels
+pub fn diff_frames(expected: &[ARGBColor], actual: &[ARGBColor]) -> Vec<ARGBColor> {
+    expected.iter().zip(actual.iter()).map(|(expected_color, actual_color)| {
+        if (expected_color.red, expected_color.green, expected_color.blue) == (actual_color.red, actual_color.green, actual_color.blue) {
+            ARGBColor::new(255, expected_color.red / 4, expected_color.green / 4, expected_color.blue / 4)
+        }
+        else {
+            DIFF_COLOR
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::save_png;
+
+    const MANIFEST: &str = "# Comment\nsmb_title smb.nes 120 - 00000000000000ff\n\n  # Indented comment\nsmb_walk smb.nes 600 smb_walk.fm2 -\n";
+
+    fn rgb(color: &ARGBColor) -> (u8, u8, u8) {
+        (color.red, color.green, color.blue)
+    }
+
+    #[test]
+    fn parse_manifest_lines() {
+        let golden_frames: Vec<GoldenFrame> = parse_manifest(MANIFEST).unwrap();
+        assert_eq!(golden_frames, vec![
+            GoldenFrame { name: String::from("smb_title"), rom: String::from("smb.nes"), frames: 120, o_input: None, o_hash: Some(0xFF) },
+            GoldenFrame { name: String::from("smb_walk"), rom: String::from("smb.nes"), frames: 600, o_input: Some(String::from("smb_walk.fm2")), o_hash: None }
+        ]);
+        assert!(parse_manifest("smb_title smb.nes 120 -\n").is_err());
+        assert!(parse_manifest("smb_title smb.nes ten - -\n").is_err());
+        assert!(parse_manifest("smb_title smb.nes 120 - hash\n").is_err());
+    }
+
+    #[test]
+    fn manifest_round_trip() {
+        let mut golden_frames: Vec<GoldenFrame> = parse_manifest(MANIFEST).unwrap();
+        assert_eq!(update_manifest(MANIFEST, &golden_frames), MANIFEST);
+
+        golden_frames[1].o_hash = Some(0x0123_4567_89AB_CDEF);
+        let updated: String = update_manifest(MANIFEST, &golden_frames);
+        assert_eq!(updated, "# Comment\nsmb_title smb.nes 120 - 00000000000000ff\n\n  # Indented comment\nsmb_walk smb.nes 600 smb_walk.fm2 0123456789abcdef\n");
+        assert_eq!(parse_manifest(&updated).unwrap(), golden_frames);
+    }
+
+    #[test]
+    fn hash_frame_colors() {
+        assert_eq!(hash_frame(&[]), FNV_OFFSET_BASIS);
+        let frame: Vec<ARGBColor> = vec![ARGBColor::new(255, 1, 2, 3), ARGBColor::new(255, 4, 5, 6)];
+        // The alpha isn't hashed, the order of the pixels is
+        assert_eq!(hash_frame(&frame), hash_frame(&[ARGBColor::new(0, 1, 2, 3), ARGBColor::new(128, 4, 5, 6)]));
+        assert_ne!(hash_frame(&frame), hash_frame(&[frame[1], frame[0]]));
+        assert_ne!(hash_frame(&frame), hash_frame(&[ARGBColor::new(255, 1, 2, 3), ARGBColor::new(255, 4, 5, 7)]));
+    }
+
+    #[test]
+    fn diff_frames_pixels() {
+        let expected: Vec<ARGBColor> = vec![ARGBColor::new(255, 40, 80, 120), ARGBColor::new(255, 40, 80, 120)];
+        let actual: Vec<ARGBColor> = vec![ARGBColor::new(255, 40, 80, 120), ARGBColor::new(255, 40, 80, 121)];
+        let diff: Vec<ARGBColor> = diff_frames(&expected, &actual);
+        assert_eq!(diff.iter().map(rgb).collect::<Vec<(u8, u8, u8)>>(), vec![(10, 20, 30), rgb(&DIFF_COLOR)]);
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let frame: Vec<ARGBColor> = (0..FRAME_WIDTH*FRAME_HEIGHT).map(|index| ARGBColor::new(255, index as u8, (index >> 8) as u8, 7)).collect();
+        let path: std::path::PathBuf = std::env::temp_dir().join("nes_emulator_golden_frame.png");
+        save_png(&frame, &path).unwrap();
+        let loaded: Vec<ARGBColor> = load_png(&path).unwrap();
+        assert_eq!(loaded.iter().map(rgb).collect::<Vec<(u8, u8, u8)>>(), frame.iter().map(rgb).collect::<Vec<(u8, u8, u8)>>());
+        assert_eq!(hash_frame(&loaded), hash_frame(&frame));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
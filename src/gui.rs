@@ -8,9 +8,9 @@
 use std::sync::{Arc, Mutex};
 
 use pixels::{Pixels, SurfaceTexture};
-use winit::{dpi::LogicalSize, event_loop::EventLoop, window::{Window, WindowBuilder}};
+use winit::{dpi::LogicalSize, event_loop::EventLoop, window::{Window, WindowBuilder, WindowId}};
 
-use crate::ppu::palette::ARGBColor;
+use crate::{memory_viewer::{MemoryViewer, VIEWER_HEIGHT, VIEWER_WIDTH}, ppu::palette::ARGBColor};
 
 // ===== CONSTANTS =====
 
@@ -98,4 +98,52 @@ impl GUI {
     pub fn update(&mut self) {
         self.main_window.request_redraw();
     }
+}
+
+// ===== MEMORY WINDOW STRUCT =====
+
+// Window of the memory viewer, drawn by the event loop of the main window
+pub struct MemoryWindow {
+    window: Window,
+    pixels: Pixels<Window>
+}
+
+impl MemoryWindow {
+    pub fn new(event_loop: &EventLoop<()>) -> Self {
+        let window = WindowBuilder::new()
+            .with_title("Nesmulator - Memory")
+            .with_inner_size(LogicalSize::new(VIEWER_WIDTH*3, VIEWER_HEIGHT*3))
+            .build(event_loop)
+            .expect("Cannot create memory window");
+
+        let surface_texture = SurfaceTexture::new(VIEWER_WIDTH*3, VIEWER_HEIGHT*3, &window);
+        let pixels = Pixels::new(VIEWER_WIDTH, VIEWER_HEIGHT, surface_texture).unwrap();
+
+        MemoryWindow {
+            window,
+            pixels
+        }
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        self.window.set_visible(visible);
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.pixels.resize(width, height);
+    }
+
+    pub fn request_redraw(&self) {
+        self.window.request_redraw();
+    }
+
+    // Draws the memory viewer in the window
+    pub fn render(&mut self, memory_viewer: &MemoryViewer) -> Result<(), String> {
+        memory_viewer.render(self.pixels.get_frame());
+        self.pixels.render().map_err(|why| format!("pixels.render() failed: {}", why))
+    }
 }
\ No newline at end of file
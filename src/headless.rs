@@ -0,0 +1,69 @@
+// Runs the NES without any window
+// Used to run ROMs in CI and to take screenshots of the frames
+
+// ===== IMPORTS =====
+
+use std::{fs::File, io::BufWriter, path::Path, sync::{Arc, Mutex}};
+
+use crate::{bus::Bus, cartridge::cartridge::Cartridge, cpu::cpu::CPU, movie::Movie, nes::NES};
+use crate::ppu::{palette::ARGBColor, ppu::{FRAME_HEIGHT, FRAME_WIDTH, PPU}};
+
+// ===== STRUCT =====
+
+pub struct Headless {
+    pub nes: NES
+}
+
+impl Headless {
+    // Creates the NES architecture without GUI, inserts the cartridge and powers the NES on
+    pub fn new(cartridge: Cartridge, display_cpu_logs: bool) -> Self {
+        let p_ppu: Arc<Mutex<PPU>> = Arc::new(Mutex::new(PPU::new(None)));
+        let p_bus: Arc<Mutex<Bus>> = Arc::new(Mutex::new(Bus::new(p_ppu.clone())));
+        let p_cpu: Arc<Mutex<CPU>> = Arc::new(Mutex::new(CPU::new(p_bus.clone(), display_cpu_logs)));
+        let mut nes: NES = NES::new(p_bus, p_cpu, p_ppu, None);
+        nes.insert_cartdrige(cartridge);
+        nes.power_cycle();
+        Headless {
+            nes
+        }
+    }
+
+    // Plays the inputs of a movie, plugs a Four Score if the movie needs it
+    pub fn set_movie(&mut self, movie: Movie) {
+        self.nes.set_movie(movie);
+    }
+
+    pub fn run_frame(&mut self) {
+        self.nes.run_frame();
+    }
+
+    // Number of frames rendered since power on
+    pub fn frame_count(&self) -> u64 {
+        self.nes.p_ppu.lock().unwrap().frame_count
+    }
+
+    pub fn frame_buffer(&self) -> Vec<ARGBColor> {
+        self.nes.p_ppu.lock().unwrap().frame_buffer.clone()
+    }
+
+    // Saves the last frame rendered by the PPU
+    pub fn save_screenshot(&self, path: &Path) -> Result<(), String> {
+        save_png(&self.frame_buffer(), path)
+    }
+}
+
+// Writes a frame buffer of the PPU in a PNG file
+pub fn save_png(frame_buffer: &[ARGBColor], path: &Path) -> Result<(), String> {
+    let file: File = File::create(path).map_err(|why| format!("Couldn't create {}: {}", path.display(), why))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), FRAME_WIDTH as u32, FRAME_HEIGHT as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut data: Vec<u8> = Vec::with_capacity(FRAME_WIDTH*FRAME_HEIGHT*3);
+    for color in frame_buffer.iter() {
+        data.extend_from_slice(&[color.red, color.green, color.blue]);
+    }
+
+    let mut writer = encoder.write_header().map_err(|why| format!("Couldn't write {}: {}", path.display(), why))?;
+    writer.write_image_data(&data).map_err(|why| format!("Couldn't write {}: {}", path.display(), why))
+}
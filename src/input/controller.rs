@@ -0,0 +1,90 @@
+// Represents the standard NES controller
+
+// ===== IMPORTS =====
+
+use super::device::{InputDevice, InputState};
+
+// ===== ENUMS =====
+
+#[derive(Debug, Clone, Copy)]
+pub enum ControllerInput {
+    Right = 0b0000_0001,
+    Left = 0b0000_0010,
+    Down = 0b0000_0100,
+    Up = 0b0000_1000,
+    Start = 0b0001_0000,
+    Select = 0b0010_0000,
+    B = 0b0100_0000,
+    A = 0b1000_0000
+}
+
+// ===== STRUCT =====
+
+#[derive(Debug, Clone, Copy)]
+pub struct Controller {
+    // Index of the player using this controller in the input state
+    pub player: usize,
+    pub buffer: u8,
+    pub shifter: u8,
+    pub strobe: bool
+}
+
+impl Controller {
+    pub fn new(player: usize) -> Self {
+        Controller {
+            player,
+            buffer: 0,
+            shifter: 0,
+            strobe: false
+        }
+    }
+
+    // Returns the state of the next button
+    // While the strobe is high, the state of A is returned
+    // After the 8 buttons have been read, a standard controller returns 1
+    pub fn check_shifter(&mut self) -> u8 {
+        if self.strobe {
+            return (self.buffer & 0x80 > 0) as u8;
+        }
+        let value: u8 = (self.shifter & 0x80 > 0) as u8;
+        self.shifter = (self.shifter << 1) | 0x01;
+        value
+    }
+
+    // Called when the CPU writes to 0x4016
+    // The shifter is reloaded as long as the strobe is high
+    pub fn write_strobe(&mut self, value: u8) {
+        let strobe: bool = (value & 0x01) > 0;
+        if self.strobe || strobe {
+            self.update_shifter();
+        }
+        self.strobe = strobe;
+    }
+
+    pub fn update_shifter(&mut self) {
+        self.shifter = self.buffer;
+    }
+}
+
+impl InputDevice for Controller {
+    fn write(&mut self, value: u8) {
+        self.write_strobe(value);
+    }
+
+    fn read(&mut self, _port: usize) -> u8 {
+        self.check_shifter()
+    }
+
+    fn peek(&self, _port: usize) -> u8 {
+        if self.strobe {
+            (self.buffer & 0x80 > 0) as u8
+        }
+        else {
+            (self.shifter & 0x80 > 0) as u8
+        }
+    }
+
+    fn update(&mut self, state: &InputState) {
+        self.buffer = state.controllers[self.player];
+    }
+}
@@ -0,0 +1,103 @@
+// Implements the common interface of the devices that can be
+// connected to the controller ports or to the Famicom expansion port
+
+// ===== IMPORTS =====
+
+use std::sync::{Arc, Mutex};
+
+use crate::ppu::ppu::PPU;
+
+use super::{controller::Controller, famicom_adapter::FamicomAdapter, family_keyboard::{FamilyKeyboard, KEYBOARD_ROWS}, four_score::FourScore, power_pad::PowerPad, vaus::Vaus, zapper::Zapper};
+
+// ===== CONSTANTS =====
+
+// Commands given to the console with the inputs of a frame, as in FM2 movies
+pub const COMMAND_SOFT_RESET: u8 = 0x01;
+pub const COMMAND_POWER_CYCLE: u8 = 0x02;
+
+// ===== INPUT STATE =====
+
+// State of the inputs given by the frontend
+// Each device picks the values it needs from it
+#[derive(Debug, Clone, Copy)]
+pub struct InputState {
+    // Buttons held on the 4 standard controllers
+    pub controllers: [u8;4],
+    // Position of the mouse on the screen, if it is inside the screen
+    pub mouse_position: Option<(usize, usize)>,
+    // Is the left mouse button held ?
+    pub mouse_pressed: bool,
+    // Buttons held on the Power Pad (bit n is button n+1)
+    pub power_pad: u16,
+    // Keys held on the Family BASIC keyboard, for each row and column
+    pub keyboard_matrix: [[u8;2];KEYBOARD_ROWS],
+    // Buttons of the console pressed since the last frame (COMMAND_* bits)
+    pub commands: u8,
+    // Turbo buttons held on the 4 standard controllers, pressed every few frames by the emulation
+    pub turbo_controllers: [u8;4],
+    // Macro keys pressed since the last frame (bit n is slot n), to play a macro or to start or stop its recording
+    pub macro_play: u8,
+    pub macro_record: u8
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        InputState {
+            controllers: [0;4],
+            mouse_position: None,
+            mouse_pressed: false,
+            power_pad: 0,
+            keyboard_matrix: [[0;2];KEYBOARD_ROWS],
+            commands: 0,
+            turbo_controllers: [0;4],
+            macro_play: 0,
+            macro_record: 0
+        }
+    }
+}
+
+// ===== INPUT DEVICE TRAIT =====
+
+pub trait InputDevice {
+    // Called when the CPU writes to 0x4016
+    // The 3 lower bits are the OUT lines shared by all the devices
+    fn write(&mut self, value: u8);
+    // Called when the CPU reads 0x4016 (port 0) or 0x4017 (port 1)
+    // Returns the D0-D4 bits driven by the device
+    fn read(&mut self, port: usize) -> u8;
+    // Same as read, without changing the state of the device
+    fn peek(&self, port: usize) -> u8;
+    // Updates the device with the inputs given by the frontend
+    fn update(&mut self, state: &InputState);
+}
+
+// ===== DEVICE CREATION =====
+
+// Creates the device plugged in one of the controller ports
+pub fn create_port_device(name: &str, port: usize, p_ppu: Arc<Mutex<PPU>>) -> Box<dyn InputDevice> {
+    match name {
+        "controller" => Box::new(Controller::new(port)),
+        "four-score" => Box::new(FourScore::new(port)),
+        "zapper" => Box::new(Zapper::new(p_ppu)),
+        "vaus" => Box::new(Vaus::new(false)),
+        "power-pad" => Box::new(PowerPad::new()),
+        _ => panic!("Unknown controller port device : {}", name)
+    }
+}
+
+// Creates the device plugged in the Famicom expansion port
+pub fn create_expansion_device(name: &str) -> Option<Box<dyn InputDevice>> {
+    match name {
+        "none" => None,
+        "famicom-4p" => Some(Box::new(FamicomAdapter::new())),
+        "vaus" => Some(Box::new(Vaus::new(true))),
+        "keyboard" => Some(Box::new(FamilyKeyboard::new())),
+        _ => panic!("Unknown expansion port device : {}", name)
+    }
+}
@@ -0,0 +1,47 @@
+// Represents a Famicom 4 players adapter plugged in the expansion port
+// Controllers 3 and 4 are read on the D1 bit of 0x4016 and 0x4017
+
+// ===== IMPORTS =====
+
+use super::{controller::Controller, device::{InputDevice, InputState}};
+
+// ===== STRUCT =====
+
+#[derive(Debug, Clone, Copy)]
+pub struct FamicomAdapter {
+    pub controllers: [Controller;2]
+}
+
+impl Default for FamicomAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FamicomAdapter {
+    pub fn new() -> Self {
+        FamicomAdapter {
+            controllers: [Controller::new(2), Controller::new(3)]
+        }
+    }
+}
+
+impl InputDevice for FamicomAdapter {
+    fn write(&mut self, value: u8) {
+        self.controllers[0].write_strobe(value);
+        self.controllers[1].write_strobe(value);
+    }
+
+    fn read(&mut self, port: usize) -> u8 {
+        self.controllers[port].check_shifter() << 1
+    }
+
+    fn peek(&self, port: usize) -> u8 {
+        self.controllers[port].peek(port) << 1
+    }
+
+    fn update(&mut self, state: &InputState) {
+        self.controllers[0].update(state);
+        self.controllers[1].update(state);
+    }
+}
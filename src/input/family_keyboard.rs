@@ -0,0 +1,77 @@
+// Represents the Family BASIC keyboard, plugged in the Famicom expansion port
+// The keys are organized in a 9 rows * 2 columns matrix of 4 keys
+// Writes to 0x4016 :
+// - bit 0 : resets the selection to the first row
+// - bit 1 : selects the column, the next row is selected when it goes from 1 to 0
+// - bit 2 : enables the keyboard
+// Reads of 0x4017 return the 4 keys of the selected column on D1-D4 (0 if pressed)
+
+// ===== IMPORTS =====
+
+use super::device::{InputDevice, InputState};
+
+// ===== CONSTANTS =====
+
+pub const KEYBOARD_ROWS: usize = 9;
+
+// ===== STRUCT =====
+
+#[derive(Debug, Clone, Copy)]
+pub struct FamilyKeyboard {
+    // Keys pressed for each row and column (bit n is D(n+1))
+    pub matrix: [[u8;2];KEYBOARD_ROWS],
+    pub row: usize,
+    pub column: usize,
+    pub enabled: bool
+}
+
+impl Default for FamilyKeyboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FamilyKeyboard {
+    pub fn new() -> Self {
+        FamilyKeyboard {
+            matrix: [[0;2];KEYBOARD_ROWS],
+            row: 0,
+            column: 0,
+            enabled: false
+        }
+    }
+}
+
+impl InputDevice for FamilyKeyboard {
+    fn write(&mut self, value: u8) {
+        let column: usize = ((value & 0x02) >> 1) as usize;
+        if self.column == 1 && column == 0 {
+            self.row += 1;
+        }
+        self.column = column;
+        if (value & 0x01) > 0 {
+            self.row = 0;
+        }
+        self.enabled = (value & 0x04) > 0;
+    }
+
+    fn read(&mut self, port: usize) -> u8 {
+        self.peek(port)
+    }
+
+    fn peek(&self, port: usize) -> u8 {
+        if port == 0 || !self.enabled {
+            return 0;
+        }
+        if self.row < KEYBOARD_ROWS {
+            (!self.matrix[self.row][self.column] << 1) & 0x1E
+        }
+        else {
+            0x1E
+        }
+    }
+
+    fn update(&mut self, state: &InputState) {
+        self.matrix = state.keyboard_matrix;
+    }
+}
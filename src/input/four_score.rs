@@ -0,0 +1,75 @@
+// Represents the NES Four Score adapter
+// It is plugged in both controller ports, and each port serializes
+// two controllers followed by a signature byte:
+// - 0x4016 : controller 1, controller 3, signature 0x10
+// - 0x4017 : controller 2, controller 4, signature 0x20
+
+// ===== IMPORTS =====
+
+use super::device::{InputDevice, InputState};
+
+// ===== CONSTANTS =====
+
+const SIGNATURES: [u8;2] = [0x10, 0x20];
+
+// ===== STRUCT =====
+
+#[derive(Debug, Clone, Copy)]
+pub struct FourScore {
+    // Port the adapter is read from
+    pub port: usize,
+    // Buttons of the two controllers read from this port
+    pub buffers: [u8;2],
+    // 24 bits shifted out on reads, stored in the upper bits
+    pub shifter: u32,
+    pub strobe: bool
+}
+
+impl FourScore {
+    pub fn new(port: usize) -> Self {
+        FourScore {
+            port,
+            buffers: [0;2],
+            shifter: 0,
+            strobe: false
+        }
+    }
+
+    pub fn update_shifter(&mut self) {
+        self.shifter = ((self.buffers[0] as u32) << 24)
+            | ((self.buffers[1] as u32) << 16)
+            | ((SIGNATURES[self.port] as u32) << 8)
+            | 0xFF;
+    }
+}
+
+impl InputDevice for FourScore {
+    fn write(&mut self, value: u8) {
+        let strobe: bool = (value & 0x01) > 0;
+        if self.strobe || strobe {
+            self.update_shifter();
+        }
+        self.strobe = strobe;
+    }
+
+    fn read(&mut self, port: usize) -> u8 {
+        let value: u8 = self.peek(port);
+        if !self.strobe {
+            self.shifter = (self.shifter << 1) | 0x01;
+        }
+        value
+    }
+
+    fn peek(&self, _port: usize) -> u8 {
+        if self.strobe {
+            (self.buffers[0] & 0x80 > 0) as u8
+        }
+        else {
+            (self.shifter & 0x8000_0000 > 0) as u8
+        }
+    }
+
+    fn update(&mut self, state: &InputState) {
+        self.buffers = [state.controllers[self.port], state.controllers[self.port + 2]];
+    }
+}
@@ -0,0 +1,68 @@
+// Implements the input macros
+// A macro is a sequence of controller states, recorded and played back frame by frame
+
+// ===== CONSTANTS =====
+
+// Number of macros of the first controller
+pub const MACRO_SLOTS: usize = 4;
+
+// ===== STRUCT =====
+
+pub struct Macros {
+    // Sequence of controller states for each macro slot
+    pub slots: Vec<Vec<u8>>,
+    // Slot being recorded and last frame recorded
+    pub o_recording: Option<(usize, u64)>,
+    // Slot being played and frame at which the playback started
+    pub o_playing: Option<(usize, u64)>
+}
+
+impl Macros {
+    pub fn new(n_slots: usize) -> Self {
+        Macros {
+            slots: vec![vec![];n_slots],
+            o_recording: None,
+            o_playing: None
+        }
+    }
+
+    // Starts recording the given slot, or stops the recording if it is already recording
+    pub fn toggle_recording(&mut self, slot: usize) {
+        match self.o_recording {
+            Some((recorded_slot, _)) if recorded_slot == slot => self.o_recording = None,
+            _ => {
+                self.slots[slot].clear();
+                self.o_recording = Some((slot, u64::MAX));
+                self.o_playing = None;
+            }
+        }
+    }
+
+    // Starts playing the given slot
+    pub fn play(&mut self, slot: usize, frame: u64) {
+        if self.o_recording.is_none() && !self.slots[slot].is_empty() {
+            self.o_playing = Some((slot, frame));
+        }
+    }
+
+    // Records or plays back the controller state for the given frame
+    // Returns the controller state to use
+    pub fn apply(&mut self, buttons: u8, frame: u64) -> u8 {
+        if let Some((slot, last_frame)) = self.o_recording {
+            // Only one state is recorded per frame
+            if frame != last_frame {
+                self.slots[slot].push(buttons);
+                self.o_recording = Some((slot, frame));
+            }
+            return buttons;
+        }
+        if let Some((slot, start_frame)) = self.o_playing {
+            let index: usize = (frame - start_frame) as usize;
+            if index < self.slots[slot].len() {
+                return buttons | self.slots[slot][index];
+            }
+            self.o_playing = None;
+        }
+        buttons
+    }
+}
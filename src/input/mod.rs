@@ -0,0 +1,10 @@
+pub mod device;
+pub mod controller;
+pub mod four_score;
+pub mod famicom_adapter;
+pub mod zapper;
+pub mod vaus;
+pub mod power_pad;
+pub mod family_keyboard;
+pub mod turbo;
+pub mod macros;
\ No newline at end of file
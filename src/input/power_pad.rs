@@ -0,0 +1,83 @@
+// Represents the Power Pad (or Family Trainer mat), driven by the keyboard
+// Its 12 buttons are read serially on D3 and D4
+//  1  2  3  4
+//  5  6  7  8
+//  9 10 11 12
+
+// ===== IMPORTS =====
+
+use super::device::{InputDevice, InputState};
+
+// ===== CONSTANTS =====
+
+// Order in which the buttons are read on D3 and D4
+const D3_BUTTONS: [u8;8] = [2, 1, 5, 9, 6, 10, 11, 7];
+const D4_BUTTONS: [u8;4] = [4, 3, 12, 8];
+
+// ===== STRUCT =====
+
+#[derive(Debug, Clone, Copy)]
+pub struct PowerPad {
+    // Bit n is set if button n+1 is pressed
+    pub buttons: u16,
+    // Shifters for D3 and D4
+    pub shifters: [u8;2],
+    pub strobe: bool
+}
+
+impl Default for PowerPad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PowerPad {
+    pub fn new() -> Self {
+        PowerPad {
+            buttons: 0,
+            shifters: [0;2],
+            strobe: false
+        }
+    }
+
+    pub fn is_pressed(&self, button: u8) -> bool {
+        self.buttons & (1 << (button - 1)) > 0
+    }
+
+    pub fn update_shifters(&mut self) {
+        self.shifters = [0, 0x0F];
+        for (i, button) in D3_BUTTONS.iter().enumerate() {
+            self.shifters[0] |= (self.is_pressed(*button) as u8) << (7 - i);
+        }
+        for (i, button) in D4_BUTTONS.iter().enumerate() {
+            self.shifters[1] |= (self.is_pressed(*button) as u8) << (7 - i);
+        }
+    }
+}
+
+impl InputDevice for PowerPad {
+    fn write(&mut self, value: u8) {
+        let strobe: bool = (value & 0x01) > 0;
+        if self.strobe || strobe {
+            self.update_shifters();
+        }
+        self.strobe = strobe;
+    }
+
+    fn read(&mut self, port: usize) -> u8 {
+        let value: u8 = self.peek(port);
+        if !self.strobe {
+            self.shifters[0] = (self.shifters[0] << 1) | 0x01;
+            self.shifters[1] = (self.shifters[1] << 1) | 0x01;
+        }
+        value
+    }
+
+    fn peek(&self, _port: usize) -> u8 {
+        (((self.shifters[0] & 0x80 > 0) as u8) << 3) | (((self.shifters[1] & 0x80 > 0) as u8) << 4)
+    }
+
+    fn update(&mut self, state: &InputState) {
+        self.buttons = state.power_pad;
+    }
+}
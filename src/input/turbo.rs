@@ -0,0 +1,28 @@
+// Implements the turbo buttons
+// A turbo button is alternately pressed and released every few frames while it is held
+
+// ===== STRUCT =====
+
+#[derive(Debug, Clone, Copy)]
+pub struct Turbo {
+    // Number of frames during which the buttons are pressed, then released
+    pub rate: u64
+}
+
+impl Turbo {
+    pub fn new(rate: u64) -> Self {
+        Turbo {
+            rate: rate.max(1)
+        }
+    }
+
+    // Returns the buttons that are pressed during the given frame
+    pub fn apply(&self, buttons: u8, frame: u64) -> u8 {
+        if (frame / self.rate).is_multiple_of(2) {
+            buttons
+        }
+        else {
+            0
+        }
+    }
+}
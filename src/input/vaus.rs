@@ -0,0 +1,105 @@
+// Represents the Arkanoid Vaus controller, driven by the mouse
+// The position of the potentiometer is read serially, inverted and MSB first
+// - NES (controller port) : D3 is the fire button and D4 the potentiometer
+// - Famicom (expansion port) : D1 of 0x4016 is the fire button and D1 of 0x4017 the potentiometer
+
+// ===== IMPORTS =====
+
+use crate::ppu::ppu::FRAME_WIDTH;
+
+use super::device::{InputDevice, InputState};
+
+// ===== CONSTANTS =====
+
+// Values returned by the potentiometer at both ends of the screen
+const MIN_POSITION: u16 = 0x62;
+const MAX_POSITION: u16 = 0xF2;
+
+// ===== STRUCT =====
+
+#[derive(Debug, Clone, Copy)]
+pub struct Vaus {
+    // Is the controller plugged in the Famicom expansion port ?
+    pub famicom: bool,
+    pub position: u8,
+    pub button: bool,
+    pub shifter: u8,
+    pub strobe: bool
+}
+
+impl Vaus {
+    pub fn new(famicom: bool) -> Self {
+        Vaus {
+            famicom,
+            position: MIN_POSITION as u8,
+            button: false,
+            shifter: 0,
+            strobe: false
+        }
+    }
+
+    pub fn get_serial_bit(&self) -> u8 {
+        (self.shifter & 0x80 > 0) as u8
+    }
+}
+
+impl InputDevice for Vaus {
+    fn write(&mut self, value: u8) {
+        let strobe: bool = (value & 0x01) > 0;
+        if self.strobe || strobe {
+            self.shifter = !self.position;
+        }
+        self.strobe = strobe;
+    }
+
+    fn read(&mut self, port: usize) -> u8 {
+        let value: u8 = self.peek(port);
+        // The Famicom version only shifts when its data line is read
+        if !self.strobe && (!self.famicom || port == 1) {
+            self.shifter <<= 1;
+        }
+        value
+    }
+
+    fn peek(&self, port: usize) -> u8 {
+        if self.famicom {
+            match port {
+                0 => (self.button as u8) << 1,
+                _ => self.get_serial_bit() << 1
+            }
+        }
+        else {
+            ((self.button as u8) << 3) | (self.get_serial_bit() << 4)
+        }
+    }
+
+    fn update(&mut self, state: &InputState) {
+        if let Some((x, _)) = state.mouse_position {
+            self.position = (MIN_POSITION + (x as u16)*(MAX_POSITION - MIN_POSITION)/(FRAME_WIDTH as u16 - 1)) as u8;
+        }
+        self.button = state.mouse_pressed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nes_port() {
+        let mut vaus: Vaus = Vaus::new(false);
+        vaus.position = 0xA5;
+        vaus.button = true;
+        vaus.write(1);
+        vaus.write(0);
+        // The potentiometer is inverted and read MSB first on D4, the fire button stays on D3
+        let serial: u8 = !0xA5;
+        for i in 0..8 {
+            let value: u8 = vaus.read(1);
+            assert_eq!(value & 0x08, 0x08, "fire button on bit {}", i);
+            assert_eq!((value & 0x10) >> 4, (serial >> (7 - i)) & 0x01, "potentiometer bit {}", i);
+        }
+        vaus.button = false;
+        assert_eq!(vaus.peek(1) & 0x08, 0);
+    }
+}
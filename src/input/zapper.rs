@@ -0,0 +1,96 @@
+// Represents the Zapper light gun, driven by the mouse
+// D3 is low when light is detected and D4 is high while the trigger is pulled
+
+// ===== IMPORTS =====
+
+use std::sync::{Arc, Mutex};
+
+use crate::ppu::ppu::{FRAME_HEIGHT, FRAME_WIDTH, PPU};
+
+use super::device::{InputDevice, InputState};
+
+// ===== CONSTANTS =====
+
+// Distance in pixels around the cursor seen by the light sensor
+const SENSOR_RADIUS: isize = 2;
+// Number of scanlines during which a drawn pixel is still seen by the light sensor
+const LIGHT_DURATION: usize = 20;
+// Minimal brightness of a pixel seen by the light sensor
+const BRIGHTNESS_THRESHOLD: u16 = 85;
+
+// ===== STRUCT =====
+
+pub struct Zapper {
+    // Position of the cursor on the screen
+    pub o_position: Option<(usize, usize)>,
+    pub trigger: bool,
+
+    // Used to look at the pixels drawn by the PPU
+    pub p_ppu: Arc<Mutex<PPU>>
+}
+
+impl Zapper {
+    pub fn new(p_ppu: Arc<Mutex<PPU>>) -> Self {
+        Zapper {
+            o_position: None,
+            trigger: false,
+            p_ppu
+        }
+    }
+
+    // Checks if a bright pixel has been drawn near the cursor during the last scanlines
+    pub fn detect_light(&self) -> bool {
+        let (x, y) = match self.o_position {
+            Some(position) => position,
+            None => return false
+        };
+        let ppu = self.p_ppu.lock().unwrap();
+        let scanline: usize = ppu.scanline as usize;
+        let cycle: usize = ppu.cycles as usize;
+        for dy in -SENSOR_RADIUS..=SENSOR_RADIUS {
+            let pixel_y: isize = y as isize + dy;
+            if pixel_y < 0 || pixel_y >= FRAME_HEIGHT as isize {
+                continue;
+            }
+            let pixel_y: usize = pixel_y as usize;
+            // This line has not been drawn yet or its light is gone
+            if pixel_y > scanline || scanline - pixel_y > LIGHT_DURATION {
+                continue;
+            }
+            for dx in -SENSOR_RADIUS..=SENSOR_RADIUS {
+                let pixel_x: isize = x as isize + dx;
+                if pixel_x < 0 || pixel_x >= FRAME_WIDTH as isize {
+                    continue;
+                }
+                let pixel_x: usize = pixel_x as usize;
+                // This pixel of the current line has not been drawn yet
+                if pixel_y == scanline && pixel_x + 1 >= cycle {
+                    continue;
+                }
+                let color = ppu.frame_buffer[pixel_y*FRAME_WIDTH + pixel_x];
+                let brightness: u16 = (color.red as u16 + color.green as u16 + color.blue as u16) / 3;
+                if brightness >= BRIGHTNESS_THRESHOLD {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+impl InputDevice for Zapper {
+    fn write(&mut self, _value: u8) {}
+
+    fn read(&mut self, port: usize) -> u8 {
+        self.peek(port)
+    }
+
+    fn peek(&self, _port: usize) -> u8 {
+        (((!self.detect_light()) as u8) << 3) | ((self.trigger as u8) << 4)
+    }
+
+    fn update(&mut self, state: &InputState) {
+        self.o_position = state.mouse_position;
+        self.trigger = state.mouse_pressed;
+    }
+}
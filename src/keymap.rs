@@ -0,0 +1,210 @@
+// Keyboard mapping of the frontend
+// Converts the keys held on the keyboard into inputs for the emulated devices
+
+// ===== IMPORTS =====
+
+use winit::event::VirtualKeyCode;
+use winit_input_helper::WinitInputHelper;
+
+use nes_emulator::input::{controller::ControllerInput, device::InputState, family_keyboard::KEYBOARD_ROWS, macros::MACRO_SLOTS};
+use nes_emulator::memory_viewer::ViewerKey;
+
+// ===== CONSTANTS =====
+
+// Keyboard mapping of each controller
+pub const CONTROLLER_KEYS: [[(VirtualKeyCode, ControllerInput);8];4] = [
+    [
+        (VirtualKeyCode::Z, ControllerInput::Up),
+        (VirtualKeyCode::S, ControllerInput::Down),
+        (VirtualKeyCode::Q, ControllerInput::Left),
+        (VirtualKeyCode::D, ControllerInput::Right),
+        (VirtualKeyCode::I, ControllerInput::A),
+        (VirtualKeyCode::O, ControllerInput::B),
+        (VirtualKeyCode::X, ControllerInput::Start),
+        (VirtualKeyCode::C, ControllerInput::Select)
+    ],
+    [
+        (VirtualKeyCode::Up, ControllerInput::Up),
+        (VirtualKeyCode::Down, ControllerInput::Down),
+        (VirtualKeyCode::Left, ControllerInput::Left),
+        (VirtualKeyCode::Right, ControllerInput::Right),
+        (VirtualKeyCode::Numpad2, ControllerInput::A),
+        (VirtualKeyCode::Numpad1, ControllerInput::B),
+        (VirtualKeyCode::Return, ControllerInput::Start),
+        (VirtualKeyCode::RShift, ControllerInput::Select)
+    ],
+    [
+        (VirtualKeyCode::T, ControllerInput::Up),
+        (VirtualKeyCode::G, ControllerInput::Down),
+        (VirtualKeyCode::F, ControllerInput::Left),
+        (VirtualKeyCode::H, ControllerInput::Right),
+        (VirtualKeyCode::Y, ControllerInput::A),
+        (VirtualKeyCode::R, ControllerInput::B),
+        (VirtualKeyCode::V, ControllerInput::Start),
+        (VirtualKeyCode::B, ControllerInput::Select)
+    ],
+    [
+        (VirtualKeyCode::Numpad8, ControllerInput::Up),
+        (VirtualKeyCode::Numpad5, ControllerInput::Down),
+        (VirtualKeyCode::Numpad4, ControllerInput::Left),
+        (VirtualKeyCode::Numpad6, ControllerInput::Right),
+        (VirtualKeyCode::Numpad9, ControllerInput::A),
+        (VirtualKeyCode::Numpad7, ControllerInput::B),
+        (VirtualKeyCode::NumpadAdd, ControllerInput::Start),
+        (VirtualKeyCode::NumpadSubtract, ControllerInput::Select)
+    ]
+];
+
+// Keyboard mapping of the turbo buttons of the first two controllers
+pub const TURBO_KEYS: [[(VirtualKeyCode, ControllerInput);2];2] = [
+    [
+        (VirtualKeyCode::K, ControllerInput::A),
+        (VirtualKeyCode::L, ControllerInput::B)
+    ],
+    [
+        (VirtualKeyCode::NumpadDecimal, ControllerInput::A),
+        (VirtualKeyCode::Numpad0, ControllerInput::B)
+    ]
+];
+
+// Keys playing the macros of the first controller
+// Held with control, they start or stop the recording of the macro
+pub const MACRO_KEYS: [VirtualKeyCode;MACRO_SLOTS] = [
+    VirtualKeyCode::F9, VirtualKeyCode::F10, VirtualKeyCode::F11, VirtualKeyCode::F12
+];
+
+// Key of the reset button of the console
+// Held with control, it power cycles the console
+pub const RESET_KEY: VirtualKeyCode = VirtualKeyCode::Back;
+
+// Key pausing the emulation in the debugger
+pub const PAUSE_KEY: VirtualKeyCode = VirtualKeyCode::Pause;
+
+// Key showing the memory viewer window again after it was closed
+// It must not be used by the Family BASIC keyboard
+pub const MEMORY_VIEWER_KEY: VirtualKeyCode = VirtualKeyCode::Scroll;
+
+// Keyboard mapping of the Power Pad buttons 1 to 12
+pub const POWER_PAD_KEYS: [VirtualKeyCode;12] = [
+    VirtualKeyCode::Key1, VirtualKeyCode::Key2, VirtualKeyCode::Key3, VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5, VirtualKeyCode::Key6, VirtualKeyCode::Key7, VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9, VirtualKeyCode::Key0, VirtualKeyCode::Minus, VirtualKeyCode::Equals
+];
+
+// Keyboard mapping of the Family BASIC keyboard matrix
+// For each row and column, the keys are given from D4 to D1
+pub const FAMILY_KEYBOARD_KEYS: [[[VirtualKeyCode;4];2];KEYBOARD_ROWS] = [
+    [
+        [VirtualKeyCode::RBracket, VirtualKeyCode::LBracket, VirtualKeyCode::Return, VirtualKeyCode::F8],
+        [VirtualKeyCode::End, VirtualKeyCode::Backslash, VirtualKeyCode::RShift, VirtualKeyCode::RControl]
+    ],
+    [
+        [VirtualKeyCode::Semicolon, VirtualKeyCode::Apostrophe, VirtualKeyCode::Grave, VirtualKeyCode::F7],
+        [VirtualKeyCode::Equals, VirtualKeyCode::Minus, VirtualKeyCode::Slash, VirtualKeyCode::RAlt]
+    ],
+    [
+        [VirtualKeyCode::K, VirtualKeyCode::L, VirtualKeyCode::O, VirtualKeyCode::F6],
+        [VirtualKeyCode::Key0, VirtualKeyCode::P, VirtualKeyCode::Comma, VirtualKeyCode::Period]
+    ],
+    [
+        [VirtualKeyCode::J, VirtualKeyCode::U, VirtualKeyCode::I, VirtualKeyCode::F5],
+        [VirtualKeyCode::Key8, VirtualKeyCode::Key9, VirtualKeyCode::N, VirtualKeyCode::M]
+    ],
+    [
+        [VirtualKeyCode::H, VirtualKeyCode::G, VirtualKeyCode::Y, VirtualKeyCode::F4],
+        [VirtualKeyCode::Key6, VirtualKeyCode::Key7, VirtualKeyCode::V, VirtualKeyCode::B]
+    ],
+    [
+        [VirtualKeyCode::D, VirtualKeyCode::R, VirtualKeyCode::T, VirtualKeyCode::F3],
+        [VirtualKeyCode::Key4, VirtualKeyCode::Key5, VirtualKeyCode::C, VirtualKeyCode::F]
+    ],
+    [
+        [VirtualKeyCode::A, VirtualKeyCode::S, VirtualKeyCode::W, VirtualKeyCode::F2],
+        [VirtualKeyCode::Key3, VirtualKeyCode::E, VirtualKeyCode::Z, VirtualKeyCode::X]
+    ],
+    [
+        [VirtualKeyCode::LControl, VirtualKeyCode::Q, VirtualKeyCode::Tab, VirtualKeyCode::F1],
+        [VirtualKeyCode::Key2, VirtualKeyCode::Key1, VirtualKeyCode::LAlt, VirtualKeyCode::LShift]
+    ],
+    [
+        [VirtualKeyCode::Left, VirtualKeyCode::Right, VirtualKeyCode::Up, VirtualKeyCode::Home],
+        [VirtualKeyCode::Insert, VirtualKeyCode::Delete, VirtualKeyCode::Space, VirtualKeyCode::Down]
+    ]
+];
+
+// ===== FUNCTIONS =====
+
+// Returns the turbo buttons held for each controller
+pub fn read_turbo_keys(input_helper: &WinitInputHelper) -> [u8;4] {
+    let mut buttons: [u8;4] = [0;4];
+    for (player, keys) in TURBO_KEYS.iter().enumerate() {
+        for (key, input) in keys.iter() {
+            if input_helper.key_held(*key) {
+                buttons[player] |= *input as u8;
+            }
+        }
+    }
+    buttons
+}
+
+// Keys of the memory viewer window, the characters are typed separately
+pub fn memory_viewer_key(key: VirtualKeyCode) -> Option<ViewerKey> {
+    match key {
+        VirtualKeyCode::Up => Some(ViewerKey::Up),
+        VirtualKeyCode::Down => Some(ViewerKey::Down),
+        VirtualKeyCode::Left => Some(ViewerKey::Left),
+        VirtualKeyCode::Right => Some(ViewerKey::Right),
+        VirtualKeyCode::PageUp => Some(ViewerKey::PageUp),
+        VirtualKeyCode::PageDown => Some(ViewerKey::PageDown),
+        VirtualKeyCode::Home => Some(ViewerKey::Home),
+        VirtualKeyCode::End => Some(ViewerKey::End),
+        VirtualKeyCode::Tab => Some(ViewerKey::NextSpace),
+        VirtualKeyCode::Return => Some(ViewerKey::Enter),
+        VirtualKeyCode::Escape => Some(ViewerKey::Escape),
+        VirtualKeyCode::Back => Some(ViewerKey::Backspace),
+        _ => None
+    }
+}
+
+// Fills the input state with the keys held on the keyboard
+pub fn read_keyboard(input_helper: &WinitInputHelper, input_state: &mut InputState) {
+    // Controllers
+    for (player, keys) in CONTROLLER_KEYS.iter().enumerate() {
+        for (key, input) in keys.iter() {
+            if input_helper.key_held(*key) {
+                input_state.controllers[player] |= *input as u8;
+            }
+        }
+    }
+
+    // Power Pad
+    for (button, key) in POWER_PAD_KEYS.iter().enumerate() {
+        if input_helper.key_held(*key) {
+            input_state.power_pad |= 1 << button;
+        }
+    }
+
+    // Family BASIC keyboard
+    for (row, columns) in FAMILY_KEYBOARD_KEYS.iter().enumerate() {
+        for (column, keys) in columns.iter().enumerate() {
+            for (i, key) in keys.iter().enumerate() {
+                if input_helper.key_held(*key) {
+                    input_state.keyboard_matrix[row][column] |= 1 << (3 - i);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frontend_keys_are_not_keyboard_keys() {
+        let keyboard_keys: Vec<VirtualKeyCode> = FAMILY_KEYBOARD_KEYS.iter().flatten().flatten().copied().collect();
+        for key in [RESET_KEY, PAUSE_KEY, MEMORY_VIEWER_KEY].iter().chain(MACRO_KEYS.iter()) {
+            assert!(!keyboard_keys.contains(key), "{:?}", key);
+        }
+    }
+}
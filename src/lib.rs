@@ -0,0 +1,27 @@
+// Emulation core of the NES, shared by the binaries of the crate
+
+// The components are shared through Arc<Mutex> even though some of them (the GUI, the mappers) aren't Send
+// NES is moved once to the emulation thread (unsafe impl Send for NES) and the components are only locked from there,
+// or from the frontend through the Mutex, so the Arc is never used to send them to another thread on its own
+#![allow(clippy::arc_with_non_send_sync)]
+
+pub mod cpu;
+pub mod apu;
+pub mod bus;
+pub mod dma;
+pub mod cartridge;
+pub mod ppu;
+pub mod nes;
+pub mod debugger;
+pub mod gdb_stub;
+pub mod symbols;
+pub mod code_data_logger;
+pub mod disassembler;
+pub mod trace_logger;
+pub mod memory_viewer;
+pub mod gui;
+pub mod input;
+pub mod movie;
+pub mod headless;
+pub mod test_rom;
+pub mod golden_frame;
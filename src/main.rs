@@ -1,24 +1,18 @@
-mod cpu;
-mod bus;
-mod cartridge;
-mod ppu;
-mod nes;
-mod gui;
-mod controllers;
-
-use std::{path::Path, sync::{Arc, Mutex}, thread};
-
-use bus::Bus;
-use cartridge::cartridge::Cartridge;
-use clap::{App, Arg};
-use controllers::ControllerInput;
-use cpu::cpu::CPU;
+// The components are shared through Arc<Mutex> as the library expects, see src/lib.rs
+#![allow(clippy::arc_with_non_send_sync)]
+
+mod gamepad;
+mod keymap;
+
+use std::{path::Path, process, sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}}, thread, time::{SystemTime, UNIX_EPOCH}};
+
+use clap::{App, Arg, ArgMatches};
 use env_logger::Env;
-use log::{error, warn};
-use nes::NES;
-use ppu::ppu::PPU;
-use gui::GUI;
-use winit::{event::{Event, VirtualKeyCode}, event_loop::{ControlFlow, EventLoop}};
+use gamepad::{GamepadMapping, Gamepads, create_gamepad_source};
+use log::{error, info, warn};
+use nes_emulator::{bus::{Bus, RamPattern}, cartridge::cartridge::Cartridge, code_data_logger::CodeDataLogger, cpu::cpu::CPU, debugger::Debugger, gdb_stub::GdbStub, gui::{GUI, MemoryWindow}, memory_viewer::MemoryViewer, movie::Movie, nes::NES, ppu::ppu::PPU, symbols::SymbolTable, trace_logger::{self, TraceFormat, TraceLogger, TraceTrigger}};
+use nes_emulator::input::{device::{COMMAND_POWER_CYCLE, COMMAND_SOFT_RESET, InputState, create_expansion_device, create_port_device}, turbo::Turbo};
+use winit::{event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent}, event_loop::{ControlFlow, EventLoop}};
 use winit_input_helper::WinitInputHelper;
 
 fn main() {
@@ -49,7 +43,179 @@ fn main() {
             Arg::new("log")
                 .short('l')
                 .long("log")
-                .about("Display the CPU logs to the console")
+                .about("Display the CPU logs to the console, with the trace options")
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .value_name("FILE")
+                .takes_value(true)
+                .about("Writes the trace of the executed instructions to a file")
+        )
+        .arg(
+            Arg::new("trace-format")
+                .long("trace-format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&["nintendulator", "fceux", "mesen"])
+                .default_value("nintendulator")
+                .about("Sets the format of the trace lines")
+        )
+        .arg(
+            Arg::new("trace-pc")
+                .long("trace-pc")
+                .value_name("START-END")
+                .takes_value(true)
+                .about("Only traces the instructions in this range of hexadecimal addresses")
+        )
+        .arg(
+            Arg::new("trace-frames")
+                .long("trace-frames")
+                .value_name("FIRST-LAST")
+                .takes_value(true)
+                .about("Only traces the instructions of these frames")
+        )
+        .arg(
+            Arg::new("trace-trigger")
+                .long("trace-trigger")
+                .value_name("ADDR[=VALUE]")
+                .takes_value(true)
+                .about("Starts the trace when the PC reaches the hexadecimal address, or when the byte at the address has the value")
+        )
+        .arg(
+            Arg::new("trace-ppu")
+                .long("trace-ppu")
+                .about("Adds the v and t registers of the PPU to the trace")
+        )
+        .arg(
+            Arg::new("trace-banks")
+                .long("trace-banks")
+                .about("Adds the PRG ROM banks mapped at $8000 and $C000 to the trace")
+        )
+        .arg(
+            Arg::new("debugger")
+                .long("debugger")
+                .about("Runs the command line debugger in the terminal when the Pause key is pressed")
+        )
+        .arg(
+            Arg::new("pause")
+                .long("pause")
+                .requires("debugger")
+                .about("Pauses the emulation in the debugger before the first instruction")
+        )
+        .arg(
+            Arg::new("symbols")
+                .long("symbols")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .about("Loads the labels of a ca65 debug file (.dbg), a FCEUX name list (.nl) or a Mesen label file (.mlb), can be repeated")
+        )
+        .arg(
+            Arg::new("cdl")
+                .long("cdl")
+                .value_name("FILE")
+                .takes_value(true)
+                .about("Marks the code and data of the ROM in a FCEUX Code/Data Logger file, the file is updated if it exists and saved on exit")
+        )
+        .arg(
+            Arg::new("memory-viewer")
+                .long("memory-viewer")
+                .about("Opens the memory viewer and hex editor window")
+        )
+        .arg(
+            Arg::new("gdb")
+                .long("gdb")
+                .value_name("PORT")
+                .takes_value(true)
+                .conflicts_with("debugger")
+                .about("Waits for a GDB connection on this local port before the first instruction")
+        )
+        .arg(
+            Arg::new("start-address")
+                .long("start-address")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .about("Starts the CPU at this hexadecimal address instead of the reset vector (C000 runs nestest in automation mode)")
+        )
+        .arg(
+            Arg::new("port1")
+                .long("port1")
+                .value_name("DEVICE")
+                .takes_value(true)
+                .possible_values(&["controller", "zapper", "vaus", "power-pad"])
+                .default_value("controller")
+                .about("Sets the device plugged in the first controller port")
+        )
+        .arg(
+            Arg::new("port2")
+                .long("port2")
+                .value_name("DEVICE")
+                .takes_value(true)
+                .possible_values(&["controller", "zapper", "vaus", "power-pad"])
+                .default_value("controller")
+                .about("Sets the device plugged in the second controller port")
+        )
+        .arg(
+            Arg::new("four-score")
+                .long("four-score")
+                .conflicts_with_all(&["port1", "port2"])
+                .about("Plug a NES Four Score in the controller ports")
+        )
+        .arg(
+            Arg::new("expansion")
+                .long("expansion")
+                .value_name("DEVICE")
+                .takes_value(true)
+                .possible_values(&["none", "famicom-4p", "vaus", "keyboard"])
+                .default_value("none")
+                .about("Sets the device plugged in the Famicom expansion port")
+        )
+        .arg(
+            Arg::new("turbo-rate")
+                .long("turbo-rate")
+                .value_name("FRAMES")
+                .takes_value(true)
+                .default_value("2")
+                .about("Sets the number of frames during which turbo buttons are pressed, then released")
+        )
+        .arg(
+            Arg::new("record-movie")
+                .long("record-movie")
+                .value_name("FM2_FILE")
+                .takes_value(true)
+                .conflicts_with("play-movie")
+                .about("Records the inputs from power on to a movie, saved when the emulator is closed")
+        )
+        .arg(
+            Arg::new("play-movie")
+                .long("play-movie")
+                .value_name("FM2_FILE")
+                .takes_value(true)
+                .about("Plays the inputs of a movie from power on")
+        )
+        .arg(
+            Arg::new("ram-pattern")
+                .long("ram-pattern")
+                .value_name("PATTERN")
+                .takes_value(true)
+                .possible_values(&["zeros", "ff", "random"])
+                .default_value("zeros")
+                .about("Sets the content of the RAM at power on")
+        )
+        .arg(
+            Arg::new("ram-seed")
+                .long("ram-seed")
+                .value_name("SEED")
+                .takes_value(true)
+                .about("Sets the seed of the random RAM pattern (taken from the clock by default)")
+        )
+        .arg(
+            Arg::new("gamepad-config")
+                .long("gamepad-config")
+                .value_name("FILE")
+                .takes_value(true)
+                .about("Remaps the gamepad buttons and sets the deadzone of the axes (needs the gamepad feature)")
         )
         .get_matches();
 
@@ -80,9 +246,58 @@ fn main() {
         warn!("Invalid debug level : {:?}, value must be in [0;4]",matches.value_of("debug"));
     }
 
-    // Display logs from cpu
+    // Start address of the CPU
 
-    let display_cpu_logs: bool = matches.is_present("log");
+    let o_start_address: Option<u16> = matches.value_of("start-address").and_then(|value| {
+        match u16::from_str_radix(value.trim_start_matches('$').trim_start_matches("0x"), 16) {
+            Ok(address) => Some(address),
+            Err(_) => {
+                warn!("Invalid start address : {:?}, using the reset vector", value);
+                None
+            }
+        }
+    });
+
+    // Input devices
+
+    let port_devices: [&str;2] = match matches.is_present("four-score") {
+        false => [matches.value_of("port1").unwrap(), matches.value_of("port2").unwrap()],
+        true => ["four-score", "four-score"]
+    };
+    let expansion_device: &str = matches.value_of("expansion").unwrap();
+
+    // Turbo buttons
+
+    let turbo_rate: u64 = match matches.value_of("turbo-rate").unwrap().parse() {
+        Ok(rate) if rate > 0 => rate,
+        _ => {
+            warn!("Invalid turbo rate : {:?}, using 2 frames", matches.value_of("turbo-rate"));
+            2
+        }
+    };
+
+    // RAM content at power on
+
+    let ram_pattern: RamPattern = match matches.value_of("ram-pattern").unwrap() {
+        "ff" => RamPattern::Ones,
+        "random" => {
+            let o_seed: Option<u64> = matches.value_of("ram-seed").and_then(|value| {
+                match value.parse() {
+                    Ok(seed) => Some(seed),
+                    Err(_) => {
+                        warn!("Invalid RAM seed : {:?}, using the clock", value);
+                        None
+                    }
+                }
+            });
+            let seed: u64 = o_seed.unwrap_or_else(|| {
+                SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos() as u64).unwrap_or(0)
+            });
+            info!("Random RAM pattern seed : {}", seed);
+            RamPattern::Random(seed)
+        },
+        _ => RamPattern::Zeros
+    };
 
     // Path to the game to launch
 
@@ -100,10 +315,126 @@ fn main() {
     let p_gui: Arc<Mutex<GUI>> = Arc::new(Mutex::new(GUI::new(&event_loop)));
 
     // Creates the NES architecture
-    let p_ppu: Arc<Mutex<PPU>> = Arc::new(Mutex::new(PPU::new(p_gui.clone())));
+    let p_ppu: Arc<Mutex<PPU>> = Arc::new(Mutex::new(PPU::new(Some(p_gui.clone()))));
     let p_bus: Arc<Mutex<Bus>> = Arc::new(Mutex::new(Bus::new(p_ppu.clone())));
-    let p_cpu: Arc<Mutex<CPU>> = Arc::new(Mutex::new(CPU::new(p_bus.clone(), display_cpu_logs)));
-    let mut nes: NES = NES::new(p_bus.clone(), p_cpu.clone(), p_ppu.clone(), p_gui.clone());
+    p_bus.lock().unwrap().input_ports = [
+        create_port_device(port_devices[0], 0, p_ppu.clone()),
+        create_port_device(port_devices[1], 1, p_ppu.clone())
+    ];
+    p_bus.lock().unwrap().o_p_expansion_device = create_expansion_device(expansion_device);
+    let p_cpu: Arc<Mutex<CPU>> = Arc::new(Mutex::new(CPU::new(p_bus.clone(), false)));
+    let mut nes: NES = NES::new(p_bus.clone(), p_cpu.clone(), p_ppu.clone(), Some(p_gui.clone()));
+    nes.o_start_address = o_start_address;
+
+    // Trace of the instructions, the CPU logs are written to the console through a trace logger
+    let o_trace_path: Option<&str> = match (matches.value_of("trace"), matches.is_present("log")) {
+        (Some(path), _) => Some(path),
+        (None, true) => Some("-"),
+        (None, false) => None
+    };
+    if let Some(path) = o_trace_path {
+        match create_trace_logger(&matches, path, p_cpu.clone(), p_bus.clone(), p_ppu.clone()) {
+            Err(why) => {
+                error!("Couldn't start the trace: {}", why);
+                process::exit(1);
+            },
+            Ok(trace_logger) => nes.o_p_trace_logger = Some(Arc::new(Mutex::new(trace_logger)))
+        }
+    }
+    let o_p_trace_logger: Option<Arc<Mutex<TraceLogger>>> = nes.o_p_trace_logger.clone();
+
+    // Symbols
+    if let Some(paths) = matches.values_of("symbols") {
+        let mut symbols: SymbolTable = SymbolTable::new();
+        for path in paths {
+            if let Err(why) = symbols.load(Path::new(path)) {
+                panic!("Couldn't load the symbols: {}", why);
+            }
+        }
+        p_cpu.lock().unwrap().o_symbols = Some(Arc::new(symbols));
+    }
+    nes.ram_pattern = ram_pattern;
+
+    // Debugger
+    let mut o_pause_request: Option<Arc<AtomicBool>> = None;
+    let mut o_quit_request: Option<Arc<AtomicBool>> = None;
+    if matches.is_present("debugger") {
+        let mut debugger: Debugger = Debugger::new(p_cpu.clone(), p_bus.clone(), p_ppu.clone());
+        debugger.paused = matches.is_present("pause");
+        o_pause_request = Some(debugger.p_pause_request.clone());
+        o_quit_request = Some(debugger.p_quit_request.clone());
+        nes.o_debugger = Some(debugger);
+    }
+    if let Some(port) = matches.value_of("gdb") {
+        let port: u16 = port.parse().unwrap_or_else(|_| panic!("Invalid GDB port: {}", port));
+        match GdbStub::new(port, p_cpu.clone(), p_bus.clone(), p_ppu.clone()) {
+            Err(why) => panic!("{}", why),
+            Ok(gdb_stub) => {
+                o_pause_request = Some(gdb_stub.debugger.p_pause_request.clone());
+                nes.o_gdb_stub = Some(gdb_stub);
+            }
+        }
+    }
+
+    // Movie, the ports get a Four Score if the movie needs it
+    if let Some(movie_path) = matches.value_of("play-movie") {
+        match Movie::load_fm2(Path::new(movie_path)) {
+            Err(why) => {
+                error!("Couldn't load movie {}: {}", movie_path, why);
+                process::exit(1);
+            },
+            Ok(movie) => nes.set_movie(movie)
+        }
+    }
+    else if matches.is_present("record-movie") {
+        let rom_filename: &str = path.file_stem().and_then(|name| name.to_str()).unwrap_or("");
+        nes.set_movie(Movie::new(rom_filename, matches.is_present("four-score")));
+    }
+    let o_p_movie: Option<Arc<Mutex<Movie>>> = nes.o_p_movie.clone();
+    nes.turbo = Turbo::new(turbo_rate);
+    let record_path: Option<String> = matches.value_of("record-movie").map(String::from);
+    let p_input_state: Arc<Mutex<InputState>> = nes.p_input_state.clone();
+
+    // Gamepads, only read when the emulator is built with the gamepad feature
+    #[cfg(not(feature = "gamepad"))]
+    if matches.is_present("gamepad-config") {
+        warn!("Gamepads are not supported by this build, run it with `--features gamepad` to use --gamepad-config");
+    }
+    let mut gamepad_mapping: GamepadMapping = GamepadMapping::new();
+    if let Some(config_path) = matches.value_of("gamepad-config") {
+        match GamepadMapping::load(Path::new(config_path)) {
+            Err(why) => warn!("Invalid gamepad config, the default mapping is used: {}", why),
+            Ok(mapping) => gamepad_mapping = mapping
+        }
+    }
+    let mut gamepads: Gamepads = Gamepads::new(create_gamepad_source(), gamepad_mapping);
+
+    // Code/Data Logger, the flags of the previous sessions are kept
+    let mut o_p_code_data_logger: Option<Arc<Mutex<CodeDataLogger>>> = None;
+    let cdl_path: Option<String> = matches.value_of("cdl").map(String::from);
+    if let Some(path) = cdl_path.as_ref() {
+        let code_data_logger: CodeDataLogger = match Path::new(path).exists() {
+            // The file isn't replaced by an empty one when it doesn't match the ROM
+            true => CodeDataLogger::load(Path::new(path), cartridge.prg_rom_size(), cartridge.chr_rom_size()).unwrap_or_else(|why| {
+                error!("Couldn't load the Code/Data Logger file: {}", why);
+                process::exit(1);
+            }),
+            false => CodeDataLogger::new(cartridge.prg_rom_size(), cartridge.chr_rom_size())
+        };
+        let p_code_data_logger: Arc<Mutex<CodeDataLogger>> = Arc::new(Mutex::new(code_data_logger));
+        nes.set_code_data_logger(p_code_data_logger.clone());
+        o_p_code_data_logger = Some(p_code_data_logger);
+    }
+
+    // Memory viewer, in its own window
+    let mut o_p_memory_viewer: Option<Arc<Mutex<MemoryViewer>>> = None;
+    let mut o_memory_window: Option<MemoryWindow> = None;
+    if matches.is_present("memory-viewer") {
+        let memory_viewer: MemoryViewer = MemoryViewer::new(p_bus.clone(), p_ppu.clone(), cartridge.prg_rom.clone(), cartridge.chr_rom.clone());
+        o_p_memory_viewer = Some(Arc::new(Mutex::new(memory_viewer)));
+        o_memory_window = Some(MemoryWindow::new(&event_loop));
+    }
+    nes.o_p_memory_viewer = o_p_memory_viewer.clone();
 
     // Runs the game on the cartridge
     nes.insert_cartdrige(cartridge);
@@ -116,6 +447,35 @@ fn main() {
     event_loop.run(move |event, _, control_flow| {
         *control_flow = ControlFlow::Wait;
 
+        // Events of the memory viewer window, they aren't given to the emulation
+        if let (Some(memory_window), Some(p_memory_viewer)) = (o_memory_window.as_mut(), o_p_memory_viewer.as_ref()) {
+            match &event {
+                Event::RedrawRequested(window_id) if *window_id == memory_window.id() => {
+                    if let Err(why) = memory_window.render(&p_memory_viewer.lock().unwrap()) {
+                        error!("{}", why);
+                    }
+                    return;
+                },
+                Event::WindowEvent { window_id, event: window_event } if *window_id == memory_window.id() => {
+                    let mut memory_viewer = p_memory_viewer.lock().unwrap();
+                    match window_event {
+                        WindowEvent::CloseRequested => memory_window.set_visible(false),
+                        WindowEvent::Resized(size) => memory_window.resize(size.width, size.height),
+                        WindowEvent::ReceivedCharacter(character) => memory_viewer.handle_character(*character),
+                        WindowEvent::KeyboardInput { input: KeyboardInput { virtual_keycode: Some(key), state: ElementState::Pressed, .. }, .. } => {
+                            if let Some(viewer_key) = keymap::memory_viewer_key(*key) {
+                                memory_viewer.handle_key(viewer_key);
+                            }
+                        },
+                        _ => ()
+                    }
+                    memory_window.request_redraw();
+                    return;
+                },
+                _ => ()
+            }
+        }
+
         if let Event::RedrawRequested(_) = event {
             if main_pixels.lock().unwrap()
                 .render()
@@ -125,11 +485,35 @@ fn main() {
                 *control_flow = ControlFlow::Exit;
                 return;
             }
+            // The memory viewer is drawn with the frames of the game
+            if let Some(memory_window) = o_memory_window.as_ref() {
+                memory_window.request_redraw();
+            }
         }
 
         if input_helper.update(&event) {
-            // Close event
-            if input_helper.key_pressed(VirtualKeyCode::Escape) || input_helper.quit() {
+            // Close event, or quit command of the debugger
+            let quit_requested: bool = o_quit_request.as_ref().is_some_and(|quit_request| quit_request.load(Ordering::Relaxed));
+            if input_helper.key_pressed(VirtualKeyCode::Escape) || input_helper.quit() || quit_requested {
+                // Save the recorded movie
+                if let (Some(p_movie), Some(record_path)) = (o_p_movie.as_ref(), record_path.as_ref()) {
+                    match p_movie.lock().unwrap().save_fm2(Path::new(record_path)) {
+                        Err(why) => error!("{}", why),
+                        Ok(_) => info!("Movie saved to {}", record_path)
+                    }
+                }
+                if let Some(p_trace_logger) = o_p_trace_logger.as_ref() {
+                    p_trace_logger.lock().unwrap().flush();
+                }
+                // Save the Code/Data Logger file
+                if let (Some(p_code_data_logger), Some(path)) = (o_p_code_data_logger.as_ref(), cdl_path.as_ref()) {
+                    let code_data_logger = p_code_data_logger.lock().unwrap();
+                    let (code, data): (usize, usize) = code_data_logger.prg_statistics();
+                    match code_data_logger.save(Path::new(path)) {
+                        Err(why) => error!("{}", why),
+                        Ok(_) => info!("Code/Data Logger saved to {} ({} bytes of code, {} bytes of data)", path, code, data)
+                    }
+                }
                 *control_flow = ControlFlow::Exit;
                 return;
             }
@@ -137,6 +521,18 @@ fn main() {
             if let Some(size) = input_helper.window_resized() {
                 main_pixels.lock().unwrap().resize(size.width, size.height);
             }
+            // Pause in the debugger
+            if input_helper.key_pressed(keymap::PAUSE_KEY) {
+                if let Some(pause_request) = o_pause_request.as_ref() {
+                    pause_request.store(true, Ordering::Relaxed);
+                }
+            }
+            // Shows the memory viewer again after it was closed
+            if input_helper.key_pressed(keymap::MEMORY_VIEWER_KEY) {
+                if let Some(memory_window) = o_memory_window.as_ref() {
+                    memory_window.set_visible(true);
+                }
+            }
             // Debug window
             if input_helper.key_pressed(VirtualKeyCode::E) {
                 if !p_gui.lock().unwrap().debug {
@@ -145,31 +541,59 @@ fn main() {
                 }
             }
             // Controller inputs
-            p_bus.lock().unwrap().controllers[0].buffer = 0;
-            if input_helper.key_held(VirtualKeyCode::Z) {
-                p_bus.lock().unwrap().controllers[0].buffer |= ControllerInput::Up as u8;
-            }
-            if input_helper.key_held(VirtualKeyCode::Q) {
-                p_bus.lock().unwrap().controllers[0].buffer |= ControllerInput::Left as u8;
-            }
-            if input_helper.key_held(VirtualKeyCode::S) {
-                p_bus.lock().unwrap().controllers[0].buffer |= ControllerInput::Down as u8;
-            }
-            if input_helper.key_held(VirtualKeyCode::D) {
-                p_bus.lock().unwrap().controllers[0].buffer |= ControllerInput::Right as u8;
-            }
-            if input_helper.key_held(VirtualKeyCode::X) {
-                p_bus.lock().unwrap().controllers[0].buffer |= ControllerInput::Start as u8;
+            let mut input_state: InputState = InputState::new();
+            keymap::read_keyboard(&input_helper, &mut input_state);
+            gamepads.poll();
+            for (player, buttons) in gamepads.read_controllers().iter().enumerate() {
+                input_state.controllers[player] |= buttons;
             }
-            if input_helper.key_held(VirtualKeyCode::C) {
-                p_bus.lock().unwrap().controllers[0].buffer |= ControllerInput::Select as u8;
-            }
-            if input_helper.key_held(VirtualKeyCode::I) {
-                p_bus.lock().unwrap().controllers[0].buffer |= ControllerInput::A as u8;
+            // Turbo buttons and macros of the first controller, applied by the emulation on each frame
+            input_state.turbo_controllers = keymap::read_turbo_keys(&input_helper);
+            for (slot, key) in keymap::MACRO_KEYS.iter().enumerate() {
+                if input_helper.key_pressed(*key) {
+                    match input_helper.held_control() {
+                        false => input_state.macro_play |= 1 << slot,
+                        true => input_state.macro_record |= 1 << slot
+                    }
+                }
             }
-            if input_helper.key_held(VirtualKeyCode::O) {
-                p_bus.lock().unwrap().controllers[0].buffer |= ControllerInput::B as u8;
+            // Mouse inputs
+            input_state.mouse_position = input_helper.mouse().and_then(
+                |position| main_pixels.lock().unwrap().window_pos_to_pixel(position).ok()
+            );
+            input_state.mouse_pressed = input_helper.mouse_held(0);
+            // Reset button, the console is power cycled when control is held
+            if input_helper.key_pressed(keymap::RESET_KEY) {
+                match input_helper.held_control() {
+                    false => input_state.commands = COMMAND_SOFT_RESET,
+                    true => input_state.commands = COMMAND_POWER_CYCLE
+                }
             }
+            // The inputs are sampled by the emulation once per frame
+            // Commands and macro keys are kept until the emulation executes them
+            let mut shared_input_state = p_input_state.lock().unwrap();
+            input_state.commands |= shared_input_state.commands;
+            input_state.macro_play |= shared_input_state.macro_play;
+            input_state.macro_record |= shared_input_state.macro_record;
+            *shared_input_state = input_state;
         }
     });
 }
+
+// Creates the trace logger with the filters and the columns given on the command line
+fn create_trace_logger(matches: &ArgMatches, path: &str, p_cpu: Arc<Mutex<CPU>>, p_bus: Arc<Mutex<Bus>>, p_ppu: Arc<Mutex<PPU>>) -> Result<TraceLogger, String> {
+    let mut trace_logger: TraceLogger = TraceLogger::create(path, p_cpu, p_bus, p_ppu)?;
+    trace_logger.format = TraceFormat::parse(matches.value_of("trace-format").unwrap_or("nintendulator"))?;
+    if let Some(range) = matches.value_of("trace-pc") {
+        trace_logger.o_pc_range = Some(trace_logger::parse_address_range(range)?);
+    }
+    if let Some(range) = matches.value_of("trace-frames") {
+        trace_logger.o_frame_range = Some(trace_logger::parse_frame_range(range)?);
+    }
+    if let Some(trigger) = matches.value_of("trace-trigger") {
+        trace_logger.o_trigger = Some(TraceTrigger::parse(trigger)?);
+    }
+    trace_logger.ppu_registers = matches.is_present("trace-ppu");
+    trace_logger.mapper_banks = matches.is_present("trace-banks");
+    Ok(trace_logger)
+}
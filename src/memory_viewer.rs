@@ -0,0 +1,710 @@
+// Memory viewer and hex editor, displayed in its own window
+// Shows the CPU and PPU address spaces, the OAM, the palette RAM and the cartridge ROM
+// The recent reads and writes are highlighted, and the bytes can be edited while the emulation is paused, searched and frozen
+
+// ===== IMPORTS =====
+
+use std::sync::{Arc, Mutex};
+
+use crate::{bus::Bus, ppu::ppu::PPU};
+
+// ===== CONSTANTS =====
+
+// Size of the pixel buffer of the window
+pub const VIEWER_WIDTH: u32 = 256;
+pub const VIEWER_HEIGHT: u32 = 240;
+
+// Size of a character, the font is 3x5 pixels
+const CHARACTER_WIDTH: usize = 4;
+const CHARACTER_HEIGHT: usize = 6;
+
+const BYTES_PER_ROW: usize = 16;
+// Rows of bytes between the title line and the status line
+pub const VISIBLE_ROWS: usize = 36;
+const FIRST_ROW_LINE: usize = 2;
+const STATUS_LINE: usize = 39;
+
+// Number of frames during which an access stays highlighted
+const HIGHLIGHT_FRAMES: u64 = 60;
+
+// Colors (red, green, blue)
+const BACKGROUND_COLOR: [u8;3] = [0x10, 0x10, 0x18];
+const TEXT_COLOR: [u8;3] = [0xC0, 0xC0, 0xC0];
+const ADDRESS_COLOR: [u8;3] = [0x70, 0x80, 0xB0];
+const TITLE_COLOR: [u8;3] = [0xFF, 0xFF, 0xFF];
+const READ_COLOR: [u8;3] = [0x40, 0xFF, 0x40];
+const WRITE_COLOR: [u8;3] = [0xFF, 0x40, 0x40];
+const FROZEN_COLOR: [u8;3] = [0x40, 0xC0, 0xFF];
+
+const MEMORY_SPACES: [MemorySpace;6] = [MemorySpace::Cpu, MemorySpace::Ppu, MemorySpace::Oam, MemorySpace::Palette, MemorySpace::PrgRom, MemorySpace::ChrRom];
+
+// ===== ENUMS =====
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemorySpace {
+    Cpu,
+    Ppu,
+    Oam,
+    Palette,
+    PrgRom,
+    ChrRom
+}
+
+impl MemorySpace {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MemorySpace::Cpu => "CPU",
+            MemorySpace::Ppu => "PPU",
+            MemorySpace::Oam => "OAM",
+            MemorySpace::Palette => "PALETTE",
+            MemorySpace::PrgRom => "PRG ROM",
+            MemorySpace::ChrRom => "CHR ROM"
+        }
+    }
+}
+
+// Keys of the viewer that don't type a character
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViewerKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    NextSpace,
+    Enter,
+    Escape,
+    Backspace
+}
+
+// ===== ACCESS HISTORY STRUCT =====
+
+// Frame of the last read and write of each address of a memory space
+// Recorded by the buses while the memory viewer is open
+pub struct AccessHistory {
+    // 0 when the address has never been accessed
+    pub last_read: Vec<u64>,
+    pub last_write: Vec<u64>,
+    // Current frame, counted from 1
+    pub frame: u64
+}
+
+impl AccessHistory {
+    pub fn new(size: usize) -> Self {
+        AccessHistory {
+            last_read: vec![0;size],
+            last_write: vec![0;size],
+            frame: 1
+        }
+    }
+
+    pub fn record_read(&mut self, address: usize) {
+        if let Some(frame) = self.last_read.get_mut(address) {
+            *frame = self.frame;
+        }
+    }
+
+    pub fn record_write(&mut self, address: usize) {
+        if let Some(frame) = self.last_write.get_mut(address) {
+            *frame = self.frame;
+        }
+    }
+
+    // Number of frames since the last read and the last write of an address
+    pub fn ages(&self, address: usize) -> (Option<u64>, Option<u64>) {
+        let age = |frame: Option<&u64>| match frame {
+            Some(0) | None => None,
+            Some(frame) => Some(self.frame.saturating_sub(*frame))
+        };
+        (age(self.last_read.get(address)), age(self.last_write.get(address)))
+    }
+}
+
+// ===== MEMORY VIEWER STRUCT =====
+
+// Byte rewritten at each frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrozenByte {
+    pub space: MemorySpace,
+    pub address: usize,
+    pub value: u8
+}
+
+pub struct MemoryViewer {
+    pub p_bus: Arc<Mutex<Bus>>,
+    pub p_ppu: Arc<Mutex<PPU>>,
+    // Content of the cartridge ROM, it can't be edited
+    pub prg_rom: Vec<u8>,
+    pub chr_rom: Vec<u8>,
+
+    pub space: MemorySpace,
+    pub cursor: usize,
+    // First row displayed
+    pub top_row: usize,
+
+    // The emulation waits while the viewer is paused
+    pub paused: bool,
+    pub frozen: Vec<FrozenByte>,
+
+    // First digit of the byte being typed
+    o_edit_digit: Option<u8>,
+    // Pattern being typed after '/'
+    o_search_input: Option<String>,
+    search_pattern: Vec<u8>,
+    // Message displayed on the last line
+    pub status: String
+}
+
+impl MemoryViewer {
+    // The buses record their accesses while the viewer exists
+    pub fn new(p_bus: Arc<Mutex<Bus>>, p_ppu: Arc<Mutex<PPU>>, prg_rom: Vec<u8>, chr_rom: Vec<u8>) -> Self {
+        p_bus.lock().unwrap().o_access_history = Some(AccessHistory::new(0x10000));
+        p_ppu.lock().unwrap().ppu_bus.o_access_history = Some(AccessHistory::new(0x4000));
+        MemoryViewer {
+            p_bus,
+            p_ppu,
+            prg_rom,
+            chr_rom,
+
+            space: MemorySpace::Cpu,
+            cursor: 0,
+            top_row: 0,
+
+            paused: false,
+            frozen: Vec::new(),
+
+            o_edit_digit: None,
+            o_search_input: None,
+            search_pattern: Vec::new(),
+            status: String::new()
+        }
+    }
+
+    // ===== MEMORY =====
+
+    pub fn size(&self, space: MemorySpace) -> usize {
+        match space {
+            MemorySpace::Cpu => 0x10000,
+            MemorySpace::Ppu => 0x4000,
+            MemorySpace::Oam => 0x100,
+            MemorySpace::Palette => 0x20,
+            MemorySpace::PrgRom => self.prg_rom.len(),
+            MemorySpace::ChrRom => self.chr_rom.len()
+        }
+    }
+
+    // Reads bytes without side effects, the PPU memory reads nothing until a cartridge is inserted
+    pub fn read_range(&self, space: MemorySpace, start: usize, length: usize) -> Vec<u8> {
+        let end: usize = (start + length).min(self.size(space));
+        let addresses = start..end.max(start);
+        match space {
+            MemorySpace::Cpu => {
+                let bus = self.p_bus.lock().unwrap();
+                addresses.map(|address| bus.read_only(address as u16)).collect()
+            },
+            MemorySpace::Ppu => {
+                let ppu = self.p_ppu.lock().unwrap();
+                match ppu.ppu_bus.o_p_mapper.is_some() {
+                    true => addresses.map(|address| ppu.ppu_bus.read(address as u16)).collect(),
+                    false => addresses.map(|_| 0).collect()
+                }
+            },
+            MemorySpace::Oam => {
+                let ppu = self.p_ppu.lock().unwrap();
+                addresses.map(|address| ppu.oam.read_primary(address as u8)).collect()
+            },
+            MemorySpace::Palette => {
+                let ppu = self.p_ppu.lock().unwrap();
+                addresses.map(|address| ppu.ppu_bus.read_palette_table(address as u16)).collect()
+            },
+            MemorySpace::PrgRom => self.prg_rom[addresses].to_vec(),
+            MemorySpace::ChrRom => self.chr_rom[addresses].to_vec()
+        }
+    }
+
+    pub fn read(&self, space: MemorySpace, address: usize) -> u8 {
+        self.read_range(space, address, 1).first().copied().unwrap_or(0)
+    }
+
+    // Returns an error for the bytes that can't be edited or frozen
+    // In the CPU address space, only the RAM is edited : writes to the registers would have side effects
+    // In the PPU address space, the pattern tables are only edited when the cartridge has CHR RAM
+    pub fn check_editable(&self, space: MemorySpace, address: usize) -> Result<(), String> {
+        match space {
+            MemorySpace::Cpu if !Bus::is_ram(address as u16) => Err(String::from("ONLY THE RAM CAN BE EDITED")),
+            MemorySpace::Ppu if address < 0x2000 && !self.chr_rom.is_empty() => Err(String::from("THE ROM CAN'T BE EDITED")),
+            MemorySpace::PrgRom | MemorySpace::ChrRom => Err(String::from("THE ROM CAN'T BE EDITED")),
+            _ => Ok(())
+        }
+    }
+
+    // The CPU writes go through the bus, like the writes of the game
+    pub fn write(&self, space: MemorySpace, address: usize, value: u8) -> Result<(), String> {
+        self.check_editable(space, address)?;
+        match space {
+            MemorySpace::Cpu => self.p_bus.lock().unwrap().write(address as u16, value),
+            MemorySpace::Ppu => {
+                let mut ppu = self.p_ppu.lock().unwrap();
+                if ppu.ppu_bus.o_p_mapper.is_none() {
+                    return Err(String::from("NO CARTRIDGE"));
+                }
+                ppu.ppu_bus.write(address as u16, value);
+            },
+            MemorySpace::Oam => self.p_ppu.lock().unwrap().oam.write_primary(address as u8, value),
+            MemorySpace::Palette => self.p_ppu.lock().unwrap().ppu_bus.write_palette_table(address as u16, value),
+            MemorySpace::PrgRom | MemorySpace::ChrRom => ()
+        }
+        Ok(())
+    }
+
+    // Number of frames since the last read and write of an address, the palette is in the PPU address space
+    fn ages(&self, space: MemorySpace, address: usize) -> (Option<u64>, Option<u64>) {
+        match space {
+            MemorySpace::Cpu => self.p_bus.lock().unwrap().o_access_history.as_ref().map_or((None, None), |history| history.ages(address)),
+            MemorySpace::Ppu => self.p_ppu.lock().unwrap().ppu_bus.o_access_history.as_ref().map_or((None, None), |history| history.ages(address)),
+            MemorySpace::Palette => self.p_ppu.lock().unwrap().ppu_bus.o_access_history.as_ref().map_or((None, None), |history| history.ages(0x3F00 + address)),
+            _ => (None, None)
+        }
+    }
+
+    // ===== EMULATION =====
+
+    // Called by the emulation at the beginning of each v blank period
+    pub fn new_frame(&mut self, frame: u64) {
+        if let Some(history) = self.p_bus.lock().unwrap().o_access_history.as_mut() {
+            history.frame = frame + 1;
+        }
+        if let Some(history) = self.p_ppu.lock().unwrap().ppu_bus.o_access_history.as_mut() {
+            history.frame = frame + 1;
+        }
+        for frozen_byte in self.frozen.iter() {
+            let _ = self.write(frozen_byte.space, frozen_byte.address, frozen_byte.value);
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.o_edit_digit = None;
+    }
+
+    // Freezes the byte under the cursor to its current value, or unfreezes it
+    pub fn toggle_freeze(&mut self) {
+        let (space, address): (MemorySpace, usize) = (self.space, self.cursor);
+        match self.frozen.iter().position(|frozen_byte| frozen_byte.space == space && frozen_byte.address == address) {
+            Some(index) => {
+                self.frozen.remove(index);
+                self.status = format!("${:04X} UNFROZEN", address);
+            },
+            None => match self.check_editable(space, address) {
+                Err(why) => self.status = why,
+                Ok(_) => {
+                    let value: u8 = self.read(space, address);
+                    self.frozen.push(FrozenByte { space, address, value });
+                    self.status = format!("${:04X} FROZEN TO {:02X}", address, value);
+                }
+            }
+        }
+    }
+
+    // ===== NAVIGATION =====
+
+    pub fn set_space(&mut self, space: MemorySpace) {
+        self.space = space;
+        self.o_edit_digit = None;
+        self.move_to(0);
+    }
+
+    // Moves the cursor and scrolls so that it stays visible
+    pub fn move_to(&mut self, address: usize) {
+        self.cursor = address.min(self.size(self.space).saturating_sub(1));
+        let row: usize = self.cursor / BYTES_PER_ROW;
+        if row < self.top_row {
+            self.top_row = row;
+        }
+        else if row >= self.top_row + VISIBLE_ROWS {
+            self.top_row = row + 1 - VISIBLE_ROWS;
+        }
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        self.o_edit_digit = None;
+        let address: usize = (self.cursor as isize + delta).max(0) as usize;
+        self.move_to(address);
+    }
+
+    // ===== SEARCH =====
+
+    // Address of the next occurrence of the pattern after the cursor, the search goes on from the start
+    pub fn search(&self, pattern: &[u8]) -> Option<usize> {
+        let size: usize = self.size(self.space);
+        if pattern.is_empty() || pattern.len() > size {
+            return None;
+        }
+        let memory: Vec<u8> = self.read_range(self.space, 0, size);
+        (1..=size)
+            .map(|offset| (self.cursor + offset) % size)
+            .find(|address| *address + pattern.len() <= size && memory[*address..*address + pattern.len()] == *pattern)
+    }
+
+    fn find_next(&mut self) {
+        match self.search(&self.search_pattern.clone()) {
+            Some(address) => {
+                self.move_to(address);
+                self.status = format!("FOUND AT ${:04X}", address);
+            },
+            None => self.status = String::from("NOT FOUND")
+        }
+    }
+
+    // ===== INPUTS =====
+
+    // Typed characters : hexadecimal digits edit the byte under the cursor or the search pattern
+    pub fn handle_character(&mut self, character: char) {
+        let o_digit: Option<u8> = character.to_digit(16).map(|digit| digit as u8);
+        if let Some(search_input) = self.o_search_input.as_mut() {
+            if o_digit.is_some() || character == ' ' {
+                search_input.push(character.to_ascii_uppercase());
+            }
+            return;
+        }
+        match (character, o_digit) {
+            ('/', _) => {
+                self.o_search_input = Some(String::new());
+                self.o_edit_digit = None;
+            },
+            ('n', _) | ('N', _) => self.find_next(),
+            ('l', _) | ('L', _) => self.toggle_freeze(),
+            (' ', _) => self.toggle_pause(),
+            (_, Some(_)) if !self.paused => self.status = String::from("PAUSE TO EDIT"),
+            (_, Some(digit)) => match self.o_edit_digit.take() {
+                None => self.o_edit_digit = Some(digit),
+                Some(first_digit) => {
+                    let value: u8 = (first_digit << 4) | digit;
+                    match self.write(self.space, self.cursor, value) {
+                        Err(why) => self.status = why,
+                        Ok(_) => {
+                            // A frozen byte keeps the new value
+                            let (space, address): (MemorySpace, usize) = (self.space, self.cursor);
+                            if let Some(frozen_byte) = self.frozen.iter_mut().find(|frozen_byte| frozen_byte.space == space && frozen_byte.address == address) {
+                                frozen_byte.value = value;
+                            }
+                            self.move_cursor(1);
+                        }
+                    }
+                }
+            },
+            _ => ()
+        }
+    }
+
+    pub fn handle_key(&mut self, key: ViewerKey) {
+        let page: isize = (VISIBLE_ROWS*BYTES_PER_ROW) as isize;
+        match (key, self.o_search_input.as_mut()) {
+            (ViewerKey::Enter, Some(search_input)) => {
+                let o_pattern: Option<Vec<u8>> = search_input.split_whitespace()
+                    .flat_map(|word| word.as_bytes().chunks(2).map(|digits| std::str::from_utf8(digits).unwrap_or("").to_string()).collect::<Vec<String>>())
+                    .map(|digits| u8::from_str_radix(&digits, 16).ok())
+                    .collect();
+                self.o_search_input = None;
+                match o_pattern {
+                    Some(pattern) if !pattern.is_empty() => {
+                        self.search_pattern = pattern;
+                        self.find_next();
+                    },
+                    _ => self.status = String::from("INVALID PATTERN")
+                }
+            },
+            (ViewerKey::Escape, Some(_)) => self.o_search_input = None,
+            (ViewerKey::Backspace, Some(search_input)) => {
+                search_input.pop();
+            },
+            (_, Some(_)) => (),
+            (ViewerKey::Up, None) => self.move_cursor(-(BYTES_PER_ROW as isize)),
+            (ViewerKey::Down, None) => self.move_cursor(BYTES_PER_ROW as isize),
+            (ViewerKey::Left, None) => self.move_cursor(-1),
+            (ViewerKey::Right, None) => self.move_cursor(1),
+            (ViewerKey::PageUp, None) => self.move_cursor(-page),
+            (ViewerKey::PageDown, None) => self.move_cursor(page),
+            (ViewerKey::Home, None) => self.move_to(0),
+            (ViewerKey::End, None) => self.move_to(self.size(self.space)),
+            (ViewerKey::NextSpace, None) => {
+                let index: usize = MEMORY_SPACES.iter().position(|space| *space == self.space).unwrap_or(0);
+                self.set_space(MEMORY_SPACES[(index + 1) % MEMORY_SPACES.len()]);
+            },
+            (ViewerKey::Escape, None) | (ViewerKey::Backspace, None) => {
+                self.o_edit_digit = None;
+                self.status.clear();
+            },
+            (ViewerKey::Enter, None) => ()
+        }
+    }
+
+    // ===== DISPLAY =====
+
+    // Draws the viewer in a RGBA buffer of VIEWER_WIDTH x VIEWER_HEIGHT pixels
+    pub fn render(&self, frame: &mut [u8]) {
+        for pixel in frame.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[BACKGROUND_COLOR[0], BACKGROUND_COLOR[1], BACKGROUND_COLOR[2], 0xFF]);
+        }
+        let size: usize = self.size(self.space);
+        // 4 hexadecimal digits for the addresses, 5 for the big ROMs
+        let address_digits: usize = if size > 0x10000 { 5 } else { 4 };
+
+        let state: &str = if self.paused { "PAUSED" } else { "RUNNING" };
+        let title: String = format!("{} ${:0width$X} = {:02X}", self.space.name(), self.cursor, self.read(self.space, self.cursor), width = address_digits);
+        draw_text(frame, 0, 0, &title, TITLE_COLOR, None);
+        draw_text(frame, 64 - state.len(), 0, state, if self.paused { WRITE_COLOR } else { READ_COLOR }, None);
+
+        let start: usize = self.top_row*BYTES_PER_ROW;
+        let memory: Vec<u8> = self.read_range(self.space, start, VISIBLE_ROWS*BYTES_PER_ROW);
+        for (index, value) in memory.iter().enumerate() {
+            let address: usize = start + index;
+            let line: usize = FIRST_ROW_LINE + index / BYTES_PER_ROW;
+            let column: usize = address_digits + 1 + (index % BYTES_PER_ROW)*3;
+            if index % BYTES_PER_ROW == 0 {
+                draw_text(frame, 0, line, &format!("{:0width$X}", address, width = address_digits), ADDRESS_COLOR, None);
+            }
+            let frozen: bool = self.frozen.iter().any(|frozen_byte| frozen_byte.space == self.space && frozen_byte.address == address);
+            let color: [u8;3] = match (frozen, self.ages(self.space, address)) {
+                (true, _) => FROZEN_COLOR,
+                (false, (_, Some(age))) if age < HIGHLIGHT_FRAMES => fade(WRITE_COLOR, age),
+                (false, (Some(age), _)) if age < HIGHLIGHT_FRAMES => fade(READ_COLOR, age),
+                _ => TEXT_COLOR
+            };
+            let text: String = match (address == self.cursor, self.o_edit_digit) {
+                (true, Some(digit)) => format!("{:X}_", digit),
+                _ => format!("{:02X}", value)
+            };
+            match address == self.cursor {
+                true => draw_text(frame, column, line, &text, BACKGROUND_COLOR, Some(color)),
+                false => draw_text(frame, column, line, &text, color, None)
+            }
+        }
+
+        let status: String = match self.o_search_input.as_ref() {
+            Some(search_input) => format!("/{}_", search_input),
+            None if !self.status.is_empty() => self.status.clone(),
+            None => String::from("TAB SPACE:PAUSE L:FREEZE /:FIND N:NEXT")
+        };
+        draw_text(frame, 0, STATUS_LINE, &status, TEXT_COLOR, None);
+    }
+}
+
+// ===== HELPER FUNCTIONS =====
+
+// Darkens the highlight of the old accesses
+fn fade(color: [u8;3], age: u64) -> [u8;3] {
+    let mut faded: [u8;3] = [0;3];
+    for (channel, value) in faded.iter_mut().enumerate() {
+        let highlight: u64 = color[channel] as u64 * (2*HIGHLIGHT_FRAMES - age) / (2*HIGHLIGHT_FRAMES);
+        let text: u64 = TEXT_COLOR[channel] as u64 * age / (2*HIGHLIGHT_FRAMES);
+        *value = (highlight + text).min(0xFF) as u8;
+    }
+    faded
+}
+
+// Draws text at a position given in characters, on a background color if one is given
+fn draw_text(frame: &mut [u8], column: usize, line: usize, text: &str, color: [u8;3], o_background: Option<[u8;3]>) {
+    for (index, character) in text.chars().enumerate() {
+        let glyph: [u8;5] = glyph(character);
+        for y in 0..CHARACTER_HEIGHT {
+            for x in 0..CHARACTER_WIDTH {
+                let pixel_x: usize = (column + index)*CHARACTER_WIDTH + x;
+                let pixel_y: usize = line*CHARACTER_HEIGHT + y;
+                if pixel_x >= VIEWER_WIDTH as usize || pixel_y >= VIEWER_HEIGHT as usize {
+                    continue;
+                }
+                let row: u8 = glyph.get(y).copied().unwrap_or(0);
+                let lit: bool = x < 3 && (row >> (2 - x)) & 1 == 1;
+                let o_pixel_color: Option<[u8;3]> = match lit {
+                    true => Some(color),
+                    false => o_background
+                };
+                if let Some(pixel_color) = o_pixel_color {
+                    let index: usize = (pixel_y*VIEWER_WIDTH as usize + pixel_x)*4;
+                    frame[index..index + 3].copy_from_slice(&pixel_color);
+                }
+            }
+        }
+    }
+}
+
+// 3x5 font, each row is 3 bits with the left pixel as the highest bit
+fn glyph(character: char) -> [u8;5] {
+    match character.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '$' => [0b011, 0b110, 0b010, 0b011, 0b110],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '\'' => [0b010, 0b010, 0b000, 0b000, 0b000],
+        _ => [0;5]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debugger::{Debugger, tests::create_debugger};
+
+    fn create_memory_viewer() -> MemoryViewer {
+        let debugger: Debugger = create_debugger(&[]);
+        MemoryViewer::new(debugger.p_bus.clone(), debugger.p_ppu.clone(), vec![0x11;0x4000], vec![0x22;0x2000])
+    }
+
+    #[test]
+    fn edit_and_freeze() {
+        let mut memory_viewer: MemoryViewer = create_memory_viewer();
+        memory_viewer.move_to(0x0010);
+        // The bytes are only edited while paused
+        memory_viewer.handle_character('4');
+        memory_viewer.handle_character('2');
+        assert_eq!(memory_viewer.read(MemorySpace::Cpu, 0x0010), 0x00);
+        memory_viewer.handle_character(' ');
+        memory_viewer.handle_character('4');
+        memory_viewer.handle_character('2');
+        assert_eq!(memory_viewer.read(MemorySpace::Cpu, 0x0010), 0x42);
+        assert_eq!(memory_viewer.cursor, 0x0011);
+
+        memory_viewer.move_to(0x0010);
+        memory_viewer.handle_character('l');
+        memory_viewer.p_bus.lock().unwrap().write(0x0010, 0x00);
+        memory_viewer.new_frame(1);
+        assert_eq!(memory_viewer.read(MemorySpace::Cpu, 0x0010), 0x42);
+
+        // The other spaces
+        memory_viewer.set_space(MemorySpace::Palette);
+        memory_viewer.move_to(0x10);
+        memory_viewer.handle_character('2');
+        memory_viewer.handle_character('a');
+        assert_eq!(memory_viewer.read(MemorySpace::Palette, 0x00), 0x2A);
+        assert_eq!(memory_viewer.read(MemorySpace::Ppu, 0x3F00), 0x2A);
+        memory_viewer.set_space(MemorySpace::PrgRom);
+        memory_viewer.handle_character('0');
+        memory_viewer.handle_character('0');
+        assert_eq!(memory_viewer.status, "THE ROM CAN'T BE EDITED");
+        assert_eq!(memory_viewer.read(MemorySpace::PrgRom, 0), 0x11);
+
+        // The registers and the ROM seen by the CPU can't be edited nor frozen
+        memory_viewer.set_space(MemorySpace::Cpu);
+        memory_viewer.move_to(0x2000);
+        memory_viewer.handle_character('8');
+        memory_viewer.handle_character('0');
+        assert_eq!(memory_viewer.status, "ONLY THE RAM CAN BE EDITED");
+        assert_eq!(memory_viewer.p_ppu.lock().unwrap().registers.ctrl, 0x00);
+        memory_viewer.move_to(0x8000);
+        memory_viewer.handle_character('l');
+        assert_eq!(memory_viewer.status, "ONLY THE RAM CAN BE EDITED");
+        assert_eq!(memory_viewer.frozen.len(), 1);
+    }
+
+    #[test]
+    fn pattern_tables() {
+        // With CHR ROM, only the nametables and the palettes of the PPU space are edited
+        let mut memory_viewer: MemoryViewer = create_memory_viewer();
+        let chr_value: u8 = memory_viewer.read(MemorySpace::Ppu, 0x0100);
+        assert_eq!(memory_viewer.write(MemorySpace::Ppu, 0x0100, 0x42), Err(String::from("THE ROM CAN'T BE EDITED")));
+        assert_eq!(memory_viewer.read(MemorySpace::Ppu, 0x0100), chr_value);
+        assert_eq!(memory_viewer.check_editable(MemorySpace::Ppu, 0x1FFF), Err(String::from("THE ROM CAN'T BE EDITED")));
+        assert_eq!(memory_viewer.write(MemorySpace::Ppu, 0x2000, 0x42), Ok(()));
+        assert_eq!(memory_viewer.read(MemorySpace::Ppu, 0x2000), 0x42);
+
+        // With CHR RAM, they are edited too
+        memory_viewer.chr_rom.clear();
+        assert_eq!(memory_viewer.check_editable(MemorySpace::Ppu, 0x0100), Ok(()));
+    }
+
+    #[test]
+    fn search() {
+        let mut memory_viewer: MemoryViewer = create_memory_viewer();
+        memory_viewer.p_bus.lock().unwrap().data[0x0300..0x0303].copy_from_slice(&[0xA9, 0x00, 0x8D]);
+        memory_viewer.handle_character('/');
+        for character in "a9 008d".chars() {
+            memory_viewer.handle_character(character);
+        }
+        memory_viewer.handle_key(ViewerKey::Enter);
+        assert_eq!(memory_viewer.cursor, 0x0300);
+        // The RAM is mirrored up to $1FFF
+        memory_viewer.handle_character('n');
+        assert_eq!(memory_viewer.cursor, 0x0B00);
+        assert!(memory_viewer.top_row*BYTES_PER_ROW <= 0x0B00 && 0x0B00 < (memory_viewer.top_row + VISIBLE_ROWS)*BYTES_PER_ROW);
+        memory_viewer.handle_character('/');
+        memory_viewer.handle_character('f');
+        memory_viewer.handle_character('7');
+        memory_viewer.handle_key(ViewerKey::Enter);
+        assert_eq!(memory_viewer.status, "NOT FOUND");
+    }
+
+    #[test]
+    fn access_highlights() {
+        let mut memory_viewer: MemoryViewer = create_memory_viewer();
+        memory_viewer.new_frame(10);
+        {
+            let mut bus = memory_viewer.p_bus.lock().unwrap();
+            let history: &mut AccessHistory = bus.o_access_history.as_mut().unwrap();
+            history.record_write(0x0001);
+            history.record_read(0x0002);
+        }
+        memory_viewer.new_frame(12);
+        assert_eq!(memory_viewer.ages(MemorySpace::Cpu, 0x0001), (None, Some(2)));
+        assert_eq!(memory_viewer.ages(MemorySpace::Cpu, 0x0002), (Some(2), None));
+
+        // The byte written is drawn in red, the cursor on the first byte has a background
+        let mut frame: Vec<u8> = vec![0;(VIEWER_WIDTH*VIEWER_HEIGHT*4) as usize];
+        memory_viewer.render(&mut frame);
+        let line_pixels = |column: usize| -> Vec<[u8;3]> {
+            (0..CHARACTER_WIDTH*2).flat_map(|x| (0..CHARACTER_HEIGHT).map(move |y| (x, y)))
+                .map(|(x, y)| {
+                    let index: usize = ((FIRST_ROW_LINE*CHARACTER_HEIGHT + y)*VIEWER_WIDTH as usize + column*CHARACTER_WIDTH + x)*4;
+                    [frame[index], frame[index + 1], frame[index + 2]]
+                })
+                .collect()
+        };
+        assert_eq!(line_pixels(5)[3*CHARACTER_HEIGHT], TEXT_COLOR);
+        assert_eq!(line_pixels(14)[3*CHARACTER_HEIGHT], BACKGROUND_COLOR);
+        assert!(line_pixels(8).contains(&fade(WRITE_COLOR, 2)));
+        assert!(line_pixels(11).contains(&fade(READ_COLOR, 2)));
+        assert!(line_pixels(14).contains(&TEXT_COLOR));
+    }
+}
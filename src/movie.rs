@@ -0,0 +1,256 @@
+// Records and plays back the inputs of the controllers, frame by frame
+// Movies are stored in the FM2 format used by FCEUX
+// The inputs of each frame are sampled at the beginning of the v blank period
+// Only standard controllers are supported (with or without a Four Score)
+
+// ===== IMPORTS =====
+
+use std::{fs, path::Path};
+
+use log::info;
+
+use crate::input::device::InputState;
+
+// ===== CONSTANTS =====
+
+// Order of the buttons in an FM2 input log, from bit 0 to bit 7
+const FM2_BUTTONS: [char;8] = ['R', 'L', 'D', 'U', 'T', 'S', 'B', 'A'];
+
+// Input type of a standard controller in an FM2 header
+const FM2_GAMEPAD: u8 = 1;
+
+// ===== ENUMS =====
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovieMode {
+    Recording,
+    Playing,
+    Finished
+}
+
+// ===== STRUCTS =====
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovieFrame {
+    // Commands of the frame (bit 0 : soft reset, bit 1 : power cycle)
+    pub commands: u8,
+    // Buttons held on each controller
+    pub controllers: [u8;4]
+}
+
+pub struct Movie {
+    pub mode: MovieMode,
+    pub frames: Vec<MovieFrame>,
+    // Index of the next frame to record or play
+    pub current_frame: usize,
+    pub four_score: bool,
+    pub rom_filename: String
+}
+
+impl Movie {
+    // Creates an empty movie, recorded from power on
+    pub fn new(rom_filename: &str, four_score: bool) -> Self {
+        Movie {
+            mode: MovieMode::Recording,
+            frames: vec![],
+            current_frame: 0,
+            four_score,
+            rom_filename: String::from(rom_filename)
+        }
+    }
+
+    // ===== FRAME PROCESSING =====
+
+    // Called once per frame when the inputs are sampled
+    // Records the inputs, or replaces them with the ones of the movie
+    pub fn process_frame(&mut self, input_state: &mut InputState) {
+        match self.mode {
+            MovieMode::Recording => {
+                self.frames.push(MovieFrame {
+                    commands: input_state.commands,
+                    controllers: input_state.controllers
+                });
+                self.current_frame += 1;
+            }
+            MovieMode::Playing => {
+                if self.current_frame < self.frames.len() {
+                    input_state.commands = self.frames[self.current_frame].commands;
+                    input_state.controllers = self.frames[self.current_frame].controllers;
+                    self.current_frame += 1;
+                }
+                else {
+                    info!("Movie playback finished after {} frames", self.frames.len());
+                    self.mode = MovieMode::Finished;
+                }
+            }
+            MovieMode::Finished => ()
+        }
+    }
+
+    // ===== FM2 FORMAT =====
+
+    // Loads a movie from an FM2 file, ready to be played
+    pub fn load_fm2(path: &Path) -> Result<Self, String> {
+        let content: String = match fs::read_to_string(path) {
+            Err(why) => return Err(format!("Couldn't read {}: {}", path.display(), why)),
+            Ok(content) => content
+        };
+        Movie::from_fm2(&content)
+    }
+
+    // Parses the content of an FM2 file
+    pub fn from_fm2(content: &str) -> Result<Self, String> {
+        let mut movie: Movie = Movie::new("", false);
+        movie.mode = MovieMode::Playing;
+        let mut ports: [u8;2] = [FM2_GAMEPAD, FM2_GAMEPAD];
+
+        for (n_line, line) in content.lines().enumerate() {
+            let line: &str = line.trim_end();
+            if line.starts_with('|') {
+                movie.frames.push(Movie::parse_fm2_frame(line, movie.four_score, ports)
+                    .map_err(|why| format!("Line {}: {}", n_line + 1, why))?);
+            }
+            else if let Some((key, value)) = line.split_once(' ') {
+                match key {
+                    "version" if value != "3" => return Err(format!("Unsupported FM2 version : {}", value)),
+                    "binary" if value != "0" => return Err(String::from("Binary FM2 input logs are not supported")),
+                    "savestate" => return Err(String::from("Movies starting from a save state are not supported")),
+                    "romFilename" => movie.rom_filename = String::from(value),
+                    "fourscore" => movie.four_score = value == "1",
+                    "port0" | "port1" => {
+                        let port: usize = (key == "port1") as usize;
+                        ports[port] = value.parse().map_err(|_| format!("Invalid port type : {}", value))?;
+                        if ports[port] > FM2_GAMEPAD {
+                            return Err(format!("Unsupported input device on {} : {}", key, value));
+                        }
+                    }
+                    _ => ()
+                }
+            }
+        }
+        Ok(movie)
+    }
+
+    // Parses one frame of an FM2 input log, ex: |0|RLDUTSBA|........||
+    fn parse_fm2_frame(line: &str, four_score: bool, ports: [u8;2]) -> Result<MovieFrame, String> {
+        let fields: Vec<&str> = line.split('|').collect();
+        let n_controllers: usize = if four_score { 4 } else { 2 };
+        if fields.len() < n_controllers + 2 {
+            return Err(format!("Invalid input log : {}", line));
+        }
+        let mut frame: MovieFrame = MovieFrame {
+            commands: fields[1].trim().parse().map_err(|_| format!("Invalid commands : {}", fields[1]))?,
+            controllers: [0;4]
+        };
+        for i in 0..n_controllers {
+            if !four_score && ports[i] != FM2_GAMEPAD {
+                continue;
+            }
+            let buttons: Vec<char> = fields[i + 2].chars().collect();
+            if buttons.len() != FM2_BUTTONS.len() {
+                return Err(format!("Invalid controller input : {}", fields[i + 2]));
+            }
+            for (bit, button) in buttons.iter().enumerate() {
+                if *button != '.' && *button != ' ' {
+                    frame.controllers[i] |= 1 << bit;
+                }
+            }
+        }
+        Ok(frame)
+    }
+
+    // Saves the movie to an FM2 file
+    pub fn save_fm2(&self, path: &Path) -> Result<(), String> {
+        match fs::write(path, self.to_fm2()) {
+            Err(why) => Err(format!("Couldn't write {}: {}", path.display(), why)),
+            Ok(_) => Ok(())
+        }
+    }
+
+    // Converts the movie to the content of an FM2 file
+    pub fn to_fm2(&self) -> String {
+        let mut content: String = String::new();
+        content.push_str("version 3\n");
+        content.push_str("emuVersion 0\n");
+        content.push_str("rerecordCount 0\n");
+        content.push_str("palFlag 0\n");
+        content.push_str(&format!("romFilename {}\n", self.rom_filename));
+        content.push_str(&format!("fourscore {}\n", self.four_score as u8));
+        content.push_str("microphone 0\n");
+        if self.four_score {
+            content.push_str("port0 0\nport1 0\n");
+        }
+        else {
+            content.push_str(&format!("port0 {}\nport1 {}\n", FM2_GAMEPAD, FM2_GAMEPAD));
+        }
+        content.push_str("port2 0\n");
+        content.push_str("FDS 0\n");
+        content.push_str("NewPPU 0\n");
+
+        let n_controllers: usize = if self.four_score { 4 } else { 2 };
+        for frame in self.frames.iter() {
+            content.push_str(&format!("|{}|", frame.commands));
+            for controller in frame.controllers.iter().take(n_controllers) {
+                for (bit, button) in FM2_BUTTONS.iter().enumerate() {
+                    content.push(if controller & (1 << bit) > 0 { *button } else { '.' });
+                }
+                content.push('|');
+            }
+            content.push_str("|\n");
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::controller::ControllerInput;
+
+    const FM2_HEADER: &str = "version 3\nemuVersion 22020\nrerecordCount 4\npalFlag 0\nromFilename smb\nguid 452DE2C3-EF43-2FA9-77AC-0677FC51543B\nfourscore 0\nmicrophone 0\nport0 1\nport1 1\nport2 0\nFDS 0\nNewPPU 0\n";
+
+    #[test]
+    fn parse_fm2() {
+        let content: String = format!("{}|0|R..U...A|........||\n|1|.L..TS..|..D...B.||\n", FM2_HEADER);
+        let movie: Movie = Movie::from_fm2(&content).unwrap();
+        assert_eq!(movie.mode, MovieMode::Playing);
+        assert_eq!(movie.rom_filename, "smb");
+        assert!(!movie.four_score);
+        assert_eq!(movie.frames, vec![
+            MovieFrame {
+                commands: 0,
+                controllers: [ControllerInput::Right as u8 | ControllerInput::Up as u8 | ControllerInput::A as u8, 0, 0, 0]
+            },
+            MovieFrame {
+                commands: 1,
+                controllers: [ControllerInput::Left as u8 | ControllerInput::Start as u8 | ControllerInput::Select as u8, ControllerInput::Down as u8 | ControllerInput::B as u8, 0, 0]
+            }
+        ]);
+        assert!(Movie::from_fm2("version 2\n").is_err());
+        assert!(Movie::from_fm2(&format!("{}|0|R..U|........||\n", FM2_HEADER)).is_err());
+        assert_eq!(Movie::from_fm2(&format!("{}binary 1\n", FM2_HEADER)).err(), Some(String::from("Binary FM2 input logs are not supported")));
+        assert_eq!(Movie::from_fm2(&format!("{}savestate 0x1234\n", FM2_HEADER)).err(), Some(String::from("Movies starting from a save state are not supported")));
+    }
+
+    #[test]
+    fn round_trip() {
+        let content: String = format!("{}|0|R..U...A|........||\n|2|........|.LD...BA||\n", FM2_HEADER);
+        let movie: Movie = Movie::from_fm2(&content).unwrap();
+        let saved: String = movie.to_fm2();
+        assert!(saved.contains("|0|R..U...A|........||\n|2|........|.LD...BA||\n"));
+        assert_eq!(Movie::from_fm2(&saved).unwrap().frames, movie.frames);
+    }
+
+    #[test]
+    fn four_score() {
+        let mut movie: Movie = Movie::new("smb", true);
+        movie.frames.push(MovieFrame { commands: 0, controllers: [0x01, 0x02, 0x40, 0x80] });
+        let saved: String = movie.to_fm2();
+        assert!(saved.contains("fourscore 1\n"));
+        assert!(saved.contains("port0 0\nport1 0\n"));
+        assert!(saved.ends_with("|0|R.......|.L......|......B.|.......A||\n"));
+        let loaded: Movie = Movie::from_fm2(&saved).unwrap();
+        assert!(loaded.four_score);
+        assert_eq!(loaded.frames, movie.frames);
+    }
+}
@@ -2,12 +2,13 @@
 
 // ===== IMPORTS =====
 
-use std::sync::{Arc, Mutex};
+use std::{sync::{Arc, Mutex}, thread, time::Duration};
 
 use cartridge::cartridge::Cartridge;
 
-use crate::{cartridge, cpu::{cpu::CPU, enums::Interrupt}, gui::GUI};
-use crate::bus::Bus;
+use crate::{cartridge::{self, mapper::Mapper}, code_data_logger::CodeDataLogger, cpu::cpu::CPU, debugger::Debugger, gdb_stub::GdbStub, gui::GUI, memory_viewer::MemoryViewer, movie::Movie, trace_logger::TraceLogger};
+use crate::bus::{Bus, RamPattern};
+use crate::input::{device::{COMMAND_POWER_CYCLE, COMMAND_SOFT_RESET, InputState, create_port_device}, macros::{MACRO_SLOTS, Macros}, turbo::Turbo};
 use crate::ppu::ppu::PPU;
 
 // ===== NES STRUCT =====
@@ -17,36 +18,60 @@ pub struct NES {
     pub p_bus: Arc<Mutex<Bus>>,
     pub p_cpu: Arc<Mutex<CPU>>,
     pub p_ppu: Arc<Mutex<PPU>>,
-    pub p_gui: Arc<Mutex<GUI>>,
+    // None when running without a window
+    pub o_p_gui: Option<Arc<Mutex<GUI>>>,
 
-    // NES clock counter
-    pub total_clock: u64,
+    // Inputs given by the frontend, sampled once per frame
+    pub p_input_state: Arc<Mutex<InputState>>,
+    // Movie being recorded or played
+    pub o_p_movie: Option<Arc<Mutex<Movie>>>,
+    // Turbo buttons and macros, applied to the inputs of each frame
+    pub turbo: Turbo,
+    pub macros: Macros,
 
-    // DMA variables
-    pub dma_started: bool,
-    pub dma_hi_address: u8,
-    pub dma_base_address: u8,
-    pub dma_address: u8,
-    pub dma_data: u8
+    // Content of the RAM after a power cycle
+    pub ram_pattern: RamPattern,
+    // Mapper of the inserted cartridge in its power on state, copied to the bus and the PPU at each power cycle
+    pub o_p_mapper: Option<Box<dyn Mapper>>,
+
+    // Debugger checked before each instruction
+    pub o_debugger: Option<Debugger>,
+    // Server of the GDB remote protocol, checked before each instruction
+    pub o_gdb_stub: Option<GdbStub>,
+    // Trace of the executed instructions, shared with the frontend to flush it on exit
+    pub o_p_trace_logger: Option<Arc<Mutex<TraceLogger>>>,
+    // Memory viewer of the frontend, it can pause the emulation and freeze bytes
+    pub o_p_memory_viewer: Option<Arc<Mutex<MemoryViewer>>>,
+
+    // Program counter set after a reset instead of the reset vector
+    // Used to run nestest in automation mode (0xC000)
+    pub o_start_address: Option<u16>
 }
 
 unsafe impl Send for NES {}
 
 impl NES {
-    pub fn new(p_bus: Arc<Mutex<Bus>>, p_cpu: Arc<Mutex<CPU>>, p_ppu: Arc<Mutex<PPU>>, p_gui: Arc<Mutex<GUI>>) -> Self {
+    pub fn new(p_bus: Arc<Mutex<Bus>>, p_cpu: Arc<Mutex<CPU>>, p_ppu: Arc<Mutex<PPU>>, o_p_gui: Option<Arc<Mutex<GUI>>>) -> Self {
         NES {
             p_bus,
             p_cpu,
             p_ppu,
-            p_gui,
+            o_p_gui,
+
+            p_input_state: Arc::new(Mutex::new(InputState::new())),
+            o_p_movie: None,
+            turbo: Turbo::new(2),
+            macros: Macros::new(MACRO_SLOTS),
+
+            ram_pattern: RamPattern::Zeros,
+            o_p_mapper: None,
 
-            total_clock: 0,
+            o_debugger: None,
+            o_gdb_stub: None,
+            o_p_trace_logger: None,
+            o_p_memory_viewer: None,
 
-            dma_started: false,
-            dma_hi_address: 0,
-            dma_base_address: 0,
-            dma_address: 0,
-            dma_data: 0
+            o_start_address: None
         }
     }
 
@@ -55,73 +80,217 @@ impl NES {
     pub fn insert_cartdrige(&mut self, cartridge: Cartridge) {
         self.p_bus.lock().unwrap().o_p_mapper = Some(cartridge.mapper.clone());
         self.p_ppu.lock().unwrap().ppu_bus.o_p_mapper = Some(cartridge.mapper.clone());
+        self.o_p_mapper = Some(cartridge.mapper);
+    }
+
+    // Records or plays the inputs of a movie, plugs a Four Score if the movie needs it
+    pub fn set_movie(&mut self, movie: Movie) {
+        if movie.four_score {
+            self.p_bus.lock().unwrap().input_ports = [
+                create_port_device("four-score", 0, self.p_ppu.clone()),
+                create_port_device("four-score", 1, self.p_ppu.clone())
+            ];
+        }
+        self.o_p_movie = Some(Arc::new(Mutex::new(movie)));
     }
 
-    // Resets the CPU and launches the game
+    // Shares the Code/Data Logger with the components reading the cartridge
+    pub fn set_code_data_logger(&mut self, p_code_data_logger: Arc<Mutex<CodeDataLogger>>) {
+        self.p_cpu.lock().unwrap().o_p_code_data_logger = Some(p_code_data_logger.clone());
+        self.p_bus.lock().unwrap().o_p_code_data_logger = Some(p_code_data_logger.clone());
+        self.p_ppu.lock().unwrap().ppu_bus.o_p_code_data_logger = Some(p_code_data_logger);
+    }
+
+    // Powers the NES on and launches the game
     pub fn launch_game(&mut self) {
-        
-        self.p_cpu.lock().unwrap().reset();
-        self.total_clock = 0;
-        //self.p_cpu.lock().unwrap().pc = 0xC000; // Run nestest in automation mode (Fails at C6BD because of unofficial opcode)
+        self.power_cycle();
         loop {
-            // CPU is clocked every 3 PPU cycles
-            if self.total_clock%3 == 0 {
-                // If we initialized a DMA, do not clock CPU for nearly 513 cycles
-                if self.p_ppu.lock().unwrap().registers.perform_dma {
-                    self.perform_dma();
-                }
-                else {
-                    self.p_cpu.lock().unwrap().clock();
-                }
-            }
+            self.step();
+        }
+    }
 
-            // Clock PPU
-            self.p_ppu.lock().unwrap().clock();
+    // Presses the reset button : the CPU runs its reset sequence, the RAM is kept
+    pub fn reset(&mut self) {
+        self.p_bus.lock().unwrap().reset();
+        self.p_ppu.lock().unwrap().reset();
+        self.p_cpu.lock().unwrap().reset();
+        if let Some(address) = self.o_start_address {
+            self.p_cpu.lock().unwrap().pc = address;
+        }
+    }
 
-            // Check if an NMI interrupt should be thrown
-            if self.p_ppu.lock().unwrap().registers.emit_nmi {
-                self.p_ppu.lock().unwrap().registers.emit_nmi = false;
-                self.p_cpu.lock().unwrap().interrupt(Interrupt::NMI);
-            }
+    // Turns the NES off and on again, every component is reinitialized
+    // The registers and the RAM of the cartridge are lost too
+    pub fn power_cycle(&mut self) {
+        if let Some(mapper) = self.o_p_mapper.as_ref() {
+            self.p_bus.lock().unwrap().o_p_mapper = Some(mapper.clone());
+            self.p_ppu.lock().unwrap().ppu_bus.o_p_mapper = Some(mapper.clone());
+        }
+        self.p_bus.lock().unwrap().power_on(self.ram_pattern);
+        self.p_ppu.lock().unwrap().power_on();
+        self.p_cpu.lock().unwrap().power_on();
+        self.reset();
+    }
 
-            self.total_clock += 1;
+    // Runs the NES until the PPU has finished the current frame
+    pub fn run_frame(&mut self) {
+        let frame: u64 = self.p_ppu.lock().unwrap().frame_count;
+        while self.p_ppu.lock().unwrap().frame_count == frame {
+            self.step();
         }
     }
 
-    // Performs a DMA (transfer of 256 bytes of sprite data to PPU)
-    pub fn perform_dma(&mut self) {
-        if !self.dma_started {
-            // Wait for an even cycle to start
-            if self.total_clock % 2 == 0 {
-                self.dma_hi_address = self.p_ppu.lock().unwrap().registers.oam_dma;
-                self.dma_base_address = self.p_ppu.lock().unwrap().registers.oam_addr;
-                self.dma_address = self.dma_base_address;
-                self.dma_started = true;
+    // Runs one CPU instruction and the interrupt following it
+    // The PPU and the DMA unit are clocked by the CPU on each bus access
+    pub fn step(&mut self) {
+        if let Some(debugger) = self.o_debugger.as_mut() {
+            debugger.before_instruction();
+        }
+        if let Some(gdb_stub) = self.o_gdb_stub.as_mut() {
+            gdb_stub.before_instruction();
+        }
+        if let Some(p_trace_logger) = self.o_p_trace_logger.as_ref() {
+            p_trace_logger.lock().unwrap().before_instruction();
+        }
+        if let Some(p_memory_viewer) = self.o_p_memory_viewer.as_ref() {
+            while p_memory_viewer.lock().unwrap().paused {
+                thread::sleep(Duration::from_millis(10));
             }
         }
-        else {
-            // On even cycles, read data from the bus
-            if self.total_clock % 2 == 1 {
-                let address: u16 = (self.dma_address as u16) + ((self.dma_hi_address as u16) << 8);
-                self.dma_data = self.p_bus.lock().unwrap().read(address);
+        self.p_cpu.lock().unwrap().step();
+
+        // Sample the inputs at the beginning of the v blank period
+        if self.p_ppu.lock().unwrap().vblank_started {
+            self.p_ppu.lock().unwrap().vblank_started = false;
+            self.latch_inputs();
+            // The frozen bytes are written again at each frame
+            if let Some(p_memory_viewer) = self.o_p_memory_viewer.as_ref() {
+                let frame: u64 = self.p_ppu.lock().unwrap().frame_count;
+                p_memory_viewer.lock().unwrap().new_frame(frame);
             }
-            // On odd cycles, write data to the PPU OAM
-            else {
-                self.p_ppu.lock().unwrap().oam.write_primary(self.dma_address, self.dma_data);
-
-                if self.dma_address < 255 {
-                    self.dma_address += 1;
-                }
-                else {
-                    self.dma_address = 0;
-                }
-
-                // End DMA
-                if self.dma_address == self.dma_base_address {
-                    self.dma_started = false;
-                    self.p_ppu.lock().unwrap().registers.perform_dma = false;
-                }
+        }
+    }
+
+    // Gives the inputs of the current frame to the input devices
+    // They come from the frontend with the turbo buttons and the macros, or from the movie being played
+    // The commands of the frame are executed afterwards
+    pub fn latch_inputs(&mut self) {
+        let mut input_state: InputState;
+        {
+            let mut frontend_state = self.p_input_state.lock().unwrap();
+            input_state = *frontend_state;
+            // Each command and macro key is executed once
+            frontend_state.commands = 0;
+            frontend_state.macro_play = 0;
+            frontend_state.macro_record = 0;
+        }
+
+        // The turbo phase and the macros follow the emulated frames
+        let frame: u64 = self.p_ppu.lock().unwrap().frame_count;
+        for (buttons, turbo_buttons) in input_state.controllers.iter_mut().zip(input_state.turbo_controllers.iter()) {
+            *buttons |= self.turbo.apply(*turbo_buttons, frame);
+        }
+        for slot in 0..MACRO_SLOTS {
+            if input_state.macro_record & (1 << slot) != 0 {
+                self.macros.toggle_recording(slot);
+            }
+            else if input_state.macro_play & (1 << slot) != 0 {
+                self.macros.play(slot, frame);
+            }
+        }
+        input_state.controllers[0] = self.macros.apply(input_state.controllers[0], frame);
+
+        if let Some(p_movie) = self.o_p_movie.as_ref() {
+            p_movie.lock().unwrap().process_frame(&mut input_state);
+        }
+        self.p_bus.lock().unwrap().update_inputs(&input_state);
+
+        if input_state.commands & COMMAND_POWER_CYCLE != 0 {
+            self.power_cycle();
+        }
+        else if input_state.commands & COMMAND_SOFT_RESET != 0 {
+            self.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::{cartridge::INesHeader, mapper::Mirroring, mapper_002::Mapper2};
+    use crate::input::controller::ControllerInput;
+
+    // Creates a NES with an UxROM cartridge of 4 banks, the first byte of each bank is its number
+    fn create_nes() -> NES {
+        let mut prg_rom: Vec<[u8;16*1024]> = vec![[0;16*1024];4];
+        for (bank, data) in prg_rom.iter_mut().enumerate() {
+            data[0] = bank as u8;
+            // Reset vector of the fixed bank
+            data[0x3FFC..0x3FFE].copy_from_slice(&[0x00, 0xC0]);
+        }
+        let cartridge: Cartridge = Cartridge {
+            header: INesHeader::new(),
+            mapper: Box::new(Mapper2::new(prg_rom, vec![[0;8*1024]], Mirroring::Vertical)),
+            prg_rom: vec![],
+            chr_rom: vec![]
+        };
+        let p_ppu: Arc<Mutex<PPU>> = Arc::new(Mutex::new(PPU::new(None)));
+        let p_bus: Arc<Mutex<Bus>> = Arc::new(Mutex::new(Bus::new(p_ppu.clone())));
+        let p_cpu: Arc<Mutex<CPU>> = Arc::new(Mutex::new(CPU::new(p_bus.clone(), false)));
+        let mut nes: NES = NES::new(p_bus, p_cpu, p_ppu, None);
+        nes.insert_cartdrige(cartridge);
+        nes.power_cycle();
+        nes
+    }
+
+    #[test]
+    fn power_cycle_resets_the_cartridge() {
+        let mut nes: NES = create_nes();
+        let p_bus: Arc<Mutex<Bus>> = nes.p_bus.clone();
+        p_bus.lock().unwrap().write(0x8000, 0x02);
+        assert_eq!(p_bus.lock().unwrap().read_only(0x8000), 0x02);
+        // The bank selected by the game is kept by a reset, not by a power cycle
+        nes.reset();
+        assert_eq!(p_bus.lock().unwrap().read_only(0x8000), 0x02);
+        nes.power_cycle();
+        assert_eq!(p_bus.lock().unwrap().read_only(0x8000), 0x00);
+    }
+
+    #[test]
+    fn turbo_and_macros() {
+        let (a, b): (u8, u8) = (ControllerInput::A as u8, ControllerInput::B as u8);
+        let mut nes: NES = create_nes();
+        nes.turbo = Turbo::new(1);
+        let p_movie: Arc<Mutex<Movie>> = Arc::new(Mutex::new(Movie::new("", false)));
+        nes.o_p_movie = Some(p_movie.clone());
+        // Sets the inputs of the frontend, then latches them as the emulation does at the given frame
+        let run_frame = |nes: &mut NES, frame: u64, buttons: u8, turbo_buttons: u8, macro_play: u8, macro_record: u8| {
+            {
+                let mut input_state = nes.p_input_state.lock().unwrap();
+                input_state.controllers[0] = buttons;
+                input_state.turbo_controllers[0] = turbo_buttons;
+                input_state.macro_play |= macro_play;
+                input_state.macro_record |= macro_record;
             }
+            nes.p_ppu.lock().unwrap().frame_count = frame;
+            nes.latch_inputs();
+        };
+
+        // The turbo button is pressed on even frames, whatever the number of frontend updates
+        for frame in 0..4 {
+            run_frame(&mut nes, frame, 0, a, 0, 0);
         }
+        // A macro is recorded on slot 1, then played
+        run_frame(&mut nes, 4, b, 0, 0, 0x02);
+        run_frame(&mut nes, 5, a, 0, 0, 0);
+        run_frame(&mut nes, 6, 0, 0, 0, 0x02);
+        run_frame(&mut nes, 7, 0, 0, 0x02, 0);
+        run_frame(&mut nes, 8, 0, 0, 0, 0);
+        run_frame(&mut nes, 9, 0, 0, 0, 0);
+        // The macro keys are only used once
+        assert_eq!(nes.p_input_state.lock().unwrap().macro_play, 0);
+
+        let recorded: Vec<u8> = p_movie.lock().unwrap().frames.iter().map(|frame| frame.controllers[0]).collect();
+        assert_eq!(recorded, vec![a, 0, a, 0, b, a, 0, b, a, 0]);
     }
-}
\ No newline at end of file
+}
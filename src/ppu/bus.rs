@@ -2,7 +2,9 @@
 
 // ===== IMPORTS =====
 
-use crate::cartridge::mapper::{Mapper, Mirroring};
+use std::sync::{Arc, Mutex};
+
+use crate::{cartridge::mapper::{Mapper, Mirroring}, code_data_logger::CodeDataLogger, memory_viewer::AccessHistory};
 
 use super::enums::VRAMAddressMask;
 
@@ -61,7 +63,13 @@ pub struct PPUBus {
     pub tmp_vram_address: VRAMAddress,
 
     // Mapper
-    pub o_p_mapper: Option<Box<dyn Mapper>>
+    pub o_p_mapper: Option<Box<dyn Mapper>>,
+
+    // Marks the bytes of the CHR ROM rendered or read through $2007
+    pub o_p_code_data_logger: Option<Arc<Mutex<CodeDataLogger>>>,
+
+    // Accesses through $2007, highlighted by the memory viewer
+    pub o_access_history: Option<AccessHistory>
 }
 
 impl PPUBus {
@@ -76,7 +84,11 @@ impl PPUBus {
             vram_address: VRAMAddress::new(),
             tmp_vram_address: VRAMAddress::new(),
 
-            o_p_mapper: None
+            o_p_mapper: None,
+
+            o_p_code_data_logger: None,
+
+            o_access_history: None
         }
     }
 
@@ -92,6 +104,13 @@ impl PPUBus {
         value
     }
 
+    // Marks the byte of the CHR ROM mapped at this address in the Code/Data Logger
+    pub fn log_chr(&self, address: u16, flags: u8) {
+        if let (Some(p_code_data_logger), Some(mapper), 0x0000..=0x1FFF) = (self.o_p_code_data_logger.as_ref(), self.o_p_mapper.as_ref(), address) {
+            p_code_data_logger.lock().unwrap().log_chr(mapper.chr_rom_offset(address), flags);
+        }
+    }
+
     pub fn read_name_tables(&self, address: u16) -> u8 {
         let value: u8;
         // Vertical mirroring
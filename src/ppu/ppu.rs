@@ -5,7 +5,7 @@
 
 use std::sync::{Arc, Mutex};
 
-use crate::gui::GUI;
+use crate::{cartridge::mapper::Mapper, code_data_logger::{CHR_RENDERED, CodeDataLogger}, gui::GUI, memory_viewer::AccessHistory};
 
 use super::{bus::PPUBus, enums::{ControlFlag, MaskFlag, SpriteAttribute, StatusFlag, VRAMAddressMask}, oam::OAM, palette::{ARGBColor, PALETTE}, registers::Registers};
 
@@ -14,6 +14,9 @@ use super::{bus::PPUBus, enums::{ControlFlag, MaskFlag, SpriteAttribute, StatusF
 const MAX_CYCLES: u16 = 340;
 const MAX_SCANLINES: u16 = 261;
 
+pub const FRAME_WIDTH: usize = 256;
+pub const FRAME_HEIGHT: usize = 240;
+
 // ===== STRUCT =====
 
 pub struct PPU {
@@ -60,12 +63,25 @@ pub struct PPU {
 
     pub total_clock: u64,
 
-    // GUI
-    pub p_gui: Arc<Mutex<GUI>>
+    // Number of frames rendered since the start of the PPU
+    pub frame_count: u64,
+
+    // Set at the beginning of the v blank period, used to sample the inputs
+    pub vblank_started: bool,
+    // The status was read one dot before the v blank flag is set
+    pub vblank_suppressed: bool,
+    // Writes to $2000, $2001, $2005 and $2006 are ignored after a reset, until the end of the v blank period
+    pub warming_up: bool,
+
+    // Colors of the pixels of the current frame
+    pub frame_buffer: Vec<ARGBColor>,
+
+    // GUI, None when running without a window
+    pub o_p_gui: Option<Arc<Mutex<GUI>>>
 }
 
 impl PPU {
-    pub fn new(p_gui: Arc<Mutex<GUI>>) -> Self {
+    pub fn new(o_p_gui: Option<Arc<Mutex<GUI>>>) -> Self {
         PPU {
             registers: Registers::new(),
 
@@ -101,11 +117,48 @@ impl PPU {
             odd_frame: false,
 
             total_clock: 0,
+
+            frame_count: 0,
+
+            vblank_started: false,
+            vblank_suppressed: false,
+            warming_up: false,
+
+            frame_buffer: vec![ARGBColor::new(255, 0, 0, 0); FRAME_WIDTH*FRAME_HEIGHT],
             
-            p_gui
+            o_p_gui
         }
     }
 
+    // ===== RESET =====
+
+    // Called when the reset button is pressed on the NES
+    // The rendering position, the OAM and the VRAM are kept
+    pub fn reset(&mut self) {
+        self.registers.ctrl = 0;
+        self.registers.mask = 0;
+        self.registers.scroll = 0;
+        self.registers.data_buffer = 0;
+        self.registers.w = false;
+        self.registers.fine_x = 0;
+        self.odd_frame = false;
+        self.warming_up = true;
+    }
+
+    // Puts the PPU in its power on state
+    // The cartridge, the GUI and the frame count are kept
+    pub fn power_on(&mut self) {
+        let o_p_mapper: Option<Box<dyn Mapper>> = self.ppu_bus.o_p_mapper.take();
+        let o_p_code_data_logger: Option<Arc<Mutex<CodeDataLogger>>> = self.ppu_bus.o_p_code_data_logger.take();
+        let o_access_history: Option<AccessHistory> = self.ppu_bus.o_access_history.take();
+        let frame_count: u64 = self.frame_count;
+        *self = PPU::new(self.o_p_gui.take());
+        self.ppu_bus.o_p_mapper = o_p_mapper;
+        self.ppu_bus.o_p_code_data_logger = o_p_code_data_logger;
+        self.ppu_bus.o_access_history = o_access_history;
+        self.frame_count = frame_count;
+    }
+
     // ===== CLOCK =====
 
     // Executes a clock cycle
@@ -167,6 +220,7 @@ impl PPU {
                         + ((self.next_name_table_byte as u16)<< 4)
                         + ((self.registers.get_control_flag(ControlFlag::BackgroundPatternTableAddress) as u16) << 12);
                     self.next_low_background_byte = self.ppu_bus.read(address);
+                    self.ppu_bus.log_chr(address, CHR_RENDERED);
                 }
                 // Get the high background tile byte
                 else if ((self.cycles - 1) % 8) == 6 {
@@ -177,6 +231,7 @@ impl PPU {
                         + ((self.registers.get_control_flag(ControlFlag::BackgroundPatternTableAddress) as u16) << 12)
                         + 8;
                     self.next_high_background_byte = self.ppu_bus.read(address);
+                    self.ppu_bus.log_chr(address, CHR_RENDERED);
                 }
                 // Increment VRAM Address
                 else if ((self.cycles - 1) % 8) == 7 {
@@ -236,10 +291,11 @@ impl PPU {
 
         // Set the v blank flag at the beginning of the v blank period
         if self.scanline == 241 && self.cycles == 1 {
-            self.registers.set_status_flag(StatusFlag::VBlank, true);
-            if self.registers.get_control_flag(ControlFlag::VBlank) != 0 {
-                self.registers.emit_nmi = true;
+            self.vblank_started = true;
+            if !self.vblank_suppressed {
+                self.registers.set_status_flag(StatusFlag::VBlank, true);
             }
+            self.vblank_suppressed = false;
         }
 
         // Clear the v blank flag at the end of the v blank period
@@ -247,6 +303,7 @@ impl PPU {
             self.registers.set_status_flag(StatusFlag::VBlank, false);
             self.registers.set_status_flag(StatusFlag::Sprite0Hit, false);
             self.registers.set_status_flag(StatusFlag::SpriteOverflow, false);
+            self.warming_up = false;
         }
 
         if self.scanline == 261 && (self.cycles > 279 && self.cycles < 305) {
@@ -331,13 +388,12 @@ impl PPU {
             }
 
             // Renders pixel
-            self.p_gui
-                .lock()
-                .unwrap()
-                .update_main_buffer(
-                    (256*self.scanline as u32 + self.cycles as u32 - 1) as usize,
-                    self.get_pixel_color(palette, pattern)
-                );
+            let index: usize = FRAME_WIDTH*self.scanline as usize + self.cycles as usize - 1;
+            let color: ARGBColor = self.get_pixel_color(palette, pattern);
+            self.frame_buffer[index] = color;
+            if let Some(p_gui) = self.o_p_gui.as_ref() {
+                p_gui.lock().unwrap().update_main_buffer(index, color);
+            }
         }
 
         // Increasing cycles and scanlines to reach a 341*262 matrix
@@ -350,12 +406,15 @@ impl PPU {
             if self.scanline > MAX_SCANLINES {
                 self.scanline = 0;
                 self.odd_frame = !self.odd_frame;
-                // Debugging
-                if self.p_gui.lock().unwrap().debug {
-                    self.debug(); // Updates debug buffer to display pattern tables
+                self.frame_count += 1;
+                if let Some(p_gui) = self.o_p_gui.clone() {
+                    // Debugging
+                    if p_gui.lock().unwrap().debug {
+                        self.debug(); // Updates debug buffer to display pattern tables
+                    }
+                    // A frame is ready to be displayed
+                    p_gui.lock().unwrap().update();
                 }
-                // A frame is ready to be displayed
-                self.p_gui.lock().unwrap().update();
             }
         }
 
@@ -378,10 +437,17 @@ impl PPU {
     // ===== REGISTERS METHODS =====
 
     pub fn write_register(&mut self, address: u16, value: u8) {
+        if self.warming_up && matches!(address, 0x2000 | 0x2001 | 0x2005 | 0x2006) {
+            return;
+        }
         self.registers.write_register(&mut self.ppu_bus, &mut self.oam, address, value);
     }
 
     pub fn read_register(&mut self, address: u16) -> u8 {
+        // Reading the status just before the v blank flag is set prevents it from being set for this frame
+        if address == 0x2002 && self.scanline == 241 && self.cycles == 1 {
+            self.vblank_suppressed = true;
+        }
         self.registers.read_register(&mut self.ppu_bus, &self.oam, address)
     }
 
@@ -490,6 +556,8 @@ impl PPU {
                     // Get low and high bytes of the sprite
                     let mut lo_sprite: u8 = self.ppu_bus.read(lo_address);
                     let mut hi_sprite: u8 = self.ppu_bus.read(lo_address + 8);
+                    self.ppu_bus.log_chr(lo_address, CHR_RENDERED);
+                    self.ppu_bus.log_chr(lo_address + 8, CHR_RENDERED);
 
                     // Flip horizontally
                     if self.oam.secondary[sprite_index].get_attribute_flag(SpriteAttribute::FlipHorizontally) == 1 {
@@ -649,6 +717,7 @@ impl PPU {
 
     // ===== DEBUGGING =====
 
+    // Only called when the PPU has a GUI
     pub fn debug(&self) {
         self.display_pattern_table(0);
         self.display_pattern_table(1);
@@ -674,7 +743,7 @@ impl PPU {
                 tile_high >>= 1;
                 tile_low >>= 1;
                 let c: ARGBColor = self.get_pixel_color(0, color);
-                self.p_gui.lock().unwrap().update_debug_buffer(
+                self.o_p_gui.as_ref().unwrap().lock().unwrap().update_debug_buffer(
                     ((n_tile_x*8+(7-col) + number*128 + (n_tile_y*8+row)*256)) as usize, c
                 );
             }
@@ -683,7 +752,7 @@ impl PPU {
 
     pub fn display_separation(&self) {
         for i in 0..512 {
-            self.p_gui.lock().unwrap().update_debug_buffer(
+            self.o_p_gui.as_ref().unwrap().lock().unwrap().update_debug_buffer(
                 256*128 + i,
                 ARGBColor::new(255, 50, 50, 50)
             );
@@ -696,7 +765,9 @@ impl PPU {
             for i in 0..6 {
                 for j in 0..6 {
                     let index = 258*128 + (offset * 6) + (((offset % 4) == 0) as u32)*2 + i + j*256;
-                    self.p_gui
+                    self.o_p_gui
+                        .as_ref()
+                        .unwrap()
                         .lock()
                         .unwrap()
                         .update_debug_buffer(
@@ -1,3 +1,5 @@
+use crate::code_data_logger::CHR_READ;
+
 use super::{bus::PPUBus, enums::{ControlFlag, MaskFlag, StatusFlag, VRAMAddressMask}, oam::OAM};
 
 // Reprensents the PPU registers
@@ -23,12 +25,6 @@ pub struct Registers {
     // Required to check if it is the first or second write to 2006/2007
     pub w: bool,
     pub fine_x: u8,
-
-    // Emit an NMI interrupt
-    pub emit_nmi: bool,
-
-    // Perform a DMA
-    pub perform_dma: bool,
 }
 
 impl Registers {
@@ -50,10 +46,6 @@ impl Registers {
 
             w: false,
             fine_x: 0,
-
-            emit_nmi: false,
-
-            perform_dma: false,
         }
     }
 
@@ -62,9 +54,6 @@ impl Registers {
         match address {
             0x2000 => {
                 self.ctrl = value;
-                if self.get_status_flag(StatusFlag::VBlank) && (value & 0x80) == 0x80 {
-                    self.emit_nmi = true;
-                }
                 ppu_bus.tmp_vram_address.set_address_part(VRAMAddressMask::NametableSelect, (value & 0x03) as u16);
             }
             0x2001 => self.mask = value,
@@ -109,6 +98,9 @@ impl Registers {
             0x2007 => {
                 self.data = value;
                 ppu_bus.write(ppu_bus.vram_address.address & 0x3FFF, value);
+                if let Some(history) = ppu_bus.o_access_history.as_mut() {
+                    history.record_write((ppu_bus.vram_address.address & 0x3FFF) as usize);
+                }
                 if self.get_control_flag(ControlFlag::VRAMAddressIncrement) == 0 {
                     ppu_bus.vram_address.address += 1; // Horizontal scrolling
                 }
@@ -116,10 +108,7 @@ impl Registers {
                     ppu_bus.vram_address.address += 32; // Vertical scrolling
                 }
             }
-            0x4014 => {
-                self.oam_dma = value;
-                self.perform_dma = true;
-            }
+            0x4014 => self.oam_dma = value,
             _ => panic!("Wrong address given to PPU : {:#x}",address)
         }
         self.decay = value;
@@ -149,6 +138,10 @@ impl Registers {
                 // Read to 2007 is delayed by one read except for the palette
                 value = self.data_buffer;
                 self.data_buffer = ppu_bus.read(ppu_bus.vram_address.address);
+                ppu_bus.log_chr(ppu_bus.vram_address.address, CHR_READ);
+                if let Some(history) = ppu_bus.o_access_history.as_mut() {
+                    history.record_read((ppu_bus.vram_address.address & 0x3FFF) as usize);
+                }
                 if ppu_bus.vram_address.address >= 0x3F00 {
                     value = (self.decay & 0xC0) | (self.data_buffer & 0x3F);
                     // Fill the buffer with the mirrored nametable "under" palette RAM
@@ -198,6 +191,12 @@ impl Registers {
         (self.mask & (flag as u8)) == (flag as u8)
     }
 
+    // The NMI output is low while the v blank flag and the NMI enable bit are both set
+    // Returns true when the NMI is asserted
+    pub fn nmi_line(&self) -> bool {
+        self.get_status_flag(StatusFlag::VBlank) && (self.ctrl & ControlFlag::VBlank as u8) != 0
+    }
+
     // Used for debugging
     #[allow(dead_code)]
     pub fn read_register_without_modification(&self, address: u16) -> u8 {
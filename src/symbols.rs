@@ -0,0 +1,289 @@
+// Labels and comments of a game, loaded from the symbol files of assemblers and other emulators
+// Supported files : ca65 / ld65 debug files (.dbg), FCEUX name lists (.nl) and Mesen label files (.mlb)
+// The symbols of the cartridge ROM are located by their offset in the PRG ROM, so they follow the bank switching
+
+// ===== IMPORTS =====
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+// ===== CONSTANTS =====
+
+// Size of the iNES header, the output offsets of ld65 include it
+const INES_HEADER_SIZE: usize = 16;
+
+// FCEUX name lists describe 16KB banks
+const NL_BANK_SIZE: usize = 0x4000;
+
+// Start of the save RAM and work RAM of the cartridge
+const CARTRIDGE_RAM_START: u16 = 0x6000;
+
+// ===== STRUCTS =====
+
+// The RAM and the registers are located by their CPU address, the code and data of the ROM by their offset in the PRG ROM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SymbolLocation {
+    Cpu(u16),
+    PrgRom(usize)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub comment: String
+}
+
+#[derive(Default)]
+pub struct SymbolTable {
+    pub symbols: BTreeMap<SymbolLocation, Symbol>
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        SymbolTable {
+            symbols: BTreeMap::new()
+        }
+    }
+
+    // ===== LOADING =====
+
+    // Loads a symbol file, the format is given by its extension
+    pub fn load(&mut self, path: &Path) -> Result<(), String> {
+        let content: String = fs::read_to_string(path).map_err(|why| format!("Couldn't read {}: {}", path.display(), why))?;
+        let file_name: String = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("dbg") => self.parse_dbg(&content),
+            Some("mlb") => self.parse_mlb(&content),
+            Some("nl") => {
+                // game.nes.ram.nl names the RAM, game.nes.N.nl the PRG ROM bank N (hexadecimal)
+                let o_bank: Option<usize> = match file_name.trim_end_matches(".nl").rsplit('.').next() {
+                    Some("ram") => None,
+                    Some(bank) => Some(usize::from_str_radix(bank, 16).map_err(|_| format!("No bank number in the file name {}", file_name))?),
+                    None => None
+                };
+                self.parse_nl(&content, o_bank)
+            },
+            _ => Err(format!("Unknown symbol file format: {}", path.display()))
+        }
+    }
+
+    // ld65 debug file, the labels are read from the "sym" lines and located with their "seg" line
+    pub fn parse_dbg(&mut self, content: &str) -> Result<(), String> {
+        // Segments by id : start address and offset in the PRG ROM when they are written to the ROM
+        let mut segments: BTreeMap<u32, (u32, Option<usize>)> = BTreeMap::new();
+        let mut labels: Vec<(String, u32, Option<u32>, u32)> = vec![];
+        for line in content.lines() {
+            let (kind, fields): (&str, &str) = match line.split_once(char::is_whitespace) {
+                Some(split) => split,
+                None => continue
+            };
+            let fields: BTreeMap<&str, &str> = parse_dbg_fields(fields);
+            match kind {
+                "seg" => {
+                    let id: u32 = dbg_number(&fields, "id")?;
+                    let start: u32 = dbg_number(&fields, "start")?;
+                    let o_offset: Option<usize> = match (fields.get("ooffs"), fields.get("type")) {
+                        (Some(_), Some(&"ro")) => (dbg_number(&fields, "ooffs")? as usize).checked_sub(INES_HEADER_SIZE),
+                        _ => None
+                    };
+                    segments.insert(id, (start, o_offset));
+                },
+                // Only the labels have an address, the other symbols are constants
+                "sym" if fields.get("type") == Some(&"lab") => {
+                    let name: String = fields.get("name").ok_or("Symbol without name")?.trim_matches('"').to_string();
+                    let o_segment: Option<u32> = match fields.get("seg") {
+                        Some(_) => Some(dbg_number(&fields, "seg")?),
+                        None => None
+                    };
+                    let size: u32 = match fields.get("size") {
+                        Some(_) => dbg_number(&fields, "size")?,
+                        None => 1
+                    };
+                    labels.push((name, dbg_number(&fields, "val")?, o_segment, size));
+                },
+                _ => ()
+            }
+        }
+        for (name, value, o_segment, size) in labels {
+            let location: SymbolLocation = match o_segment.and_then(|segment| segments.get(&segment)) {
+                Some((start, Some(offset))) if value >= *start => SymbolLocation::PrgRom(offset + (value - start) as usize),
+                _ => SymbolLocation::Cpu(value as u16)
+            };
+            self.insert_range(location, size as usize, &name, "");
+        }
+        Ok(())
+    }
+
+    // FCEUX name list, lines are "$ADDR#NAME#COMMENT" or "$ADDR/SIZE#NAME#COMMENT"
+    // The addresses are CPU addresses, in the given 16KB bank for the PRG ROM
+    pub fn parse_nl(&mut self, content: &str, o_bank: Option<usize>) -> Result<(), String> {
+        for line in content.lines() {
+            if !line.starts_with('$') {
+                continue;
+            }
+            let mut fields = line[1..].splitn(3, '#');
+            let (address, size): (&str, &str) = match fields.next() {
+                Some(field) => field.split_once('/').unwrap_or((field, "1")),
+                None => continue
+            };
+            let address: u16 = u16::from_str_radix(address, 16).map_err(|_| format!("Invalid address: {}", line))?;
+            let size: usize = usize::from_str_radix(size, 16).map_err(|_| format!("Invalid size: {}", line))?;
+            let name: &str = fields.next().unwrap_or("");
+            let comment: &str = fields.next().unwrap_or("").trim_end_matches('#');
+            let location: SymbolLocation = match o_bank {
+                Some(bank) if address >= 0x8000 => SymbolLocation::PrgRom(bank * NL_BANK_SIZE + (address as usize % NL_BANK_SIZE)),
+                _ => SymbolLocation::Cpu(address)
+            };
+            self.insert_range(location, size.max(1), name, comment);
+        }
+        Ok(())
+    }
+
+    // Mesen label file, lines are "TYPE:ADDR[-END]:NAME[:COMMENT]"
+    // Mesen 2 memory type names are also accepted
+    pub fn parse_mlb(&mut self, content: &str) -> Result<(), String> {
+        for line in content.lines() {
+            let fields: Vec<&str> = line.trim_end_matches('\r').splitn(4, ':').collect();
+            if fields.len() < 3 {
+                continue;
+            }
+            let (start, end): (&str, &str) = match fields[1].split_once('-') {
+                Some(range) => range,
+                None => (fields[1], fields[1])
+            };
+            let start: usize = usize::from_str_radix(start, 16).map_err(|_| format!("Invalid address: {}", line))?;
+            let end: usize = usize::from_str_radix(end, 16).map_err(|_| format!("Invalid address: {}", line))?;
+            let location: SymbolLocation = match fields[0] {
+                "P" | "NesPrgRom" => SymbolLocation::PrgRom(start),
+                "R" | "NesInternalRam" => SymbolLocation::Cpu((start & 0x07FF) as u16),
+                "S" | "W" | "NesSaveRam" | "NesWorkRam" => SymbolLocation::Cpu(CARTRIDGE_RAM_START + (start & 0x1FFF) as u16),
+                "G" | "NesMemory" => SymbolLocation::Cpu(start as u16),
+                // The labels of the other memories (CHR, PPU RAM...) are not displayed
+                _ => continue
+            };
+            self.insert_range(location, end.saturating_sub(start) + 1, fields[2], fields.get(3).unwrap_or(&""));
+        }
+        Ok(())
+    }
+
+    // The bytes following the first one of an array are named NAME+INDEX
+    fn insert_range(&mut self, location: SymbolLocation, size: usize, name: &str, comment: &str) {
+        if name.is_empty() && comment.is_empty() {
+            return;
+        }
+        for index in 0..size {
+            let (location, name): (SymbolLocation, String) = match (location, index) {
+                (location, 0) => (location, String::from(name)),
+                (SymbolLocation::Cpu(address), _) => (SymbolLocation::Cpu(address.wrapping_add(index as u16)), format!("{}+{}", name, index)),
+                (SymbolLocation::PrgRom(offset), _) => (SymbolLocation::PrgRom(offset + index), format!("{}+{}", name, index))
+            };
+            // Only the first byte of an array has the comment, a symbol with a name isn't replaced by an array element
+            if index > 0 && self.symbols.contains_key(&location) {
+                continue;
+            }
+            let comment: String = match index {
+                0 => comment.replace("\\n", " "),
+                _ => String::new()
+            };
+            let symbol: &mut Symbol = self.symbols.entry(location).or_insert(Symbol { name: String::new(), comment: String::new() });
+            if !name.is_empty() {
+                symbol.name = name;
+            }
+            if !comment.is_empty() {
+                symbol.comment = comment;
+            }
+        }
+    }
+
+    // ===== LOOKUP =====
+
+    // Symbol of a CPU address, the ROM symbols are found with the offset of the byte in the PRG ROM
+    pub fn lookup(&self, address: u16, o_prg_rom_offset: Option<usize>) -> Option<&Symbol> {
+        if let Some(symbol) = o_prg_rom_offset.and_then(|offset| self.symbols.get(&SymbolLocation::PrgRom(offset))) {
+            return Some(symbol);
+        }
+        self.symbols.get(&SymbolLocation::Cpu(address))
+    }
+
+    // Location of a symbol by name
+    pub fn find(&self, name: &str) -> Option<SymbolLocation> {
+        self.symbols.iter().find(|(_, symbol)| symbol.name == name).map(|(location, _)| *location)
+    }
+}
+
+// ===== HELPER FUNCTIONS =====
+
+// Splits the "key=value" fields of a debug file line, the quoted values may contain commas
+fn parse_dbg_fields(fields: &str) -> BTreeMap<&str, &str> {
+    let mut result: BTreeMap<&str, &str> = BTreeMap::new();
+    let mut start: usize = 0;
+    let mut quoted: bool = false;
+    for (index, character) in fields.char_indices().chain(std::iter::once((fields.len(), ','))) {
+        match character {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                if let Some((key, value)) = fields[start..index].split_once('=') {
+                    result.insert(key.trim(), value.trim());
+                }
+                start = index + 1;
+            },
+            _ => ()
+        }
+    }
+    result
+}
+
+// Numbers are decimal or hexadecimal with a 0x prefix
+fn dbg_number(fields: &BTreeMap<&str, &str>, key: &str) -> Result<u32, String> {
+    let value: &str = fields.get(key).ok_or(format!("Missing field {}", key))?;
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => value.parse()
+    }.map_err(|_| format!("Invalid number for {}: {}", key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dbg_file() {
+        let mut symbols: SymbolTable = SymbolTable::new();
+        symbols.parse_dbg("\
+version\tmajor=2,minor=0
+seg\tid=0,name=\"ZEROPAGE\",start=0x000000,size=0x0010,addrsize=zeropage,type=rw
+seg\tid=1,name=\"CODE\",start=0x00C000,size=0x4000,addrsize=absolute,type=ro,oname=\"game.nes\",ooffs=16400
+sym\tid=0,name=\"frame_counter\",addrsize=zeropage,size=2,scope=0,def=1,ref=2,val=0x10,seg=0,type=lab
+sym\tid=1,name=\"reset\",addrsize=absolute,scope=0,def=3,val=0xC010,seg=1,type=lab
+sym\tid=2,name=\"PPUCTRL\",addrsize=absolute,scope=0,def=4,val=0x2000,type=equ
+sym\tid=3,name=\"SPEED\",addrsize=zeropage,scope=0,def=5,val=0x3,type=equ").unwrap();
+        assert_eq!(symbols.lookup(0x10, None).unwrap().name, "frame_counter");
+        assert_eq!(symbols.lookup(0x11, None).unwrap().name, "frame_counter+1");
+        assert_eq!(symbols.lookup(0xC010, Some(0x4010)).unwrap().name, "reset");
+        assert_eq!(symbols.lookup(0xC010, Some(0x0010)), None);
+        assert_eq!(symbols.lookup(0x0003, None), None);
+        assert_eq!(symbols.find("reset"), Some(SymbolLocation::PrgRom(0x4010)));
+    }
+
+    #[test]
+    fn nl_file() {
+        let mut symbols: SymbolTable = SymbolTable::new();
+        symbols.parse_nl("$C000#NMI#Vertical blank handler\n$8010#Bank0Code#\n", Some(1)).unwrap();
+        symbols.parse_nl("$0300/4#Buffer#\n$0010##Temporary\n", None).unwrap();
+        assert_eq!(symbols.lookup(0xC000, Some(0x4000)), Some(&Symbol { name: String::from("NMI"), comment: String::from("Vertical blank handler") }));
+        assert_eq!(symbols.find("Bank0Code"), Some(SymbolLocation::PrgRom(0x4010)));
+        assert_eq!(symbols.lookup(0x0303, None).unwrap().name, "Buffer+3");
+        assert_eq!(symbols.lookup(0x0304, None), None);
+        assert_eq!(symbols.lookup(0x0010, None).unwrap().comment, "Temporary");
+    }
+
+    #[test]
+    fn mlb_file() {
+        let mut symbols: SymbolTable = SymbolTable::new();
+        symbols.parse_mlb("P:7FFA:NmiVector:Interrupt vectors: NMI\nR:0800:Mirrored\nS:0000-0001:SaveChecksum\nG:2002:PPUSTATUS\nC:0000:Tiles\n").unwrap();
+        assert_eq!(symbols.lookup(0xFFFA, Some(0x7FFA)).unwrap().comment, "Interrupt vectors: NMI");
+        assert_eq!(symbols.lookup(0x0000, None).unwrap().name, "Mirrored");
+        assert_eq!(symbols.lookup(0x6001, None).unwrap().name, "SaveChecksum+1");
+        assert_eq!(symbols.find("PPUSTATUS"), Some(SymbolLocation::Cpu(0x2002)));
+        assert_eq!(symbols.find("Tiles"), None);
+    }
+}
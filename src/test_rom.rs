@@ -0,0 +1,104 @@
+// Runs the test ROMs following blargg's $6000 result protocol
+// $6001-$6003 contain $DE $B0 $61 once the result is valid
+// $6000 is the status : $80 while running, $81 when a reset is needed, then the result code (0 if passed)
+// $6004 contains a null terminated text message
+
+// ===== IMPORTS =====
+
+use std::path::Path;
+
+use crate::{cartridge::cartridge::Cartridge, headless::Headless};
+
+// ===== CONSTANTS =====
+
+pub const STATUS_ADDRESS: u16 = 0x6000;
+pub const SIGNATURE_ADDRESS: u16 = 0x6001;
+pub const TEXT_ADDRESS: u16 = 0x6004;
+
+pub const SIGNATURE: [u8;3] = [0xDE, 0xB0, 0x61];
+pub const STATUS_RUNNING: u8 = 0x80;
+pub const STATUS_RESET_NEEDED: u8 = 0x81;
+
+// The reset must be pressed at least 100 ms after the request
+pub const RESET_DELAY_FRAMES: u64 = 10;
+// 30 seconds of emulated time
+pub const DEFAULT_TIMEOUT_FRAMES: u64 = 30 * 60;
+
+const MAX_TEXT_LENGTH: u16 = 0x1000;
+
+// ===== ENUM =====
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestRomResult {
+    Passed(String),
+    // Result code and message
+    Failed(u8, String),
+    // The ROM didn't give a result before the timeout
+    Timeout
+}
+
+impl TestRomResult {
+    pub fn is_passed(&self) -> bool {
+        matches!(self, TestRomResult::Passed(_))
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            TestRomResult::Passed(message) | TestRomResult::Failed(_, message) => message,
+            TestRomResult::Timeout => "Timeout"
+        }
+    }
+}
+
+// ===== FUNCTIONS =====
+
+// Runs a test ROM until it gives a result or the timeout is reached
+pub fn run_test_rom(path: &Path, timeout_frames: u64) -> TestRomResult {
+    let mut headless: Headless = Headless::new(Cartridge::new(path), false);
+    let mut o_reset_frame: Option<u64> = None;
+
+    for frame in 0..timeout_frames {
+        headless.run_frame();
+
+        if !has_signature(&headless) {
+            continue;
+        }
+        match read_byte(&headless, STATUS_ADDRESS) {
+            STATUS_RUNNING => (),
+            STATUS_RESET_NEEDED => {
+                match o_reset_frame {
+                    None => o_reset_frame = Some(frame + RESET_DELAY_FRAMES),
+                    Some(reset_frame) if frame >= reset_frame => {
+                        headless.nes.reset();
+                        o_reset_frame = None;
+                    },
+                    Some(_) => ()
+                }
+            },
+            0x00 => return TestRomResult::Passed(read_text(&headless)),
+            code => return TestRomResult::Failed(code, read_text(&headless))
+        }
+    }
+    TestRomResult::Timeout
+}
+
+fn read_byte(headless: &Headless, address: u16) -> u8 {
+    headless.nes.p_bus.lock().unwrap().read_only(address)
+}
+
+fn has_signature(headless: &Headless) -> bool {
+    (0..SIGNATURE.len()).all(|i| read_byte(headless, SIGNATURE_ADDRESS + i as u16) == SIGNATURE[i])
+}
+
+// Reads the null terminated message written by the ROM
+fn read_text(headless: &Headless) -> String {
+    let mut text: Vec<u8> = Vec::new();
+    for offset in 0..MAX_TEXT_LENGTH {
+        let byte: u8 = read_byte(headless, TEXT_ADDRESS + offset);
+        if byte == 0 {
+            break;
+        }
+        text.push(byte);
+    }
+    String::from_utf8_lossy(&text).trim().to_string()
+}
@@ -0,0 +1,304 @@
+// Writes a trace of the executed instructions to a buffered file
+// The trace can be restricted to a range of PC, to a window of frames, and start when a trigger condition is met
+// The lines follow the format of Nintendulator, FCEUX or Mesen, so the traces can be compared with these emulators
+
+// ===== IMPORTS =====
+
+use std::{fs::File, io::{self, BufWriter, Write}, path::Path, sync::{Arc, Mutex}};
+
+use log::error;
+
+use crate::{bus::Bus, cpu::{cpu::CPU, cpu_bus::CpuBus, instructions::INSTRUCTIONS}, ppu::ppu::PPU};
+
+// ===== CONSTANTS =====
+
+// Size of the PRG ROM banks displayed in the trace
+const BANK_SIZE: usize = 0x4000;
+
+// ===== ENUMS =====
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceFormat {
+    // C000  4C F5 C5  JMP $C5F5      A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+    Nintendulator,
+    // A:00 X:00 Y:00 S:FD P:nvUbdIzc  $C000:4C F5 C5  JMP $C5F5
+    Fceux,
+    // C000  4C F5 C5  JMP $C5F5      A:00 X:00 Y:00 S:FD P:nvUbdIzc V:0   H:21  Fr:0 Cycle:7
+    Mesen
+}
+
+impl TraceFormat {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "nintendulator" => Ok(TraceFormat::Nintendulator),
+            "fceux" => Ok(TraceFormat::Fceux),
+            "mesen" => Ok(TraceFormat::Mesen),
+            _ => Err(format!("Unknown trace format : {}", name))
+        }
+    }
+}
+
+// Condition starting the trace, the trace goes on once it has been met
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceTrigger {
+    // The PC reaches the address
+    Execute(u16),
+    // The byte at the address has the value
+    MemoryEquals(u16, u8)
+}
+
+impl TraceTrigger {
+    // Parses "ADDR" or "ADDR=VALUE", in hexadecimal
+    pub fn parse(trigger: &str) -> Result<Self, String> {
+        match trigger.split_once('=') {
+            Some((address, value)) => Ok(TraceTrigger::MemoryEquals(parse_hex(address)?, parse_hex_byte(value)?)),
+            None => Ok(TraceTrigger::Execute(parse_hex(trigger)?))
+        }
+    }
+}
+
+// ===== TRACE LOGGER STRUCT =====
+
+pub struct TraceLogger {
+    pub p_cpu: Arc<Mutex<CPU>>,
+    pub p_bus: Arc<Mutex<Bus>>,
+    pub p_ppu: Arc<Mutex<PPU>>,
+
+    pub writer: BufWriter<Box<dyn Write + Send>>,
+    pub format: TraceFormat,
+
+    // Filters, the bounds are included
+    pub o_pc_range: Option<(u16, u16)>,
+    pub o_frame_range: Option<(u64, u64)>,
+    pub o_trigger: Option<TraceTrigger>,
+    pub triggered: bool,
+
+    // Extra columns : the v and t registers of the PPU, and the PRG ROM banks mapped at $8000 and $C000
+    pub ppu_registers: bool,
+    pub mapper_banks: bool,
+
+    // The trace is flushed at the beginning of each frame
+    pub last_frame: u64
+}
+
+impl TraceLogger {
+    pub fn new(writer: Box<dyn Write + Send>, p_cpu: Arc<Mutex<CPU>>, p_bus: Arc<Mutex<Bus>>, p_ppu: Arc<Mutex<PPU>>) -> Self {
+        TraceLogger {
+            p_cpu,
+            p_bus,
+            p_ppu,
+
+            writer: BufWriter::new(writer),
+            format: TraceFormat::Nintendulator,
+
+            o_pc_range: None,
+            o_frame_range: None,
+            o_trigger: None,
+            triggered: false,
+
+            ppu_registers: false,
+            mapper_banks: false,
+
+            last_frame: 0
+        }
+    }
+
+    // Writes the trace to a file, or to the standard output for "-"
+    pub fn create(path: &str, p_cpu: Arc<Mutex<CPU>>, p_bus: Arc<Mutex<Bus>>, p_ppu: Arc<Mutex<PPU>>) -> Result<Self, String> {
+        let writer: Box<dyn Write + Send> = match path {
+            "-" => Box::new(io::stdout()),
+            path => Box::new(File::create(Path::new(path)).map_err(|why| format!("Couldn't create {}: {}", path, why))?)
+        };
+        Ok(TraceLogger::new(writer, p_cpu, p_bus, p_ppu))
+    }
+
+    // ===== EMULATION HOOK =====
+
+    // Called before each instruction, logs it if it passes the filters
+    pub fn before_instruction(&mut self) {
+        let (frame, v, t): (u64, u16, u16) = {
+            let ppu = self.p_ppu.lock().unwrap();
+            (ppu.frame_count, ppu.ppu_bus.vram_address.address, ppu.ppu_bus.tmp_vram_address.address)
+        };
+        if frame != self.last_frame {
+            self.last_frame = frame;
+            self.flush();
+        }
+        if let Some((first, last)) = self.o_frame_range {
+            if frame < first || frame > last {
+                return;
+            }
+        }
+        let cpu = self.p_cpu.lock().unwrap();
+        if !self.triggered {
+            self.triggered = match self.o_trigger {
+                None => true,
+                Some(TraceTrigger::Execute(address)) => cpu.pc == address,
+                Some(TraceTrigger::MemoryEquals(address, value)) => cpu.read_only_bus(address) == value
+            };
+            if !self.triggered {
+                return;
+            }
+        }
+        if let Some((start, end)) = self.o_pc_range {
+            if cpu.pc < start || cpu.pc > end {
+                return;
+            }
+        }
+
+        let opcode: u8 = cpu.read_only_bus(cpu.pc);
+        let mut line: String = match self.format {
+            TraceFormat::Nintendulator => cpu.nintendulator_log(opcode),
+            TraceFormat::Fceux => fceux_log(&cpu, opcode),
+            TraceFormat::Mesen => mesen_log(&cpu, opcode, frame)
+        };
+        if self.ppu_registers {
+            line.push_str(&format!(" v:{:04X} t:{:04X}", v, t));
+        }
+        if self.mapper_banks {
+            let bus = self.p_bus.lock().unwrap();
+            let banks: Vec<String> = [0x8000, 0xC000].iter().map(|address| match bus.prg_rom_offset(*address) {
+                Some(offset) => format!("{:02X}", offset / BANK_SIZE),
+                None => String::from("--")
+            }).collect();
+            line.push_str(&format!(" PRG:{}", banks.join(",")));
+        }
+        let line: String = cpu.annotate_log(line);
+        drop(cpu);
+        if let Err(why) = writeln!(self.writer, "{}", line) {
+            error!("Couldn't write the trace: {}", why);
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if let Err(why) = self.writer.flush() {
+            error!("Couldn't write the trace: {}", why);
+        }
+    }
+}
+
+// ===== HELPER FUNCTIONS =====
+
+// Bytes of the instruction at the PC, separated by spaces
+fn instruction_bytes(cpu: &CPU, opcode: u8) -> (Vec<u8>, String) {
+    let parameters: Vec<u8> = (1..INSTRUCTIONS[opcode as usize].bytes as u16).map(|offset| cpu.read_only_bus(cpu.pc.wrapping_add(offset))).collect();
+    let bytes: String = std::iter::once(opcode).chain(parameters.iter().copied()).map(|byte| format!("{:02X}", byte)).collect::<Vec<String>>().join(" ");
+    (parameters, bytes)
+}
+
+// Flags in upper case when they are set, in lower case otherwise
+fn flags(p: u8) -> String {
+    "NVUBDIZC".chars().enumerate().map(|(bit, name)| {
+        match p & (0x80 >> bit) != 0 {
+            true => name,
+            false => name.to_ascii_lowercase()
+        }
+    }).collect()
+}
+
+fn fceux_log(cpu: &CPU, opcode: u8) -> String {
+    let (parameters, bytes): (Vec<u8>, String) = instruction_bytes(cpu, opcode);
+    format!("A:{:02X} X:{:02X} Y:{:02X} S:{:02X} P:{}  ${:04X}:{:<9} {}",
+        cpu.a, cpu.x, cpu.y, cpu.sp, flags(cpu.p), cpu.pc, bytes, cpu.dissassemble(cpu.pc, opcode, parameters).trim_end())
+}
+
+fn mesen_log(cpu: &CPU, opcode: u8, frame: u64) -> String {
+    let (parameters, bytes): (Vec<u8>, String) = instruction_bytes(cpu, opcode);
+    let (scanline, dot): (u16, u16) = cpu.bus.ppu_position();
+    format!("{:04X}  {:<9} {} A:{:02X} X:{:02X} Y:{:02X} S:{:02X} P:{} V:{:<3} H:{:<3} Fr:{} Cycle:{}",
+        cpu.pc, bytes, cpu.dissassemble(cpu.pc, opcode, parameters), cpu.a, cpu.x, cpu.y, cpu.sp, flags(cpu.p), scanline, dot, frame, cpu.total_clock)
+}
+
+// Parses an hexadecimal value, with an optional $ or 0x prefix
+fn parse_hex(value: &str) -> Result<u16, String> {
+    u16::from_str_radix(value.trim_start_matches('$').trim_start_matches("0x"), 16).map_err(|_| format!("Invalid value : {}", value))
+}
+
+fn parse_hex_byte(value: &str) -> Result<u8, String> {
+    u8::from_str_radix(value.trim_start_matches('$').trim_start_matches("0x"), 16).map_err(|_| format!("Invalid byte : {}", value))
+}
+
+// Parses "START-END", the values are hexadecimal
+pub fn parse_address_range(range: &str) -> Result<(u16, u16), String> {
+    let (start, end) = range.split_once('-').ok_or(format!("Invalid range : {}", range))?;
+    Ok((parse_hex(start)?, parse_hex(end)?))
+}
+
+// Parses "FIRST-LAST", the values are decimal
+pub fn parse_frame_range(range: &str) -> Result<(u64, u64), String> {
+    let (first, last) = range.split_once('-').ok_or(format!("Invalid range : {}", range))?;
+    let first: u64 = first.parse().map_err(|_| format!("Invalid frame : {}", first))?;
+    let last: u64 = last.parse().map_err(|_| format!("Invalid frame : {}", last))?;
+    Ok((first, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debugger::{Debugger, tests::create_debugger};
+
+    // Trace written to memory, read by the test
+    #[derive(Clone)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // Runs the instructions of the program with a trace logger, returns the lines of the trace
+    fn trace(program: &[u8], instructions: usize, setup: impl FnOnce(&mut TraceLogger)) -> Vec<String> {
+        let debugger: Debugger = create_debugger(program);
+        let buffer: SharedBuffer = SharedBuffer(Arc::new(Mutex::new(vec![])));
+        let mut trace_logger: TraceLogger = TraceLogger::new(Box::new(buffer.clone()), debugger.p_cpu.clone(), debugger.p_bus, debugger.p_ppu);
+        setup(&mut trace_logger);
+        for _ in 0..instructions {
+            trace_logger.before_instruction();
+            debugger.p_cpu.lock().unwrap().step();
+        }
+        trace_logger.flush();
+        let content: Vec<u8> = buffer.0.lock().unwrap().clone();
+        String::from_utf8(content).unwrap().lines().map(String::from).collect()
+    }
+
+    // LDA #$01 ; STA $10 ; NOP ; NOP
+    const PROGRAM: [u8;6] = [0xA9, 0x01, 0x85, 0x10, 0xEA, 0xEA];
+
+    #[test]
+    fn formats() {
+        let lines: Vec<String> = trace(&PROGRAM, 1, |trace_logger| trace_logger.format = TraceFormat::Fceux);
+        assert_eq!(lines, vec!["A:00 X:00 Y:00 S:FD P:nvUBdIzc  $0200:A9 01     LDA #$01"]);
+        let lines: Vec<String> = trace(&PROGRAM, 1, |trace_logger| {
+            trace_logger.format = TraceFormat::Mesen;
+            trace_logger.mapper_banks = true;
+        });
+        assert!(lines[0].starts_with("0200  A9 01     LDA #$01"));
+        assert!(lines[0].ends_with("Fr:0 Cycle:0 PRG:00,00"));
+        let lines: Vec<String> = trace(&PROGRAM, 1, |trace_logger| trace_logger.ppu_registers = true);
+        assert!(lines[0].starts_with("0200  A9 01     LDA #$01"));
+        assert!(lines[0].ends_with("v:0000 t:0000"));
+    }
+
+    #[test]
+    fn filters() {
+        let lines: Vec<String> = trace(&PROGRAM, 4, |trace_logger| trace_logger.o_pc_range = Some((0x0202, 0x0204)));
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("0202"));
+        let lines: Vec<String> = trace(&PROGRAM, 4, |trace_logger| trace_logger.o_trigger = Some(TraceTrigger::MemoryEquals(0x10, 0x01)));
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("0204"));
+        let lines: Vec<String> = trace(&PROGRAM, 4, |trace_logger| trace_logger.o_frame_range = Some((1, 2)));
+        assert!(lines.is_empty());
+        assert_eq!(TraceTrigger::parse("$C000"), Ok(TraceTrigger::Execute(0xC000)));
+        assert_eq!(TraceTrigger::parse("10=FF"), Ok(TraceTrigger::MemoryEquals(0x10, 0xFF)));
+        assert_eq!(TraceTrigger::parse("10=100"), Err(String::from("Invalid byte : 100")));
+        assert_eq!(parse_frame_range("10-20"), Ok((10, 20)));
+        assert!(parse_address_range("C000").is_err());
+    }
+}
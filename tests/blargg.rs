@@ -0,0 +1,97 @@
+// Runs the test ROMs using blargg's $6000 result protocol and regenerates the results table of TESTS.md
+// The ROMs are searched recursively in the directory given by NES_TEST_ROMS
+// The test is skipped when NES_TEST_ROMS isn't set
+// It fails if a ROM marked as passed in the previous table doesn't pass anymore
+
+use std::{env, fs, panic, path::{Path, PathBuf}};
+
+use nes_emulator::test_rom::{DEFAULT_TIMEOUT_FRAMES, TestRomResult, run_test_rom};
+
+const ROMS_VARIABLE: &str = "NES_TEST_ROMS";
+const TIMEOUT_VARIABLE: &str = "NES_TEST_TIMEOUT_FRAMES";
+
+const TABLE_START: &str = "<!-- $6000 results start -->";
+const TABLE_END: &str = "<!-- $6000 results end -->";
+
+#[test]
+fn blargg_test_roms() {
+    let roms_dir: PathBuf = match env::var(ROMS_VARIABLE) {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => {
+            eprintln!("{} is not set, skipping the test ROMs", ROMS_VARIABLE);
+            return;
+        }
+    };
+    let timeout_frames: u64 = env::var(TIMEOUT_VARIABLE).ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_TIMEOUT_FRAMES);
+
+    let mut roms: Vec<PathBuf> = Vec::new();
+    find_roms(&roms_dir, &mut roms);
+    roms.sort();
+
+    // Rows of the table : ROM, passed, message
+    let mut rows: Vec<(String, bool, String)> = Vec::new();
+    for rom in roms.iter() {
+        let name: String = rom.strip_prefix(&roms_dir).unwrap_or(rom).to_string_lossy().replace('\\', "/");
+        // Unsupported mappers and opcodes panic, this must not stop the other ROMs
+        let result: TestRomResult = match panic::catch_unwind(|| run_test_rom(rom, timeout_frames)) {
+            Ok(result) => result,
+            Err(_) => TestRomResult::Failed(0xFF, String::from("Crashed"))
+        };
+        println!("{} {} : {}", if result.is_passed() { "PASS" } else { "FAIL" }, name, result.message());
+        rows.push((name, result.is_passed(), result.message().to_string()));
+    }
+
+    let tests_path: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR")).join("TESTS.md");
+    let content: String = fs::read_to_string(&tests_path).expect("Couldn't read TESTS.md");
+    let previously_passed: Vec<String> = read_passed_roms(&content);
+    let regressions: Vec<&String> = rows.iter()
+        .filter(|(name, passed, _)| !passed && previously_passed.contains(name))
+        .map(|(name, _, _)| name)
+        .collect();
+
+    fs::write(&tests_path, replace_table(&content, &rows)).expect("Couldn't write TESTS.md");
+
+    assert!(regressions.is_empty(), "Test ROMs that passed before are now failing : {:?}", regressions);
+}
+
+fn find_roms(dir: &Path, roms: &mut Vec<PathBuf>) {
+    let entries = fs::read_dir(dir).unwrap_or_else(|why| panic!("Couldn't read {}: {}", dir.display(), why));
+    for entry in entries.flatten() {
+        let path: PathBuf = entry.path();
+        if path.is_dir() {
+            find_roms(&path, roms);
+        }
+        else if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("nes")) {
+            roms.push(path);
+        }
+    }
+}
+
+// Returns the ROMs marked as passed in the current table
+fn read_passed_roms(content: &str) -> Vec<String> {
+    content.lines()
+        .filter_map(|line| {
+            let cells: Vec<&str> = line.split(" | ").collect();
+            match cells.as_slice() {
+                [name, "PASS", ..] => Some(name.trim_start_matches("| ").to_string()),
+                _ => None
+            }
+        })
+        .collect()
+}
+
+fn replace_table(content: &str, rows: &[(String, bool, String)]) -> String {
+    let mut table: String = format!("{}\n| ROM | Result | Message |\n|---|---|---|\n", TABLE_START);
+    for (name, passed, message) in rows.iter() {
+        let message: String = message.split_whitespace().collect::<Vec<&str>>().join(" ").replace('|', "\\|");
+        table += &format!("| {} | {} | {} |\n", name, if *passed { "PASS" } else { "FAIL" }, message);
+    }
+    table += TABLE_END;
+
+    match (content.find(TABLE_START), content.find(TABLE_END)) {
+        (Some(start), Some(end)) => format!("{}{}{}", &content[..start], table, &content[end + TABLE_END.len()..]),
+        _ => format!("{}\n\n### $6000 protocol\n\n{}\n", content.trim_end(), table)
+    }
+}
@@ -0,0 +1,70 @@
+// Golden frame regression tests, listed in tests/golden_frames/manifest.txt
+// The ROMs are read next to the manifest, or from the directory given by NES_TEST_ROMS, the tests are skipped when it isn't set
+// On failure, the actual frame and a diff with the reference frame are written in the target directory
+// NES_BLESS=1 stores the current hashes and reference frames after an intentional change
+
+use std::{env, fs, path::{Path, PathBuf}};
+
+use nes_emulator::{golden_frame::{GoldenFrame, diff_frames, hash_frame, load_png, parse_manifest, update_manifest}, headless::save_png, ppu::palette::ARGBColor};
+
+const ROMS_VARIABLE: &str = "NES_TEST_ROMS";
+const BLESS_VARIABLE: &str = "NES_BLESS";
+
+#[test]
+fn golden_frames() {
+    let frames_dir: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden_frames");
+    let manifest_path: PathBuf = frames_dir.join("manifest.txt");
+    let content: String = fs::read_to_string(&manifest_path).expect("Couldn't read the golden frames manifest");
+    let mut golden_frames: Vec<GoldenFrame> = parse_manifest(&content).unwrap_or_else(|why| panic!("Invalid manifest: {}", why));
+    if golden_frames.is_empty() {
+        return;
+    }
+
+    let o_roms_dir: Option<PathBuf> = env::var(ROMS_VARIABLE).ok().map(PathBuf::from);
+    let bless: bool = env::var(BLESS_VARIABLE).is_ok_and(|value| value == "1");
+    let out_dir: PathBuf = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden_frames");
+    fs::create_dir_all(&out_dir).expect("Couldn't create the output directory");
+
+    let mut failures: Vec<String> = Vec::new();
+    for golden_frame in golden_frames.iter_mut() {
+        // The ROMs shipped with the tests are next to the manifest
+        let roms_dir: &Path = if frames_dir.join(&golden_frame.rom).is_file() {
+            &frames_dir
+        }
+        else if let Some(roms_dir) = o_roms_dir.as_ref() {
+            roms_dir
+        }
+        else {
+            eprintln!("{} is not set, skipping {}", ROMS_VARIABLE, golden_frame.name);
+            continue;
+        };
+        let frame_buffer: Vec<ARGBColor> = golden_frame.run(roms_dir, &frames_dir).unwrap_or_else(|why| panic!("{}: {}", golden_frame.name, why));
+        let hash: u64 = hash_frame(&frame_buffer);
+        let reference_path: PathBuf = frames_dir.join(format!("{}.png", golden_frame.name));
+
+        if bless {
+            golden_frame.o_hash = Some(hash);
+            save_png(&frame_buffer, &reference_path).unwrap();
+            continue;
+        }
+        if golden_frame.o_hash == Some(hash) {
+            continue;
+        }
+
+        let actual_path: PathBuf = out_dir.join(format!("{}_actual.png", golden_frame.name));
+        save_png(&frame_buffer, &actual_path).unwrap();
+        let expected: String = golden_frame.o_hash.map_or(String::from("no hash"), |expected| format!("{:016x}", expected));
+        let mut failure: String = format!("{}: expected {}, got {:016x}, frame saved to {}", golden_frame.name, expected, hash, actual_path.display());
+        if let Ok(reference) = load_png(&reference_path) {
+            let diff_path: PathBuf = out_dir.join(format!("{}_diff.png", golden_frame.name));
+            save_png(&diff_frames(&reference, &frame_buffer), &diff_path).unwrap();
+            failure += &format!(", diff saved to {}", diff_path.display());
+        }
+        failures.push(failure);
+    }
+
+    if bless {
+        fs::write(&manifest_path, update_manifest(&content, &golden_frames)).expect("Couldn't write the golden frames manifest");
+    }
+    assert!(failures.is_empty(), "Golden frames differ :\n{}", failures.join("\n"));
+}
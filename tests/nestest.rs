@@ -0,0 +1,86 @@
+// Runs nestest in automation mode and compares the CPU logs to a golden log from Nintendulator
+// NESTEST_ROM gives the path of nestest.nes, the golden log is the nestest.log file next to it
+// unless NESTEST_LOG is set. The test is skipped when NESTEST_ROM isn't set
+// It fails at the first field that differs : PC, registers, PPU scanline and dot, or CPU cycle
+
+use std::{env, fs, path::{Path, PathBuf}, sync::mpsc::{Receiver, Sender, channel}};
+
+use nes_emulator::{cartridge::cartridge::Cartridge, headless::Headless};
+
+const ROM_VARIABLE: &str = "NESTEST_ROM";
+const LOG_VARIABLE: &str = "NESTEST_LOG";
+
+// Start address of the automation mode
+const AUTOMATION_ADDRESS: u16 = 0xC000;
+// Each step runs one instruction
+const MAX_STEPS_PER_INSTRUCTION: u32 = 1;
+
+#[test]
+fn nestest_golden_log() {
+    let rom_path: PathBuf = match env::var(ROM_VARIABLE) {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => {
+            eprintln!("{} is not set, skipping nestest", ROM_VARIABLE);
+            return;
+        }
+    };
+    let log_path: PathBuf = env::var(LOG_VARIABLE).map(PathBuf::from).unwrap_or_else(|_| rom_path.with_extension("log"));
+    let golden_log: String = fs::read_to_string(&log_path).unwrap_or_else(|why| panic!("Couldn't read {}: {}", log_path.display(), why));
+
+    let mut headless: Headless = Headless::new(Cartridge::new(Path::new(&rom_path)), false);
+    // The headless runner has already reset the NES, the cycle count must start at 7
+    headless.nes.p_cpu.lock().unwrap().pc = AUTOMATION_ADDRESS;
+    let (sender, receiver): (Sender<String>, Receiver<String>) = channel();
+    headless.nes.p_cpu.lock().unwrap().o_log_sender = Some(sender);
+
+    for (index, expected) in golden_log.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let mut steps: u32 = 0;
+        let log: String = loop {
+            if let Ok(log) = receiver.try_recv() {
+                break log;
+            }
+            assert!(steps < MAX_STEPS_PER_INSTRUCTION, "Line {}: the CPU stopped executing instructions\nexpected: {}", index + 1, expected);
+            headless.nes.step();
+            steps += 1;
+        };
+        compare_lines(index + 1, expected, &log);
+    }
+}
+
+fn compare_lines(line_number: usize, expected: &str, log: &str) {
+    let expected_fields: Vec<(&str, String)> = parse_fields(expected);
+    let log_fields: Vec<(&str, String)> = parse_fields(log);
+    for ((name, expected_value), (_, value)) in expected_fields.iter().zip(log_fields.iter()) {
+        assert_eq!(
+            expected_value, value,
+            "Line {}: {} differs\nexpected: {}\ngot:      {}", line_number, name, expected, log
+        );
+    }
+}
+
+// Returns the compared fields of a log line, in the order they appear
+fn parse_fields(line: &str) -> Vec<(&'static str, String)> {
+    let mut fields: Vec<(&'static str, String)> = vec![("PC", line.get(0..4).unwrap_or("").to_string())];
+    for (name, key) in [("A", " A:"), ("X", " X:"), ("Y", " Y:"), ("P", " P:"), ("SP", " SP:")].iter() {
+        fields.push((name, read_value(line, key)));
+    }
+    // The PPU position is written "PPU:scanline,dot" with spaces used as padding
+    let mut ppu_values = line.find(" PPU:")
+        .map(|start| &line[start + 5..])
+        .and_then(|rest| rest.split(" CYC:").next())
+        .unwrap_or("")
+        .split(',')
+        .map(|value| value.trim().to_string());
+    fields.push(("PPU scanline", ppu_values.next().unwrap_or_default()));
+    fields.push(("PPU dot", ppu_values.next().unwrap_or_default()));
+    fields.push(("CYC", read_value(line, " CYC:")));
+    fields
+}
+
+// Reads the value following a key, until the next whitespace
+fn read_value(line: &str, key: &str) -> String {
+    match line.find(key) {
+        Some(start) => line[start + key.len()..].split_whitespace().next().unwrap_or("").to_string(),
+        None => String::new()
+    }
+}